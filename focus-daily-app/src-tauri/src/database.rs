@@ -1,6 +1,20 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use tauri::{AppHandle, Manager};
 use tauri_plugin_sql::{Builder, Migration, MigrationKind};
 
+/// SQL插件使用的数据库连接字符串
+pub const DATABASE_URL: &str = "sqlite:focus_daily.db";
+
+/// 数据库文件名（位于应用配置目录，与SQL插件一致）
+pub const DATABASE_FILE: &str = "focus_daily.db";
+
 /// 数据库初始化和迁移
+///
+/// 每个版本都同时提供 `Up` 和 `Down` 两个方向的迁移。
+/// 新增迁移中的时间戳统一使用 RFC 3339 格式（与前端 `toISOString()` 一致），
+/// 即 `strftime('%Y-%m-%dT%H:%M:%fZ', 'now')`。
+/// 已发布的迁移 SQL 不能修改（sqlx 会校验已执行迁移的校验和），只能通过追加新版本修正数据。
 pub fn get_migrations() -> Vec<Migration> {
     vec![
         Migration {
@@ -20,6 +34,14 @@ pub fn get_migrations() -> Vec<Migration> {
             "#,
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 1,
+            description: "删除focus_sessions表",
+            sql: r#"
+                DROP TABLE IF EXISTS focus_sessions;
+            "#,
+            kind: MigrationKind::Down,
+        },
         Migration {
             version: 2,
             description: "创建user_settings表",
@@ -41,6 +63,14 @@ pub fn get_migrations() -> Vec<Migration> {
             "#,
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 2,
+            description: "删除user_settings表",
+            sql: r#"
+                DROP TABLE IF EXISTS user_settings;
+            "#,
+            kind: MigrationKind::Down,
+        },
         Migration {
             version: 3,
             description: "创建audio_configs表",
@@ -58,6 +88,14 @@ pub fn get_migrations() -> Vec<Migration> {
             "#,
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 3,
+            description: "删除audio_configs表",
+            sql: r#"
+                DROP TABLE IF EXISTS audio_configs;
+            "#,
+            kind: MigrationKind::Down,
+        },
         Migration {
             version: 4,
             description: "插入默认用户设置",
@@ -74,12 +112,20 @@ pub fn get_migrations() -> Vec<Migration> {
             "#,
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 4,
+            description: "删除默认用户设置",
+            sql: r#"
+                DELETE FROM user_settings WHERE id = 'default_settings';
+            "#,
+            kind: MigrationKind::Down,
+        },
         Migration {
             version: 5,
             description: "插入默认音频配置",
             sql: r#"
                 INSERT INTO audio_configs (id, config_type, file_path, is_default, volume, enabled, created_at, updated_at)
-                VALUES 
+                VALUES
                     ('focus_start_default', 'FocusStart', NULL, 1, 1.0, 1, datetime('now'), datetime('now')),
                     ('focus_end_default', 'FocusEnd', NULL, 1, 1.0, 1, datetime('now'), datetime('now')),
                     ('long_break_start_default', 'LongBreakStart', NULL, 1, 1.0, 1, datetime('now'), datetime('now')),
@@ -88,11 +134,668 @@ pub fn get_migrations() -> Vec<Migration> {
             "#,
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 5,
+            description: "删除默认音频配置",
+            sql: r#"
+                DELETE FROM audio_configs WHERE id IN (
+                    'focus_start_default',
+                    'focus_end_default',
+                    'long_break_start_default',
+                    'long_break_end_default',
+                    'micro_break_start_default'
+                );
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 6,
+            description: "规范化默认数据时间戳为RFC 3339",
+            sql: r#"
+                UPDATE user_settings
+                SET created_at = strftime('%Y-%m-%dT%H:%M:%fZ', created_at),
+                    updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', updated_at)
+                WHERE created_at NOT LIKE '%T%' OR updated_at NOT LIKE '%T%';
+
+                UPDATE audio_configs
+                SET created_at = strftime('%Y-%m-%dT%H:%M:%fZ', created_at),
+                    updated_at = strftime('%Y-%m-%dT%H:%M:%fZ', updated_at)
+                WHERE created_at NOT LIKE '%T%' OR updated_at NOT LIKE '%T%';
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 6,
+            description: "恢复默认数据的datetime时间戳",
+            sql: r#"
+                UPDATE user_settings
+                SET created_at = datetime(created_at),
+                    updated_at = datetime(updated_at)
+                WHERE id = 'default_settings';
+
+                UPDATE audio_configs
+                SET created_at = datetime(created_at),
+                    updated_at = datetime(updated_at)
+                WHERE id IN (
+                    'focus_start_default',
+                    'focus_end_default',
+                    'long_break_start_default',
+                    'long_break_end_default',
+                    'micro_break_start_default'
+                );
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 7,
+            description: "创建tasks表并关联focus_sessions",
+            sql: r#"
+                CREATE TABLE tasks (
+                    id TEXT PRIMARY KEY,
+                    title TEXT NOT NULL,
+                    project TEXT,
+                    estimated_cycles INTEGER NOT NULL DEFAULT 1,
+                    status TEXT NOT NULL DEFAULT 'Todo',
+                    completed_at TEXT,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                );
+
+                ALTER TABLE focus_sessions ADD COLUMN task_id TEXT;
+                CREATE INDEX idx_focus_sessions_task_id ON focus_sessions(task_id);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 7,
+            description: "删除tasks表及focus_sessions关联",
+            sql: r#"
+                DROP INDEX IF EXISTS idx_focus_sessions_task_id;
+                ALTER TABLE focus_sessions DROP COLUMN task_id;
+                DROP TABLE IF EXISTS tasks;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 8,
+            description: "创建projects、tags和session_tags表",
+            sql: r#"
+                CREATE TABLE projects (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    parent_id TEXT,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                );
+                CREATE UNIQUE INDEX idx_projects_parent_name ON projects(COALESCE(parent_id, ''), name);
+
+                CREATE TABLE tags (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                    created_at TEXT NOT NULL
+                );
+
+                CREATE TABLE session_tags (
+                    session_id TEXT NOT NULL,
+                    tag_id TEXT NOT NULL,
+                    created_at TEXT NOT NULL,
+                    PRIMARY KEY (session_id, tag_id)
+                );
+                CREATE INDEX idx_session_tags_tag_id ON session_tags(tag_id);
+
+                ALTER TABLE focus_sessions ADD COLUMN project_id TEXT;
+                CREATE INDEX idx_focus_sessions_project_id ON focus_sessions(project_id);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 8,
+            description: "删除projects、tags和session_tags表",
+            sql: r#"
+                DROP INDEX IF EXISTS idx_focus_sessions_project_id;
+                ALTER TABLE focus_sessions DROP COLUMN project_id;
+                DROP TABLE IF EXISTS session_tags;
+                DROP TABLE IF EXISTS tags;
+                DROP TABLE IF EXISTS projects;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 9,
+            description: "创建session_ratings表并记录会话行为数据",
+            sql: r#"
+                CREATE TABLE session_ratings (
+                    session_id TEXT PRIMARY KEY,
+                    score INTEGER NOT NULL CHECK (score BETWEEN 1 AND 5),
+                    distractions INTEGER NOT NULL DEFAULT 0,
+                    note TEXT,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                );
+
+                ALTER TABLE focus_sessions ADD COLUMN pause_count INTEGER NOT NULL DEFAULT 0;
+                ALTER TABLE focus_sessions ADD COLUMN micro_breaks_triggered INTEGER NOT NULL DEFAULT 0;
+                ALTER TABLE focus_sessions ADD COLUMN micro_breaks_completed INTEGER NOT NULL DEFAULT 0;
+                ALTER TABLE focus_sessions ADD COLUMN micro_breaks_skipped INTEGER NOT NULL DEFAULT 0;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 9,
+            description: "删除session_ratings表及会话行为数据",
+            sql: r#"
+                ALTER TABLE focus_sessions DROP COLUMN micro_breaks_skipped;
+                ALTER TABLE focus_sessions DROP COLUMN micro_breaks_completed;
+                ALTER TABLE focus_sessions DROP COLUMN micro_breaks_triggered;
+                ALTER TABLE focus_sessions DROP COLUMN pause_count;
+                DROP TABLE IF EXISTS session_ratings;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 10,
+            description: "记录专注质量评分",
+            sql: r#"
+                ALTER TABLE focus_sessions ADD COLUMN focus_quality_score REAL;
+                ALTER TABLE focus_sessions ADD COLUMN micro_break_effectiveness REAL;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 10,
+            description: "删除专注质量评分",
+            sql: r#"
+                ALTER TABLE focus_sessions DROP COLUMN micro_break_effectiveness;
+                ALTER TABLE focus_sessions DROP COLUMN focus_quality_score;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 11,
+            description: "创建每日目标与目标历史表",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS daily_goals (
+                    weekday INTEGER PRIMARY KEY CHECK (weekday BETWEEN 0 AND 6),
+                    goal_type TEXT NOT NULL CHECK (goal_type IN ('Cycles', 'FocusMinutes')),
+                    target INTEGER NOT NULL CHECK (target > 0),
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                );
+
+                CREATE TABLE IF NOT EXISTS goal_history (
+                    date TEXT PRIMARY KEY,
+                    goal_type TEXT NOT NULL,
+                    target INTEGER NOT NULL,
+                    focus_seconds INTEGER NOT NULL DEFAULT 0,
+                    completed_cycles INTEGER NOT NULL DEFAULT 0,
+                    reached BOOLEAN NOT NULL DEFAULT 0,
+                    reached_at TEXT,
+                    updated_at TEXT NOT NULL
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 11,
+            description: "删除每日目标与目标历史表",
+            sql: r#"
+                DROP TABLE IF EXISTS goal_history;
+                DROP TABLE IF EXISTS daily_goals;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 12,
+            description: "创建目标连续达成状态表",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS goal_streaks (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    current_streak INTEGER NOT NULL DEFAULT 0,
+                    longest_streak INTEGER NOT NULL DEFAULT 0,
+                    last_reached_date TEXT,
+                    updated_at TEXT NOT NULL
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 12,
+            description: "删除目标连续达成状态表",
+            sql: r#"
+                DROP TABLE IF EXISTS goal_streaks;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 13,
+            description: "记录专注中断原因",
+            sql: r#"
+                CREATE TABLE IF NOT EXISTS session_interruptions (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    session_id TEXT NOT NULL,
+                    reason TEXT,
+                    elapsed_seconds INTEGER NOT NULL DEFAULT 0,
+                    created_at TEXT NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_session_interruptions_session_id ON session_interruptions(session_id);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 13,
+            description: "删除专注中断原因",
+            sql: r#"
+                DROP INDEX IF EXISTS idx_session_interruptions_session_id;
+                DROP TABLE IF EXISTS session_interruptions;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 14,
+            description: "记录长休息执行情况",
+            sql: r#"
+                ALTER TABLE focus_sessions ADD COLUMN long_break_status TEXT;
+                ALTER TABLE focus_sessions ADD COLUMN long_break_gap_seconds INTEGER;
+                ALTER TABLE focus_sessions ADD COLUMN long_break_session_id TEXT;
+                ALTER TABLE user_settings ADD COLUMN long_break_skip_warning_threshold INTEGER;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 14,
+            description: "删除长休息执行情况",
+            sql: r#"
+                ALTER TABLE user_settings DROP COLUMN long_break_skip_warning_threshold;
+                ALTER TABLE focus_sessions DROP COLUMN long_break_session_id;
+                ALTER TABLE focus_sessions DROP COLUMN long_break_gap_seconds;
+                ALTER TABLE focus_sessions DROP COLUMN long_break_status;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 15,
+            description: "支持心流模式加时",
+            sql: r#"
+                ALTER TABLE focus_sessions ADD COLUMN overtime_seconds INTEGER NOT NULL DEFAULT 0;
+                ALTER TABLE user_settings ADD COLUMN flow_mode_enabled BOOLEAN NOT NULL DEFAULT 0;
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 15,
+            description: "删除心流模式加时",
+            sql: r#"
+                ALTER TABLE user_settings DROP COLUMN flow_mode_enabled;
+                ALTER TABLE focus_sessions DROP COLUMN overtime_seconds;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 16,
+            description: "创建phase_sequences表",
+            sql: r#"
+                CREATE TABLE phase_sequences (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    steps TEXT NOT NULL,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 16,
+            description: "删除phase_sequences表",
+            sql: r#"
+                DROP TABLE IF EXISTS phase_sequences;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 17,
+            description: "添加微休息结束与阶段结束预警提示音",
+            sql: r#"
+                ALTER TABLE audio_configs ADD COLUMN lead_seconds INTEGER;
+
+                INSERT INTO audio_configs (id, config_type, file_path, is_default, volume, enabled, lead_seconds, created_at, updated_at)
+                VALUES
+                    ('micro_break_end_default', 'MicroBreakEnd', NULL, 1, 1.0, 1, NULL, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'), strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+                    ('focus_warning_default', 'FocusWarning', NULL, 1, 1.0, 1, 300, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'), strftime('%Y-%m-%dT%H:%M:%fZ', 'now')),
+                    ('long_break_warning_default', 'LongBreakWarning', NULL, 1, 1.0, 1, 60, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'), strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 17,
+            description: "删除微休息结束与阶段结束预警提示音",
+            sql: r#"
+                DELETE FROM audio_configs WHERE config_type IN ('MicroBreakEnd', 'FocusWarning', 'LongBreakWarning');
+                ALTER TABLE audio_configs DROP COLUMN lead_seconds;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 18,
+            description: "创建custom_sounds表",
+            sql: r#"
+                CREATE TABLE custom_sounds (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    file_path TEXT NOT NULL UNIQUE,
+                    format TEXT NOT NULL,
+                    size_bytes INTEGER NOT NULL,
+                    duration_ms INTEGER NOT NULL,
+                    loudness_db REAL NOT NULL,
+                    peak_db REAL NOT NULL,
+                    created_at TEXT NOT NULL
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 18,
+            description: "删除custom_sounds表",
+            sql: r#"
+                DROP TABLE IF EXISTS custom_sounds;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 19,
+            description: "创建ambient_settings表",
+            sql: r#"
+                CREATE TABLE ambient_settings (
+                    profile_id TEXT PRIMARY KEY,
+                    enabled BOOLEAN NOT NULL DEFAULT 0,
+                    source TEXT NOT NULL DEFAULT 'BrownNoise',
+                    file_path TEXT,
+                    volume REAL NOT NULL DEFAULT 0.3,
+                    micro_break_behavior TEXT NOT NULL DEFAULT 'Duck',
+                    duck_level REAL NOT NULL DEFAULT 0.3,
+                    fade_out_seconds INTEGER NOT NULL DEFAULT 10,
+                    updated_at TEXT NOT NULL
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 19,
+            description: "删除ambient_settings表",
+            sql: r#"
+                DROP TABLE IF EXISTS ambient_settings;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 20,
+            description: "创建免打扰时段表",
+            sql: r#"
+                CREATE TABLE quiet_hours_settings (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    enabled BOOLEAN NOT NULL DEFAULT 0,
+                    mode TEXT NOT NULL DEFAULT 'VisualOnly' CHECK (mode IN ('Mute', 'VisualOnly')),
+                    updated_at TEXT NOT NULL
+                );
+
+                CREATE TABLE quiet_hour_ranges (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    weekday INTEGER NOT NULL CHECK (weekday BETWEEN 0 AND 6),
+                    start_time TEXT NOT NULL,
+                    end_time TEXT NOT NULL
+                );
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 20,
+            description: "删除免打扰时段表",
+            sql: r#"
+                DROP TABLE IF EXISTS quiet_hour_ranges;
+                DROP TABLE IF EXISTS quiet_hours_settings;
+            "#,
+            kind: MigrationKind::Down,
+        },
+        Migration {
+            version: 21,
+            description: "创建event_log表",
+            sql: r#"
+                CREATE TABLE event_log (
+                    id TEXT PRIMARY KEY,
+                    event_name TEXT NOT NULL,
+                    category TEXT NOT NULL,
+                    priority TEXT NOT NULL,
+                    payload TEXT NOT NULL,
+                    created_at TEXT NOT NULL
+                );
+
+                CREATE INDEX idx_event_log_created_at ON event_log(created_at);
+                CREATE INDEX idx_event_log_category ON event_log(category, created_at);
+            "#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 21,
+            description: "删除event_log表",
+            sql: r#"
+                DROP INDEX IF EXISTS idx_event_log_category;
+                DROP INDEX IF EXISTS idx_event_log_created_at;
+                DROP TABLE IF EXISTS event_log;
+            "#,
+            kind: MigrationKind::Down,
+        },
     ]
 }
 
 /// 获取SQL插件构建器
 pub fn get_sql_plugin() -> Builder {
     Builder::default()
-        .add_migrations("sqlite:focus_daily.db", get_migrations())
-} 
\ No newline at end of file
+        .add_migrations(DATABASE_URL, get_migrations())
+}
+
+/// 连接SQL插件所管理的同一个数据库文件
+///
+/// 迁移由SQL插件在启动时执行，这里只负责建立后端使用的连接池。
+pub async fn connect_database(app_handle: &AppHandle) -> Result<SqlitePool, String> {
+    let app_dir = app_handle
+        .path()
+        .app_config_dir()
+        .map_err(|e| format!("无法获取应用配置目录: {}", e))?;
+    std::fs::create_dir_all(&app_dir)
+        .map_err(|e| format!("无法创建应用配置目录: {}", e))?;
+
+    let options = SqliteConnectOptions::new()
+        .filename(app_dir.join(DATABASE_FILE))
+        .create_if_missing(true);
+
+    SqlitePool::connect_with(options)
+        .await
+        .map_err(|e| format!("数据库连接失败: {}", e))
+}
+
+/// 格式化为数据库统一使用的时间戳（RFC 3339，毫秒精度，`Z`后缀）
+pub fn format_timestamp(time: &DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// 当前时间的数据库时间戳
+pub fn now_timestamp() -> String {
+    format_timestamp(&Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+    use sqlx::sqlite::SqlitePool;
+
+    use crate::test_support::{build_migrator, temp_pool};
+
+    /// 全部迁移执行后期望的表结构
+    const EXPECTED_SCHEMA: &[(&str, &[&str])] = &[
+        ("focus_sessions", &[
+            "id", "start_time", "end_time", "duration_seconds", "session_type",
+            "completed", "created_at", "updated_at", "task_id", "project_id",
+            "pause_count", "micro_breaks_triggered", "micro_breaks_completed",
+            "micro_breaks_skipped", "focus_quality_score", "micro_break_effectiveness",
+            "long_break_status", "long_break_gap_seconds", "long_break_session_id",
+            "overtime_seconds",
+        ]),
+        ("user_settings", &[
+            "id", "theme", "language", "auto_start", "focus_duration_minutes",
+            "long_break_duration_minutes", "micro_break_min_interval_minutes",
+            "micro_break_max_interval_minutes", "micro_break_duration_seconds",
+            "notifications_enabled", "created_at", "updated_at",
+            "long_break_skip_warning_threshold", "flow_mode_enabled",
+        ]),
+        ("audio_configs", &[
+            "id", "config_type", "file_path", "is_default", "volume", "enabled",
+            "created_at", "updated_at", "lead_seconds",
+        ]),
+        ("tasks", &[
+            "id", "title", "project", "estimated_cycles", "status", "completed_at",
+            "created_at", "updated_at",
+        ]),
+        ("projects", &["id", "name", "parent_id", "created_at", "updated_at"]),
+        ("tags", &["id", "name", "created_at"]),
+        ("session_tags", &["session_id", "tag_id", "created_at"]),
+        ("session_ratings", &[
+            "session_id", "score", "distractions", "note", "created_at", "updated_at",
+        ]),
+        ("daily_goals", &["weekday", "goal_type", "target", "created_at", "updated_at"]),
+        ("goal_history", &[
+            "date", "goal_type", "target", "focus_seconds", "completed_cycles", "reached",
+            "reached_at", "updated_at",
+        ]),
+        ("goal_streaks", &[
+            "id", "current_streak", "longest_streak", "last_reached_date", "updated_at",
+        ]),
+        ("session_interruptions", &[
+            "id", "session_id", "reason", "elapsed_seconds", "created_at",
+        ]),
+        ("phase_sequences", &["id", "name", "steps", "created_at", "updated_at"]),
+        ("custom_sounds", &[
+            "id", "name", "file_path", "format", "size_bytes", "duration_ms", "loudness_db",
+            "peak_db", "created_at",
+        ]),
+        ("ambient_settings", &[
+            "profile_id", "enabled", "source", "file_path", "volume", "micro_break_behavior",
+            "duck_level", "fade_out_seconds", "updated_at",
+        ]),
+        ("quiet_hours_settings", &["id", "enabled", "mode", "updated_at"]),
+        ("quiet_hour_ranges", &["id", "weekday", "start_time", "end_time"]),
+        ("event_log", &["id", "event_name", "category", "priority", "payload", "created_at"]),
+    ];
+
+    /// 获取应用创建的表（排除sqlite内部表和迁移记录表）
+    async fn app_tables(pool: &SqlitePool) -> BTreeSet<String> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT name FROM sqlite_master
+             WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name != '_sqlx_migrations'",
+        )
+        .fetch_all(pool)
+        .await
+        .expect("查询表列表失败")
+        .into_iter()
+        .collect()
+    }
+
+    /// 获取指定表的列名
+    async fn table_columns(pool: &SqlitePool, table: &str) -> Vec<String> {
+        sqlx::query_scalar::<_, String>("SELECT name FROM pragma_table_info(?) ORDER BY cid")
+            .bind(table)
+            .fetch_all(pool)
+            .await
+            .expect("查询表结构失败")
+    }
+
+    async fn assert_full_schema(pool: &SqlitePool) {
+        let expected_tables: BTreeSet<String> = EXPECTED_SCHEMA
+            .iter()
+            .map(|(table, _)| table.to_string())
+            .collect();
+        assert_eq!(app_tables(pool).await, expected_tables);
+
+        for (table, columns) in EXPECTED_SCHEMA {
+            assert_eq!(table_columns(pool, table).await, *columns, "表 {} 结构不符", table);
+        }
+    }
+
+    #[test]
+    fn every_up_migration_has_a_down() {
+        let migrations = get_migrations();
+        let versions_of = |kind: fn(&MigrationKind) -> bool| -> Vec<i64> {
+            migrations.iter().filter(|m| kind(&m.kind)).map(|m| m.version).collect()
+        };
+
+        let up = versions_of(|k| matches!(k, MigrationKind::Up));
+        let down = versions_of(|k| matches!(k, MigrationKind::Down));
+
+        assert!(!up.is_empty());
+        assert_eq!(up, down);
+        assert!(up.windows(2).all(|w| w[0] < w[1]), "迁移版本必须严格递增");
+    }
+
+    #[tokio::test]
+    async fn full_chain_applies_and_reverts() {
+        let (_dir, pool) = temp_pool().await;
+        let migrator = build_migrator();
+
+        migrator.run(&pool).await.expect("执行迁移失败");
+        assert_full_schema(&pool).await;
+
+        migrator.undo(&pool, 0).await.expect("回滚迁移失败");
+        assert!(app_tables(&pool).await.is_empty());
+
+        // 回滚后可以重新完整执行
+        migrator.run(&pool).await.expect("重新执行迁移失败");
+        assert_full_schema(&pool).await;
+    }
+
+    #[tokio::test]
+    async fn each_step_reverts_cleanly() {
+        let (_dir, pool) = temp_pool().await;
+        let migrator = build_migrator();
+        migrator.run(&pool).await.expect("执行迁移失败");
+
+        let mut versions: Vec<i64> = migrator.iter().map(|m| m.version).collect();
+        versions.dedup();
+
+        // 逐个版本回滚，每一步之后再完整执行一次，确认Down与Up严格对称
+        for target in versions.iter().rev().skip(1).chain(std::iter::once(&0)) {
+            migrator.undo(&pool, *target).await.expect("逐步回滚失败");
+            migrator.run(&pool).await.expect("回滚后重新执行失败");
+            assert_full_schema(&pool).await;
+            migrator.undo(&pool, *target).await.expect("逐步回滚失败");
+        }
+
+        assert!(app_tables(&pool).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn seeded_timestamps_are_rfc3339() {
+        let (_dir, pool) = temp_pool().await;
+        build_migrator().run(&pool).await.expect("执行迁移失败");
+
+        let timestamps: Vec<(String, String)> = sqlx::query_as(
+            "SELECT created_at, updated_at FROM user_settings
+             UNION ALL
+             SELECT created_at, updated_at FROM audio_configs",
+        )
+        .fetch_all(&pool)
+        .await
+        .expect("查询时间戳失败");
+
+        assert!(!timestamps.is_empty());
+        for (created_at, updated_at) in timestamps {
+            for value in [created_at, updated_at] {
+                assert!(
+                    chrono::DateTime::parse_from_rfc3339(&value).is_ok(),
+                    "时间戳不是RFC 3339格式: {}",
+                    value
+                );
+            }
+        }
+    }
+}
@@ -4,6 +4,8 @@
 mod models;
 mod database;
 mod services;
+#[cfg(test)]
+mod test_support;
 
 // 导入必要的模块
 use database::get_sql_plugin;
@@ -11,13 +13,30 @@ use services::{
     TimerManagerState,
     init_timer_manager, get_timer_state, get_cycle_state,
     start_focus_session, start_long_break_session, start_micro_break_session,
-    pause_timer, resume_timer, reset_timer, skip_micro_break,
+    pause_timer, resume_timer, reset_timer, skip_micro_break, skip_long_break,
+    finish_focus_session, extend_current_session, shorten_current_session,
+    start_open_focus_session, stop_open_focus_session,
+    start_cycle_plan, cancel_cycle_plan, get_cycle_plan,
     get_today_stats, update_timer_settings,
     EventManagerState, PerformanceMonitorState,
     get_event_stats, get_event_history, get_event_queue_status, cleanup_expired_events,
+    get_dead_letter_events, clear_dead_letter_events,
     get_performance_report, get_system_health, get_active_alerts, resolve_performance_alert,
     play_audio_event, update_audio_config, get_detailed_performance_stats,
     reset_performance_data, trigger_system_diagnostics,
+    DatabaseState, create_task, list_tasks, complete_task,
+    create_project, list_projects, list_tags, set_session_labels, get_session_labels,
+    get_tag_stats, get_project_stats,
+    rate_session, get_session_rating, get_rating_stats,
+    set_daily_goal, remove_daily_goal, list_daily_goals, get_range_stats, get_streak_stats,
+    generate_weekly_report, get_focus_heatmap, get_long_break_stats,
+    create_phase_sequence, update_phase_sequence, delete_phase_sequence,
+    list_phase_sequences, start_phase_sequence,
+    list_audio_cue_configs, update_audio_cue_config, AudioServiceState,
+    import_custom_sound, list_custom_sounds, preview_custom_sound, delete_custom_sound,
+    assign_custom_sound,
+    get_ambient_settings, update_ambient_settings, set_ambient_profile,
+    get_quiet_hours, update_quiet_hours, get_quiet_mode,
 };
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -28,6 +47,8 @@ pub fn run() {
     let timer_manager_state: TimerManagerState = Arc::new(RwLock::new(None));
     let event_manager_state: EventManagerState = Arc::new(RwLock::new(None));
     let performance_monitor_state: PerformanceMonitorState = Arc::new(RwLock::new(None));
+    let database_state: DatabaseState = Arc::new(RwLock::new(None));
+    let audio_service_state: AudioServiceState = Arc::new(RwLock::new(None));
 
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
@@ -36,6 +57,8 @@ pub fn run() {
         .manage(timer_manager_state)
         .manage(event_manager_state)
         .manage(performance_monitor_state)
+        .manage(database_state)
+        .manage(audio_service_state)
         .invoke_handler(tauri::generate_handler![
             // 原有的定时器命令
             init_timer_manager,
@@ -48,6 +71,15 @@ pub fn run() {
             resume_timer,
             reset_timer,
             skip_micro_break,
+            skip_long_break,
+            finish_focus_session,
+            extend_current_session,
+            shorten_current_session,
+            start_open_focus_session,
+            stop_open_focus_session,
+            start_cycle_plan,
+            cancel_cycle_plan,
+            get_cycle_plan,
             get_today_stats,
             update_timer_settings,
             
@@ -56,6 +88,8 @@ pub fn run() {
             get_event_history,
             get_event_queue_status,
             cleanup_expired_events,
+            get_dead_letter_events,
+            clear_dead_letter_events,
             
             // Day 4 新增的性能监控命令
             get_performance_report,
@@ -69,6 +103,63 @@ pub fn run() {
             // Day 4 新增的音频事件命令 (为Week 4准备)
             play_audio_event,
             update_audio_config,
+
+            // 任务管理命令
+            create_task,
+            list_tasks,
+            complete_task,
+
+            // 标签与项目命令
+            create_project,
+            list_projects,
+            list_tags,
+            set_session_labels,
+            get_session_labels,
+            get_tag_stats,
+            get_project_stats,
+
+            // 会话评价命令
+            rate_session,
+            get_session_rating,
+            get_rating_stats,
+
+            // 每日目标命令
+            set_daily_goal,
+            remove_daily_goal,
+            list_daily_goals,
+            get_range_stats,
+            get_streak_stats,
+
+            // 回顾报告命令
+            generate_weekly_report,
+            get_focus_heatmap,
+            get_long_break_stats,
+
+            // 阶段序列命令
+            create_phase_sequence,
+            update_phase_sequence,
+            delete_phase_sequence,
+            list_phase_sequences,
+            start_phase_sequence,
+
+            // 提示音配置命令
+            list_audio_cue_configs,
+            update_audio_cue_config,
+
+            // 自定义音效库命令
+            import_custom_sound,
+            list_custom_sounds,
+            preview_custom_sound,
+            delete_custom_sound,
+            assign_custom_sound,
+            // 专注背景音命令
+            get_ambient_settings,
+            update_ambient_settings,
+            set_ambient_profile,
+            // 免打扰时段命令
+            get_quiet_hours,
+            update_quiet_hours,
+            get_quiet_mode,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
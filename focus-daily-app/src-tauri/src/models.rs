@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Timelike, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -11,6 +11,16 @@ pub struct FocusSession {
     pub duration_seconds: i32,
     pub session_type: SessionType,
     pub completed: bool,
+    pub task_id: Option<String>,
+    pub project_id: Option<String>,
+    pub pause_count: i32,
+    pub micro_breaks_triggered: i32,
+    pub micro_breaks_completed: i32,
+    pub micro_breaks_skipped: i32,
+    pub focus_quality_score: Option<f32>,
+    pub micro_break_effectiveness: Option<f32>,
+    /// 心流模式下超出计划时长的加时（秒）
+    pub overtime_seconds: i32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -21,6 +31,25 @@ pub enum SessionType {
     Focus,     // 90分钟专注会话
     LongBreak, // 20分钟长休息
     MicroBreak, // 3-5分钟微休息
+    OpenFocus, // 不限时长的正计时专注
+    WarmUp,    // 阶段序列中的热身步骤
+    Review,    // 阶段序列中的复盘步骤
+    SequenceBreak, // 阶段序列中的休息步骤（不计入长休息执行情况）
+}
+
+impl SessionType {
+    /// 数据库中存储的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionType::Focus => "Focus",
+            SessionType::LongBreak => "LongBreak",
+            SessionType::MicroBreak => "MicroBreak",
+            SessionType::OpenFocus => "OpenFocus",
+            SessionType::WarmUp => "WarmUp",
+            SessionType::Review => "Review",
+            SessionType::SequenceBreak => "SequenceBreak",
+        }
+    }
 }
 
 /// 创建新专注会话的输入
@@ -28,6 +57,67 @@ pub enum SessionType {
 pub struct CreateFocusSession {
     pub session_type: SessionType,
     pub duration_seconds: i32,
+    pub task_id: Option<String>,
+}
+
+/// 任务（专注会话的工作意图）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Task {
+    pub id: String,
+    pub title: String,
+    pub project: Option<String>,
+    pub estimated_cycles: i32,
+    pub status: TaskStatus,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 任务状态枚举
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum TaskStatus {
+    Todo,       // 未开始
+    InProgress, // 进行中
+    Completed,  // 已完成
+}
+
+impl TaskStatus {
+    /// 数据库中存储的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Todo => "Todo",
+            TaskStatus::InProgress => "InProgress",
+            TaskStatus::Completed => "Completed",
+        }
+    }
+
+    /// 从数据库名称解析
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "Todo" => Some(TaskStatus::Todo),
+            "InProgress" => Some(TaskStatus::InProgress),
+            "Completed" => Some(TaskStatus::Completed),
+            _ => None,
+        }
+    }
+}
+
+/// 创建任务的输入
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTask {
+    pub title: String,
+    pub project: Option<String>,
+    pub estimated_cycles: i32,
+}
+
+/// 任务及其累计专注数据
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskWithStats {
+    pub task: Task,
+    /// 已完成专注会话的累计时长（秒）
+    pub focus_seconds: i64,
+    /// 已完成的专注周期数
+    pub completed_cycles: i64,
 }
 
 /// 用户设置
@@ -43,6 +133,12 @@ pub struct UserSettings {
     pub micro_break_max_interval_minutes: i32,
     pub micro_break_duration_seconds: i32,
     pub notifications_enabled: bool,
+    /// 连续多少次未休长休息后发出提醒（为空时不提醒）
+    #[serde(default)]
+    pub long_break_skip_warning_threshold: Option<u32>,
+    /// 心流模式：专注到点后继续正计时，直到手动结束
+    #[serde(default)]
+    pub flow_mode_enabled: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -55,6 +151,27 @@ pub enum Theme {
     System,
 }
 
+impl Theme {
+    /// 数据库中存储的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Theme::Light => "Light",
+            Theme::Dark => "Dark",
+            Theme::System => "System",
+        }
+    }
+
+    /// 从数据库名称解析
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "Light" => Some(Theme::Light),
+            "Dark" => Some(Theme::Dark),
+            "System" => Some(Theme::System),
+            _ => None,
+        }
+    }
+}
+
 /// 音频配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioConfig {
@@ -64,18 +181,63 @@ pub struct AudioConfig {
     pub is_default: bool,
     pub volume: f32,
     pub enabled: bool,
+    /// 预警提示音在阶段结束前多少秒播放（仅预警类型）
+    pub lead_seconds: Option<u32>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 /// 音频类型枚举
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AudioType {
-    FocusStart,      // 专注开始
-    FocusEnd,        // 专注结束
-    LongBreakStart,  // 长休息开始
-    LongBreakEnd,    // 长休息结束
-    MicroBreakStart, // 微休息开始
+    FocusStart,       // 专注开始
+    FocusEnd,         // 专注结束
+    LongBreakStart,   // 长休息开始
+    LongBreakEnd,     // 长休息结束
+    MicroBreakStart,  // 微休息开始
+    MicroBreakEnd,    // 微休息结束
+    FocusWarning,     // 专注即将结束
+    LongBreakWarning, // 长休息即将结束
+}
+
+impl AudioType {
+    /// 数据库中存储的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AudioType::FocusStart => "FocusStart",
+            AudioType::FocusEnd => "FocusEnd",
+            AudioType::LongBreakStart => "LongBreakStart",
+            AudioType::LongBreakEnd => "LongBreakEnd",
+            AudioType::MicroBreakStart => "MicroBreakStart",
+            AudioType::MicroBreakEnd => "MicroBreakEnd",
+            AudioType::FocusWarning => "FocusWarning",
+            AudioType::LongBreakWarning => "LongBreakWarning",
+        }
+    }
+
+    /// 预警提示音默认在阶段结束前多少秒播放（非预警类型为None）
+    pub fn default_lead_seconds(&self) -> Option<u32> {
+        match self {
+            AudioType::FocusWarning => Some(300),
+            AudioType::LongBreakWarning => Some(60),
+            _ => None,
+        }
+    }
+
+    /// 从数据库名称解析
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "FocusStart" => Some(AudioType::FocusStart),
+            "FocusEnd" => Some(AudioType::FocusEnd),
+            "LongBreakStart" => Some(AudioType::LongBreakStart),
+            "LongBreakEnd" => Some(AudioType::LongBreakEnd),
+            "MicroBreakStart" => Some(AudioType::MicroBreakStart),
+            "MicroBreakEnd" => Some(AudioType::MicroBreakEnd),
+            "FocusWarning" => Some(AudioType::FocusWarning),
+            "LongBreakWarning" => Some(AudioType::LongBreakWarning),
+            _ => None,
+        }
+    }
 }
 
 /// 创建音频配置的输入
@@ -85,6 +247,572 @@ pub struct CreateAudioConfig {
     pub file_path: Option<String>,
     pub volume: f32,
     pub enabled: bool,
+    #[serde(default)]
+    pub lead_seconds: Option<u32>,
+}
+
+/// 导入到音效库的自定义提示音
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomSound {
+    pub id: String,
+    pub name: String,
+    pub file_path: String,
+    pub format: String,
+    pub size_bytes: u64,
+    pub duration_ms: u64,
+    /// 平均响度（RMS，dBFS）
+    pub loudness_db: f32,
+    /// 峰值电平（dBFS）
+    pub peak_db: f32,
+    /// 引用该音效的提示音配置数量
+    pub reference_count: u32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 专注背景音的声源
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AmbientSource {
+    WhiteNoise, // 白噪音
+    PinkNoise,  // 粉红噪音
+    BrownNoise, // 棕噪音
+    File,       // 音效库中的用户音频文件
+}
+
+impl AmbientSource {
+    /// 数据库中存储的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AmbientSource::WhiteNoise => "WhiteNoise",
+            AmbientSource::PinkNoise => "PinkNoise",
+            AmbientSource::BrownNoise => "BrownNoise",
+            AmbientSource::File => "File",
+        }
+    }
+
+    /// 从数据库名称解析
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "WhiteNoise" => Some(AmbientSource::WhiteNoise),
+            "PinkNoise" => Some(AmbientSource::PinkNoise),
+            "BrownNoise" => Some(AmbientSource::BrownNoise),
+            "File" => Some(AmbientSource::File),
+            _ => None,
+        }
+    }
+}
+
+/// 微休息期间背景音的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AmbientBreakBehavior {
+    Duck,  // 降低音量
+    Pause, // 暂停
+}
+
+impl AmbientBreakBehavior {
+    /// 数据库中存储的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AmbientBreakBehavior::Duck => "Duck",
+            AmbientBreakBehavior::Pause => "Pause",
+        }
+    }
+
+    /// 从数据库名称解析
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "Duck" => Some(AmbientBreakBehavior::Duck),
+            "Pause" => Some(AmbientBreakBehavior::Pause),
+            _ => None,
+        }
+    }
+}
+
+/// 专注背景音设置（按用户设置档案保存）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmbientSettings {
+    pub profile_id: String,
+    pub enabled: bool,
+    pub source: AmbientSource,
+    /// 声源为File时播放的文件
+    pub file_path: Option<String>,
+    /// 背景音独立音量（0.0–1.0）
+    pub volume: f32,
+    pub micro_break_behavior: AmbientBreakBehavior,
+    /// 降低音量时相对于原音量的比例
+    pub duck_level: f32,
+    /// 专注结束前淡出的秒数
+    pub fade_out_seconds: u32,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl AmbientSettings {
+    /// 未保存过设置的档案使用的默认值（默认关闭）
+    pub fn defaults_for(profile_id: &str) -> Self {
+        Self {
+            profile_id: profile_id.to_string(),
+            enabled: false,
+            source: AmbientSource::BrownNoise,
+            file_path: None,
+            volume: 0.3,
+            micro_break_behavior: AmbientBreakBehavior::Duck,
+            duck_level: 0.3,
+            fade_out_seconds: 10,
+            updated_at: None,
+        }
+    }
+}
+
+/// 更新专注背景音设置的输入
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateAmbientSettings {
+    pub enabled: Option<bool>,
+    pub source: Option<AmbientSource>,
+    pub file_path: Option<String>,
+    pub volume: Option<f32>,
+    pub micro_break_behavior: Option<AmbientBreakBehavior>,
+    pub duck_level: Option<f32>,
+    pub fade_out_seconds: Option<u32>,
+}
+
+/// 免打扰时段内提示音的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuietHoursMode {
+    Mute,       // 静音，不发出任何提示
+    VisualOnly, // 不播放声音，改为界面通知
+}
+
+impl QuietHoursMode {
+    /// 数据库中存储的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuietHoursMode::Mute => "Mute",
+            QuietHoursMode::VisualOnly => "VisualOnly",
+        }
+    }
+
+    /// 从数据库名称解析
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "Mute" => Some(QuietHoursMode::Mute),
+            "VisualOnly" => Some(QuietHoursMode::VisualOnly),
+            _ => None,
+        }
+    }
+}
+
+/// 免打扰时间段（weekday：0为周一，6为周日；时间为本地时间HH:MM）
+///
+/// 结束时间早于开始时间的时间段跨过午夜，延续到次日。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuietHourRange {
+    pub weekday: u32,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+impl QuietHourRange {
+    /// 本地时间是否落在该时间段内
+    pub fn contains(&self, at: NaiveDateTime) -> bool {
+        let (Some(start), Some(end)) = (parse_clock_minutes(&self.start_time), parse_clock_minutes(&self.end_time)) else {
+            return false;
+        };
+        let weekday = at.weekday().num_days_from_monday();
+        let minute = at.hour() * 60 + at.minute();
+
+        if start < end {
+            weekday == self.weekday && (start..end).contains(&minute)
+        } else {
+            (weekday == self.weekday && minute >= start) || (weekday == (self.weekday + 1) % 7 && minute < end)
+        }
+    }
+}
+
+/// 解析HH:MM格式的时间，返回当天的分钟数
+pub fn parse_clock_minutes(value: &str) -> Option<u32> {
+    let (hour, minute) = value.trim().split_once(':')?;
+    let (hour, minute): (u32, u32) = (hour.parse().ok()?, minute.parse().ok()?);
+    (hour < 24 && minute < 60).then_some(hour * 60 + minute)
+}
+
+/// 免打扰设置：时段内提示音静音或改为界面通知，计时照常进行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub enabled: bool,
+    pub mode: QuietHoursMode,
+    pub ranges: Vec<QuietHourRange>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: QuietHoursMode::VisualOnly,
+            ranges: Vec::new(),
+            updated_at: None,
+        }
+    }
+}
+
+impl QuietHours {
+    /// 本地时间处于免打扰时段时返回处理方式
+    pub fn mode_at(&self, at: NaiveDateTime) -> Option<QuietHoursMode> {
+        (self.enabled && self.ranges.iter().any(|range| range.contains(at))).then_some(self.mode)
+    }
+}
+
+/// 更新免打扰设置的输入（ranges整体替换）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateQuietHours {
+    pub enabled: Option<bool>,
+    pub mode: Option<QuietHoursMode>,
+    pub ranges: Option<Vec<QuietHourRange>>,
+}
+
+/// 项目（支持父子层级）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Project {
+    pub id: String,
+    pub name: String,
+    pub parent_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 标签
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 会话的项目和标签
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionLabels {
+    pub session_id: String,
+    pub project_id: Option<String>,
+    pub tags: Vec<Tag>,
+}
+
+/// 按标签或项目汇总的专注统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelFocusStats {
+    pub id: String,
+    pub name: String,
+    /// 完整路径（项目为"父项目/子项目"，标签即名称）
+    pub path: String,
+    /// 已完成专注会话的累计时长（秒），项目包含子项目
+    pub focus_seconds: i64,
+    /// 已完成的专注周期数，项目包含子项目
+    pub completed_cycles: i64,
+}
+
+/// 专注会话的自我评价
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRating {
+    pub session_id: String,
+    /// 专注质量自评（1-5）
+    pub score: i32,
+    /// 自我报告的分心次数
+    pub distractions: i32,
+    pub note: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 某一评分下的会话行为平均值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RatingBucket {
+    pub score: i32,
+    pub sessions: i64,
+    /// 平均微休息完成率（无微休息的会话不计入）
+    pub average_micro_break_completion: Option<f64>,
+    /// 平均暂停（中断）次数
+    pub average_interruptions: f64,
+    /// 平均自报分心次数
+    pub average_distractions: f64,
+}
+
+/// 评分与会话行为的相关性统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RatingCorrelationStats {
+    pub rated_sessions: i64,
+    pub average_score: Option<f64>,
+    /// 评分与微休息完成率的皮尔逊相关系数
+    pub score_vs_micro_break_completion: Option<f64>,
+    /// 评分与暂停（中断）次数的皮尔逊相关系数
+    pub score_vs_interruptions: Option<f64>,
+    /// 评分与自报分心次数的皮尔逊相关系数
+    pub score_vs_distractions: Option<f64>,
+    pub buckets: Vec<RatingBucket>,
+}
+
+/// 每日目标类型
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum GoalType {
+    Cycles,       // 完成的专注周期数
+    FocusMinutes, // 累计专注分钟数
+}
+
+impl GoalType {
+    /// 数据库中存储的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GoalType::Cycles => "Cycles",
+            GoalType::FocusMinutes => "FocusMinutes",
+        }
+    }
+
+    /// 从数据库名称解析
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "Cycles" => Some(GoalType::Cycles),
+            "FocusMinutes" => Some(GoalType::FocusMinutes),
+            _ => None,
+        }
+    }
+}
+
+/// 按星期配置的每日目标（weekday：0为周一，6为周日）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyGoal {
+    pub weekday: u32,
+    pub goal_type: GoalType,
+    pub target: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 某一天（本地日期）的目标达成情况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyGoalProgress {
+    pub date: NaiveDate,
+    /// 当天适用的目标（未设置目标时为空）
+    pub goal_type: Option<GoalType>,
+    pub target: Option<i32>,
+    pub focus_seconds: i64,
+    pub completed_cycles: i64,
+    /// 完成比例（可超过1，未设置目标时为空）
+    pub progress: Option<f64>,
+    pub reached: bool,
+    pub reached_at: Option<DateTime<Utc>>,
+}
+
+/// 日期范围内的专注与目标达成统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangeStats {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub total_focus_seconds: i64,
+    pub total_completed_cycles: i64,
+    /// 设置了目标的天数
+    pub days_with_goal: i64,
+    /// 达成目标的天数
+    pub days_reached: i64,
+    /// 目标达成率（没有设置目标的天不计入）
+    pub goal_attainment_rate: Option<f64>,
+    pub days: Vec<DailyGoalProgress>,
+}
+
+/// 连续达成目标与一致性统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreakStats {
+    /// 当前连续达成天数
+    pub current_streak: i64,
+    /// 历史最长连续达成天数
+    pub longest_streak: i64,
+    pub last_reached_date: Option<NaiveDate>,
+    /// 最近7天的目标一致性得分（0.0-1.0）
+    pub consistency_7d: Option<f64>,
+    /// 最近30天的目标一致性得分（0.0-1.0）
+    pub consistency_30d: Option<f64>,
+}
+
+/// 中断原因及出现次数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterruptionReasonCount {
+    pub reason: String,
+    pub count: i64,
+}
+
+/// 任务在某段时间内的专注汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskFocusSummary {
+    pub task_id: String,
+    pub title: String,
+    pub focus_seconds: i64,
+    pub completed_cycles: i64,
+}
+
+/// 每周回顾报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeeklyReport {
+    /// 周一（本地日期）
+    pub week_start: NaiveDate,
+    /// 周日（本地日期）
+    pub week_end: NaiveDate,
+    pub total_focus_seconds: i64,
+    pub total_completed_cycles: i64,
+    pub days_reached: i64,
+    pub goal_attainment_rate: Option<f64>,
+    /// 专注时间最多的一天（本周没有专注时为空）
+    pub best_day: Option<DailyGoalProgress>,
+    /// 专注时间最少的一天（不含尚未到来的日期）
+    pub worst_day: Option<DailyGoalProgress>,
+    /// 平均微休息完成率（没有触发微休息的会话不计入）
+    pub average_micro_break_completion: Option<f64>,
+    pub total_interruptions: i64,
+    /// 最常见的中断原因（按次数降序）
+    pub interruption_reasons: Vec<InterruptionReasonCount>,
+    /// 专注时间最多的任务（按专注时间降序）
+    pub top_tasks: Vec<TaskFocusSummary>,
+    pub days: Vec<DailyGoalProgress>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// 已保存的每周回顾报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedWeeklyReport {
+    pub report: WeeklyReport,
+    pub json_path: String,
+    pub markdown_path: String,
+    pub html_path: String,
+}
+
+/// 热力图中某个星期几某个小时的格子
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HeatmapCell {
+    pub focus_minutes: f64,
+    /// 按专注分钟加权的平均质量评分（没有评分时为空）
+    pub average_quality: Option<f64>,
+}
+
+/// 按星期 × 小时（本地时间）统计的专注热力图
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FocusHeatmap {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// 7行（周一至周日）× 24列（0-23点）
+    pub cells: Vec<Vec<HeatmapCell>>,
+}
+
+/// 专注会话之后长休息的执行情况
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LongBreakStatus {
+    Taken,   // 完成了长休息
+    Skipped, // 主动跳过（开始前或进行中）
+    Missed,  // 未休息就开始了下一个专注会话
+}
+
+impl LongBreakStatus {
+    /// 数据库中存储的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LongBreakStatus::Taken => "Taken",
+            LongBreakStatus::Skipped => "Skipped",
+            LongBreakStatus::Missed => "Missed",
+        }
+    }
+}
+
+/// 长休息执行情况统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LongBreakAdherence {
+    pub taken: i64,
+    pub skipped: i64,
+    pub missed: i64,
+    /// 完成长休息的比例（没有已结束的专注会话时为空）
+    pub adherence_rate: Option<f64>,
+    /// 专注结束到开始长休息的平均间隔（秒）
+    pub average_gap_seconds: Option<f64>,
+    /// 最近连续未完成长休息的次数
+    pub consecutive_skipped: i64,
+}
+
+/// 阶段序列中步骤的类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum StepKind {
+    Focus,     // 专注（计入专注统计）
+    WarmUp,    // 热身（不计入专注统计）
+    Review,    // 复盘（不计入专注统计）
+    Break,     // 序列中的休息（不计入长休息执行情况）
+    LongBreak, // 90/20长休息（计入长休息执行情况）
+}
+
+impl StepKind {
+    /// 是否计入专注统计、目标进度和计划的专注步骤数
+    pub fn counts_as_focus(&self) -> bool {
+        *self == StepKind::Focus
+    }
+
+    /// 该步骤保存的会话类型
+    pub fn session_type(&self) -> SessionType {
+        match self {
+            StepKind::Focus => SessionType::Focus,
+            StepKind::WarmUp => SessionType::WarmUp,
+            StepKind::Review => SessionType::Review,
+            StepKind::Break => SessionType::SequenceBreak,
+            StepKind::LongBreak => SessionType::LongBreak,
+        }
+    }
+}
+
+/// 阶段序列中的一个步骤
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequenceStep {
+    pub name: String,
+    pub kind: StepKind,
+    pub duration_minutes: u32,
+    /// 是否在该步骤中安排微休息（仅对专注步骤有效）
+    #[serde(default)]
+    pub micro_breaks: bool,
+    /// 步骤开始时播放的提示音
+    #[serde(default)]
+    pub audio_cue: Option<AudioType>,
+}
+
+/// 用户定义的阶段序列（例如“热身10分钟 → 专注50 → 复盘10 → 休息15”）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseSequence {
+    pub id: String,
+    pub name: String,
+    pub steps: Vec<SequenceStep>,
+    /// 是否为内置序列（内置序列不能修改或删除）
+    pub built_in: bool,
+    /// 内置序列没有创建和更新时间
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// 多周期计划：连续进行N个专注周期，周期之间为长休息，最后可以有一次最终休息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CyclePlan {
+    /// 专注周期数
+    pub cycles: u32,
+    /// 最后一个专注之后的休息时长（分钟），为空时计划在最后一个专注后结束
+    pub final_break_minutes: Option<u32>,
+}
+
+/// 进行中的多周期计划或阶段序列进度
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CyclePlanProgress {
+    pub name: String,
+    pub steps: Vec<SequenceStep>,
+    /// 当前步骤的索引
+    pub current_step: usize,
+    /// 计划开始以来完成的专注会话数
+    pub completed_focus_sessions: u32,
+    /// 计划中的专注步骤数
+    pub target_focus_sessions: u32,
+}
+
+/// 更新提示音配置的输入
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateAudioConfig {
+    pub volume: Option<f32>,
+    pub enabled: Option<bool>,
+    pub lead_seconds: Option<u32>,
 }
 
 /// 更新用户设置的输入
@@ -99,6 +827,8 @@ pub struct UpdateUserSettings {
     pub micro_break_max_interval_minutes: Option<i32>,
     pub micro_break_duration_seconds: Option<i32>,
     pub notifications_enabled: Option<bool>,
+    pub long_break_skip_warning_threshold: Option<u32>,
+    pub flow_mode_enabled: Option<bool>,
 }
 
 impl Default for UserSettings {
@@ -114,6 +844,8 @@ impl Default for UserSettings {
             micro_break_max_interval_minutes: 5,
             micro_break_duration_seconds: 15,
             notifications_enabled: true,
+            long_break_skip_warning_threshold: None,
+            flow_mode_enabled: false,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
@@ -0,0 +1,151 @@
+use crate::services::timer::MicroBreakStats;
+
+/// 完成度在评分中的权重
+const COMPLETION_WEIGHT: f32 = 0.5;
+/// 微休息有效性在评分中的权重
+const MICRO_BREAK_WEIGHT: f32 = 0.3;
+/// 精力状态（1 - 疲劳度）在评分中的权重
+const FRESHNESS_WEIGHT: f32 = 0.2;
+/// 每次暂停的扣分
+const PAUSE_PENALTY: f32 = 0.05;
+/// 暂停扣分上限
+const MAX_PAUSE_PENALTY: f32 = 0.25;
+
+/// 专注质量评分的输入
+#[derive(Debug, Clone)]
+pub struct FocusQualityInput {
+    /// 实际专注时长（秒）
+    pub elapsed_seconds: u64,
+    /// 计划专注时长（秒）
+    pub planned_seconds: u64,
+    /// 是否完整结束（未被提前重置）
+    pub completed: bool,
+    /// 暂停次数
+    pub pause_count: u32,
+    /// 微休息统计
+    pub micro_breaks: MicroBreakStats,
+}
+
+/// 专注质量评分结果（均为0.0-1.0）
+#[derive(Debug, Clone, Copy)]
+pub struct FocusQuality {
+    pub score: f32,
+    pub micro_break_effectiveness: f32,
+}
+
+/// 根据暂停、微休息完成/跳过比例、疲劳度和完成情况计算专注质量
+pub fn compute_focus_quality(input: &FocusQualityInput) -> FocusQuality {
+    let completion = if input.completed || input.planned_seconds == 0 {
+        1.0
+    } else {
+        (input.elapsed_seconds as f32 / input.planned_seconds as f32).clamp(0.0, 1.0)
+    };
+
+    // 有明确结果（完成或跳过）的微休息按完成/跳过比例计算，否则沿用调度器的完成率
+    let handled = input.micro_breaks.completed + input.micro_breaks.skipped;
+    let micro_break_effectiveness = if handled > 0 {
+        input.micro_breaks.completed as f32 / handled as f32
+    } else {
+        input.micro_breaks.completion_rate
+    }
+    .clamp(0.0, 1.0);
+
+    let freshness = 1.0 - input.micro_breaks.fatigue_level.clamp(0.0, 1.0);
+    let pause_penalty = (input.pause_count as f32 * PAUSE_PENALTY).min(MAX_PAUSE_PENALTY);
+
+    let score = COMPLETION_WEIGHT * completion
+        + MICRO_BREAK_WEIGHT * micro_break_effectiveness
+        + FRESHNESS_WEIGHT * freshness
+        - pause_penalty;
+
+    FocusQuality {
+        score: score.clamp(0.0, 1.0),
+        micro_break_effectiveness,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn micro_breaks(completed: u32, skipped: u32, completion_rate: f32, fatigue_level: f32) -> MicroBreakStats {
+        MicroBreakStats {
+            total_triggered: completed + skipped,
+            completed,
+            skipped,
+            completion_rate,
+            fatigue_level,
+            adjustment_factor: 1.0,
+            next_break_at: None,
+        }
+    }
+
+    fn input(elapsed_seconds: u64, completed: bool, pause_count: u32, stats: MicroBreakStats) -> FocusQualityInput {
+        FocusQualityInput {
+            elapsed_seconds,
+            planned_seconds: 5400,
+            completed,
+            pause_count,
+            micro_breaks: stats,
+        }
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-5, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn perfect_session_scores_full_marks() {
+        let quality = compute_focus_quality(&input(5400, true, 0, micro_breaks(4, 0, 1.0, 0.0)));
+
+        assert_close(quality.score, 1.0);
+        assert_close(quality.micro_break_effectiveness, 1.0);
+    }
+
+    #[test]
+    fn early_end_scores_by_elapsed_share() {
+        // 只完成一半：完成度0.5
+        let quality = compute_focus_quality(&input(2700, false, 0, micro_breaks(2, 0, 1.0, 0.0)));
+        assert_close(quality.score, COMPLETION_WEIGHT * 0.5 + MICRO_BREAK_WEIGHT + FRESHNESS_WEIGHT);
+    }
+
+    #[test]
+    fn zero_planned_duration_counts_as_complete() {
+        // 正计时会话没有计划时长
+        let mut open = input(600, false, 0, micro_breaks(0, 0, 1.0, 0.0));
+        open.planned_seconds = 0;
+        assert_close(compute_focus_quality(&open).score, 1.0);
+    }
+
+    #[test]
+    fn skipped_micro_breaks_lower_effectiveness() {
+        let quality = compute_focus_quality(&input(5400, true, 0, micro_breaks(1, 3, 1.0, 0.0)));
+        assert_close(quality.micro_break_effectiveness, 0.25);
+    }
+
+    #[test]
+    fn scheduler_rate_is_used_without_handled_breaks() {
+        let quality = compute_focus_quality(&input(5400, true, 0, micro_breaks(0, 0, 0.6, 0.0)));
+        assert_close(quality.micro_break_effectiveness, 0.6);
+    }
+
+    #[test]
+    fn pause_penalty_is_capped() {
+        let stats = || micro_breaks(4, 0, 1.0, 0.0);
+        let few = compute_focus_quality(&input(5400, true, 2, stats()));
+        let many = compute_focus_quality(&input(5400, true, 50, stats()));
+
+        assert_close(few.score, 1.0 - 2.0 * PAUSE_PENALTY);
+        assert_close(many.score, 1.0 - MAX_PAUSE_PENALTY);
+    }
+
+    #[test]
+    fn score_stays_within_bounds() {
+        // 异常的疲劳度和超时时长不会让评分越界
+        let worst = compute_focus_quality(&input(0, false, 50, micro_breaks(0, 5, 0.0, 3.0)));
+        let overlong = compute_focus_quality(&input(99_999, false, 0, micro_breaks(4, 0, 1.0, -1.0)));
+
+        assert_close(worst.score, 0.0);
+        assert_close(overlong.score, 1.0);
+    }
+}
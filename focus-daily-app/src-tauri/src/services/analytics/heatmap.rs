@@ -0,0 +1,145 @@
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+
+use crate::models::HeatmapCell;
+use crate::services::storage::FocusSpan;
+
+/// 将专注时段按所在时区的小时边界切分，累计到星期 × 小时的格子中
+///
+/// 返回7行（周一至周日）× 24列的格子；质量评分按每格内的专注分钟加权平均。
+pub fn build_heatmap<Tz: TimeZone>(spans: &[FocusSpan], tz: &Tz) -> Vec<Vec<HeatmapCell>> {
+    let mut minutes = [[0.0f64; 24]; 7];
+    let mut quality_sum = [[0.0f64; 24]; 7];
+    let mut quality_minutes = [[0.0f64; 24]; 7];
+
+    for span in spans {
+        let mut cursor = span.start;
+        while cursor < span.end {
+            let local = cursor.with_timezone(tz);
+            let weekday = local.weekday().num_days_from_monday() as usize;
+            let hour = local.hour() as usize;
+
+            let segment_end = next_hour_boundary(&cursor, tz).min(span.end);
+            let segment_minutes = (segment_end - cursor).num_milliseconds() as f64 / 60_000.0;
+
+            minutes[weekday][hour] += segment_minutes;
+            if let Some(score) = span.quality_score {
+                quality_sum[weekday][hour] += score * segment_minutes;
+                quality_minutes[weekday][hour] += segment_minutes;
+            }
+            cursor = segment_end;
+        }
+    }
+
+    (0..7)
+        .map(|weekday| {
+            (0..24)
+                .map(|hour| HeatmapCell {
+                    focus_minutes: minutes[weekday][hour],
+                    average_quality: (quality_minutes[weekday][hour] > 0.0)
+                        .then(|| quality_sum[weekday][hour] / quality_minutes[weekday][hour]),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// 时刻之后的下一个本地整点
+fn next_hour_boundary<Tz: TimeZone>(time: &DateTime<Utc>, tz: &Tz) -> DateTime<Utc> {
+    let local = time.with_timezone(tz).naive_local();
+    let hour_start = local.date().and_hms_opt(local.hour(), 0, 0).unwrap_or(local);
+
+    // 下一个整点落在夏令时跳变的空档中时，取空档之后的第一个整点
+    (1..=3)
+        .filter_map(|hours| tz.from_local_datetime(&(hour_start + Duration::hours(hours))).earliest())
+        .map(|boundary| boundary.with_timezone(&Utc))
+        .find(|boundary| boundary > time)
+        .unwrap_or_else(|| *time + Duration::hours(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::test_support::DstZone;
+
+    fn utc(month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        let naive = NaiveDate::from_ymd_opt(2024, month, day).unwrap().and_hms_opt(hour, minute, 0).unwrap();
+        Utc.from_utc_datetime(&naive)
+    }
+
+    fn span(start: DateTime<Utc>, end: DateTime<Utc>, quality_score: Option<f64>) -> FocusSpan {
+        FocusSpan { start, end, quality_score }
+    }
+
+    /// 有专注时间的格子：(星期, 小时, 分钟)
+    fn filled(cells: &[Vec<HeatmapCell>]) -> Vec<(usize, usize, f64)> {
+        let mut filled = Vec::new();
+        for (weekday, row) in cells.iter().enumerate() {
+            for (hour, cell) in row.iter().enumerate() {
+                if cell.focus_minutes > 0.0 {
+                    filled.push((weekday, hour, (cell.focus_minutes * 1000.0).round() / 1000.0));
+                }
+            }
+        }
+        filled
+    }
+
+    #[test]
+    fn empty_spans_give_an_empty_grid() {
+        let cells = build_heatmap(&[], &Utc);
+
+        assert_eq!(cells.len(), 7);
+        assert!(cells.iter().all(|row| row.len() == 24));
+        assert!(filled(&cells).is_empty());
+        assert!(cells.iter().flatten().all(|cell| cell.average_quality.is_none()));
+    }
+
+    #[test]
+    fn spans_are_split_at_hour_boundaries() {
+        // 2024-06-03（周一）09:40-11:10
+        let cells = build_heatmap(&[span(utc(6, 3, 9, 40), utc(6, 3, 11, 10), None)], &Utc);
+        assert_eq!(filled(&cells), vec![(0, 9, 20.0), (0, 10, 60.0), (0, 11, 10.0)]);
+    }
+
+    #[test]
+    fn spans_crossing_local_midnight_move_to_the_next_weekday() {
+        // UTC周日22:30-23:30，在UTC+2时区是周一00:30-01:30
+        let cells = build_heatmap(&[span(utc(6, 2, 22, 30), utc(6, 2, 23, 30), None)], &DstZone);
+        assert_eq!(filled(&cells), vec![(0, 0, 30.0), (0, 1, 30.0)]);
+
+        // 同一时段在UTC中跨越23点，仍是周日
+        let cells = build_heatmap(&[span(utc(6, 2, 22, 30), utc(6, 2, 23, 30), None)], &Utc);
+        assert_eq!(filled(&cells), vec![(6, 22, 30.0), (6, 23, 30.0)]);
+    }
+
+    #[test]
+    fn spring_forward_skips_the_missing_hour() {
+        // 2024-03-31（周日）本地01:30-04:00，02:00-03:00不存在，实际专注1.5小时
+        let cells = build_heatmap(&[span(utc(3, 31, 0, 30), utc(3, 31, 2, 0), None)], &DstZone);
+        assert_eq!(filled(&cells), vec![(6, 1, 30.0), (6, 3, 60.0)]);
+    }
+
+    #[test]
+    fn fall_back_counts_the_repeated_hour_twice() {
+        // 2024-10-27（周日）本地02:00-03:00出现两次，共2小时都记在2点
+        let cells = build_heatmap(&[span(utc(10, 27, 0, 0), utc(10, 27, 2, 0), None)], &DstZone);
+        assert_eq!(filled(&cells), vec![(6, 2, 120.0)]);
+    }
+
+    #[test]
+    fn quality_is_weighted_by_minutes() {
+        let spans = [
+            span(utc(6, 4, 14, 0), utc(6, 4, 14, 45), Some(0.8)),
+            span(utc(6, 4, 14, 45), utc(6, 4, 15, 0), Some(0.4)),
+            // 没有评分的时段不影响平均质量
+            span(utc(6, 4, 15, 0), utc(6, 4, 15, 30), None),
+        ];
+        let cells = build_heatmap(&spans, &Utc);
+
+        let quality = cells[1][14].average_quality.unwrap();
+        assert!((quality - 0.7).abs() < 1e-9);
+        assert_eq!(cells[1][15].focus_minutes, 30.0);
+        assert_eq!(cells[1][15].average_quality, None);
+    }
+}
@@ -0,0 +1,12 @@
+pub mod rating_stats;
+pub mod focus_quality;
+pub mod streaks;
+pub mod weekly_report;
+pub mod heatmap;
+
+// 重新导出主要接口
+pub use rating_stats::correlate_ratings;
+pub use focus_quality::{compute_focus_quality, FocusQualityInput};
+pub use streaks::{consistency_score, StreakCounter};
+pub use weekly_report::{best_and_worst_days, render_html, render_markdown};
+pub use heatmap::build_heatmap;
@@ -0,0 +1,165 @@
+use crate::models::{RatingBucket, RatingCorrelationStats};
+use crate::services::storage::RatedSessionSample;
+
+/// 计算评分与会话行为（微休息完成率、中断、分心）的相关性
+pub fn correlate_ratings(samples: &[RatedSessionSample]) -> RatingCorrelationStats {
+    let scores: Vec<f64> = samples.iter().map(|s| s.score as f64).collect();
+    let interruptions: Vec<f64> = samples.iter().map(|s| s.pause_count as f64).collect();
+    let distractions: Vec<f64> = samples.iter().map(|s| s.distractions as f64).collect();
+
+    // 只有触发过微休息的会话才有完成率
+    let (completion_scores, completion_rates): (Vec<f64>, Vec<f64>) = samples
+        .iter()
+        .filter_map(|s| micro_break_completion(s).map(|rate| (s.score as f64, rate)))
+        .unzip();
+
+    let buckets = (1..=5)
+        .filter_map(|score| {
+            let in_bucket: Vec<&RatedSessionSample> = samples.iter().filter(|s| s.score == score).collect();
+            if in_bucket.is_empty() {
+                return None;
+            }
+
+            let count = in_bucket.len() as f64;
+            let completions: Vec<f64> = in_bucket.iter().filter_map(|s| micro_break_completion(s)).collect();
+
+            Some(RatingBucket {
+                score,
+                sessions: in_bucket.len() as i64,
+                average_micro_break_completion: mean(&completions),
+                average_interruptions: in_bucket.iter().map(|s| s.pause_count as f64).sum::<f64>() / count,
+                average_distractions: in_bucket.iter().map(|s| s.distractions as f64).sum::<f64>() / count,
+            })
+        })
+        .collect();
+
+    RatingCorrelationStats {
+        rated_sessions: samples.len() as i64,
+        average_score: mean(&scores),
+        score_vs_micro_break_completion: pearson(&completion_scores, &completion_rates),
+        score_vs_interruptions: pearson(&scores, &interruptions),
+        score_vs_distractions: pearson(&scores, &distractions),
+        buckets,
+    }
+}
+
+/// 单个会话的微休息完成率
+fn micro_break_completion(sample: &RatedSessionSample) -> Option<f64> {
+    if sample.micro_breaks_triggered <= 0 {
+        return None;
+    }
+    Some((sample.micro_breaks_completed as f64 / sample.micro_breaks_triggered as f64).min(1.0))
+}
+
+/// 平均值
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// 皮尔逊相关系数（样本不足或方差为0时无意义，返回None）
+fn pearson(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    if xs.len() != ys.len() || xs.len() < 2 {
+        return None;
+    }
+
+    let mean_x = mean(xs)?;
+    let mean_y = mean(ys)?;
+
+    let mut covariance = 0.0;
+    let mut variance_x = 0.0;
+    let mut variance_y = 0.0;
+    for (x, y) in xs.iter().zip(ys) {
+        let dx = x - mean_x;
+        let dy = y - mean_y;
+        covariance += dx * dy;
+        variance_x += dx * dx;
+        variance_y += dy * dy;
+    }
+
+    if variance_x == 0.0 || variance_y == 0.0 {
+        return None;
+    }
+
+    Some(covariance / (variance_x.sqrt() * variance_y.sqrt()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(score: i32, pause_count: i32, triggered: i32, completed: i32) -> RatedSessionSample {
+        RatedSessionSample {
+            score,
+            distractions: 0,
+            pause_count,
+            micro_breaks_triggered: triggered,
+            micro_breaks_completed: completed,
+        }
+    }
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.expect("应有数值");
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn empty_samples_have_no_statistics() {
+        let stats = correlate_ratings(&[]);
+
+        assert_eq!(stats.rated_sessions, 0);
+        assert_eq!(stats.average_score, None);
+        assert_eq!(stats.score_vs_micro_break_completion, None);
+        assert_eq!(stats.score_vs_interruptions, None);
+        assert_eq!(stats.score_vs_distractions, None);
+        assert!(stats.buckets.is_empty());
+    }
+
+    #[test]
+    fn single_sample_has_average_but_no_correlation() {
+        let stats = correlate_ratings(&[sample(4, 2, 3, 3)]);
+
+        assert_eq!(stats.rated_sessions, 1);
+        assert_close(stats.average_score, 4.0);
+        assert_eq!(stats.score_vs_interruptions, None);
+        assert_eq!(stats.buckets.len(), 1);
+        assert_close(stats.buckets[0].average_micro_break_completion, 1.0);
+    }
+
+    #[test]
+    fn correlations_follow_sample_direction() {
+        // 暂停越多评分越低，微休息完成率越高评分越高
+        let samples = [sample(5, 0, 4, 4), sample(3, 2, 4, 2), sample(1, 4, 4, 0)];
+        let stats = correlate_ratings(&samples);
+
+        assert_close(stats.score_vs_interruptions, -1.0);
+        assert_close(stats.score_vs_micro_break_completion, 1.0);
+        // 分心次数全部相同，方差为0时没有相关系数
+        assert_eq!(stats.score_vs_distractions, None);
+    }
+
+    #[test]
+    fn sessions_without_micro_breaks_are_left_out_of_completion() {
+        let samples = [sample(5, 0, 0, 0), sample(5, 1, 2, 1), sample(2, 3, 0, 0)];
+        let stats = correlate_ratings(&samples);
+
+        // 只有一个会话触发过微休息，不足以计算相关性
+        assert_eq!(stats.score_vs_micro_break_completion, None);
+
+        let scores: Vec<i32> = stats.buckets.iter().map(|bucket| bucket.score).collect();
+        assert_eq!(scores, vec![2, 5]);
+        assert_eq!(stats.buckets[0].average_micro_break_completion, None);
+        assert_eq!(stats.buckets[1].sessions, 2);
+        assert_close(stats.buckets[1].average_micro_break_completion, 0.5);
+        assert_close(Some(stats.buckets[1].average_interruptions), 0.5);
+    }
+
+    #[test]
+    fn completion_rate_is_capped_at_one() {
+        // 完成次数超过触发次数（数据异常）时按100%计算
+        let stats = correlate_ratings(&[sample(3, 0, 2, 5)]);
+        assert_close(stats.buckets[0].average_micro_break_completion, 1.0);
+    }
+}
@@ -0,0 +1,141 @@
+use chrono::NaiveDate;
+
+use crate::models::DailyGoalProgress;
+
+/// 连续达成目标的计数器
+///
+/// 按日期升序逐天推进：未设置目标的日期不中断也不延长连续天数，
+/// 尚未结束的今天没有达成时也不中断。
+#[derive(Debug, Clone, Default)]
+pub struct StreakCounter {
+    pub current_streak: i64,
+    pub longest_streak: i64,
+    pub last_reached_date: Option<NaiveDate>,
+}
+
+impl StreakCounter {
+    /// 推进一天
+    pub fn push(&mut self, day: &DailyGoalProgress, today: NaiveDate) {
+        if day.target.is_none() {
+            return;
+        }
+
+        if day.reached {
+            self.current_streak += 1;
+            self.longest_streak = self.longest_streak.max(self.current_streak);
+            self.last_reached_date = Some(day.date);
+        } else if day.date < today {
+            self.current_streak = 0;
+        }
+    }
+}
+
+/// 一组日期的目标一致性得分（达成天数 / 设置了目标的天数）
+///
+/// 今天尚未达成时不计入分母；没有任何目标日时返回`None`。
+pub fn consistency_score(days: &[DailyGoalProgress], today: NaiveDate) -> Option<f64> {
+    let counted: Vec<&DailyGoalProgress> = days
+        .iter()
+        .filter(|day| day.target.is_some() && (day.reached || day.date < today))
+        .collect();
+
+    if counted.is_empty() {
+        return None;
+    }
+    let reached = counted.iter().filter(|day| day.reached).count();
+    Some(reached as f64 / counted.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::GoalType;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, day).unwrap()
+    }
+
+    /// 某天的目标结果；target为false表示当天没有设置目标
+    fn day(day: u32, target: bool, reached: bool) -> DailyGoalProgress {
+        DailyGoalProgress {
+            date: date(day),
+            goal_type: target.then_some(GoalType::Cycles),
+            target: target.then_some(2),
+            focus_seconds: if reached { 10800 } else { 0 },
+            completed_cycles: if reached { 2 } else { 0 },
+            progress: target.then_some(if reached { 1.0 } else { 0.0 }),
+            reached,
+            reached_at: None,
+        }
+    }
+
+    fn count(days: &[DailyGoalProgress], today: NaiveDate) -> StreakCounter {
+        let mut counter = StreakCounter::default();
+        for day in days {
+            counter.push(day, today);
+        }
+        counter
+    }
+
+    #[test]
+    fn empty_range_has_no_streak() {
+        let counter = count(&[], date(10));
+
+        assert_eq!(counter.current_streak, 0);
+        assert_eq!(counter.longest_streak, 0);
+        assert_eq!(counter.last_reached_date, None);
+        assert_eq!(consistency_score(&[], date(10)), None);
+    }
+
+    #[test]
+    fn consecutive_reached_days_build_a_streak() {
+        let days = [day(1, true, true), day(2, true, true), day(3, true, true)];
+        let counter = count(&days, date(3));
+
+        assert_eq!(counter.current_streak, 3);
+        assert_eq!(counter.longest_streak, 3);
+        assert_eq!(counter.last_reached_date, Some(date(3)));
+    }
+
+    #[test]
+    fn missed_day_breaks_the_streak() {
+        // 6月3日设置了目标但没有任何专注
+        let days = [day(1, true, true), day(2, true, true), day(3, true, false), day(4, true, true)];
+        let counter = count(&days, date(5));
+
+        assert_eq!(counter.current_streak, 1);
+        assert_eq!(counter.longest_streak, 2);
+        assert_eq!(counter.last_reached_date, Some(date(4)));
+    }
+
+    #[test]
+    fn days_without_goal_neither_break_nor_extend() {
+        let days = [day(1, true, true), day(2, false, false), day(3, true, true)];
+        let counter = count(&days, date(3));
+
+        assert_eq!(counter.current_streak, 2);
+    }
+
+    #[test]
+    fn unfinished_today_keeps_the_streak() {
+        let days = [day(1, true, true), day(2, true, true), day(3, true, false)];
+
+        assert_eq!(count(&days, date(3)).current_streak, 2);
+        // 到了第二天仍未达成则中断
+        assert_eq!(count(&days, date(4)).current_streak, 0);
+    }
+
+    #[test]
+    fn consistency_counts_goal_days_only() {
+        let days = [
+            day(1, true, true),
+            day(2, true, false),
+            day(3, false, false),
+            day(4, true, true),
+            day(5, true, false), // 今天尚未达成，不计入
+        ];
+
+        assert_eq!(consistency_score(&days, date(5)), Some(2.0 / 3.0));
+        assert_eq!(consistency_score(&days[2..3], date(5)), None);
+    }
+}
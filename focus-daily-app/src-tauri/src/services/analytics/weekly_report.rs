@@ -0,0 +1,354 @@
+use std::fmt::Write;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::models::{DailyGoalProgress, WeeklyReport};
+
+/// 星期名称（周一开始）
+const WEEKDAY_NAMES: [&str; 7] = ["周一", "周二", "周三", "周四", "周五", "周六", "周日"];
+
+/// 一周中专注时间最多和最少的一天
+///
+/// 平局时都取较早的日期；最少的一天不含today之后尚未到来的日期；本周没有任何专注时都为空。
+pub fn best_and_worst_days(
+    days: &[DailyGoalProgress],
+    today: NaiveDate,
+) -> (Option<DailyGoalProgress>, Option<DailyGoalProgress>) {
+    // max_by_key取最后一个最大值，因此倒序查找
+    let best_day = days.iter()
+        .rev()
+        .filter(|day| day.focus_seconds > 0)
+        .max_by_key(|day| day.focus_seconds)
+        .cloned();
+    let worst_day = days.iter()
+        .filter(|day| day.date <= today)
+        .min_by_key(|day| day.focus_seconds)
+        .cloned()
+        .filter(|_| best_day.is_some());
+    (best_day, worst_day)
+}
+
+/// 将每周回顾报告渲染为Markdown
+pub fn render_markdown(report: &WeeklyReport) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# 每周回顾：{} ~ {}", report.week_start, report.week_end);
+    let _ = writeln!(out);
+    let _ = writeln!(out, "## 概览");
+    let _ = writeln!(out);
+    for (label, value) in summary_rows(report) {
+        let _ = writeln!(out, "- **{}**：{}", label, value);
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(out, "## 每日专注");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "| 日期 | 专注时间 | 完成周期 | 目标 |");
+    let _ = writeln!(out, "| --- | --- | --- | --- |");
+    for day in &report.days {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} |",
+            day_label(day.date),
+            format_duration(day.focus_seconds),
+            day.completed_cycles,
+            goal_status(day),
+        );
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(out, "## 常见中断原因");
+    let _ = writeln!(out);
+    if report.interruption_reasons.is_empty() {
+        let _ = writeln!(out, "本周没有记录中断原因。");
+    } else {
+        for (index, reason) in report.interruption_reasons.iter().enumerate() {
+            let _ = writeln!(out, "{}. {}（{}次）", index + 1, escape_markdown(&reason.reason), reason.count);
+        }
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(out, "## 主要任务");
+    let _ = writeln!(out);
+    if report.top_tasks.is_empty() {
+        let _ = writeln!(out, "本周没有关联任务的专注会话。");
+    } else {
+        for (index, task) in report.top_tasks.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "{}. {}：{}，{}个周期",
+                index + 1,
+                escape_markdown(&task.title),
+                format_duration(task.focus_seconds),
+                task.completed_cycles,
+            );
+        }
+    }
+
+    out
+}
+
+/// 将每周回顾报告渲染为独立的HTML页面
+pub fn render_html(report: &WeeklyReport) -> String {
+    let title = format!("每周回顾：{} ~ {}", report.week_start, report.week_end);
+    let mut out = String::new();
+
+    let _ = writeln!(out, "<!DOCTYPE html>");
+    let _ = writeln!(out, "<html lang=\"zh-CN\">");
+    let _ = writeln!(out, "<head>");
+    let _ = writeln!(out, "<meta charset=\"utf-8\">");
+    let _ = writeln!(out, "<title>{}</title>", title);
+    let _ = writeln!(
+        out,
+        "<style>body{{font-family:sans-serif;max-width:720px;margin:2em auto;}}\
+         table{{border-collapse:collapse;width:100%;}}\
+         th,td{{border:1px solid #ccc;padding:4px 8px;text-align:left;}}</style>"
+    );
+    let _ = writeln!(out, "</head>");
+    let _ = writeln!(out, "<body>");
+    let _ = writeln!(out, "<h1>{}</h1>", title);
+
+    let _ = writeln!(out, "<h2>概览</h2>");
+    let _ = writeln!(out, "<ul>");
+    for (label, value) in summary_rows(report) {
+        let _ = writeln!(out, "<li><strong>{}</strong>：{}</li>", label, escape_html(&value));
+    }
+    let _ = writeln!(out, "</ul>");
+
+    let _ = writeln!(out, "<h2>每日专注</h2>");
+    let _ = writeln!(out, "<table>");
+    let _ = writeln!(out, "<tr><th>日期</th><th>专注时间</th><th>完成周期</th><th>目标</th></tr>");
+    for day in &report.days {
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            day_label(day.date),
+            format_duration(day.focus_seconds),
+            day.completed_cycles,
+            goal_status(day),
+        );
+    }
+    let _ = writeln!(out, "</table>");
+
+    let _ = writeln!(out, "<h2>常见中断原因</h2>");
+    if report.interruption_reasons.is_empty() {
+        let _ = writeln!(out, "<p>本周没有记录中断原因。</p>");
+    } else {
+        let _ = writeln!(out, "<ol>");
+        for reason in &report.interruption_reasons {
+            let _ = writeln!(out, "<li>{}（{}次）</li>", escape_html(&reason.reason), reason.count);
+        }
+        let _ = writeln!(out, "</ol>");
+    }
+
+    let _ = writeln!(out, "<h2>主要任务</h2>");
+    if report.top_tasks.is_empty() {
+        let _ = writeln!(out, "<p>本周没有关联任务的专注会话。</p>");
+    } else {
+        let _ = writeln!(out, "<ol>");
+        for task in &report.top_tasks {
+            let _ = writeln!(
+                out,
+                "<li>{}：{}，{}个周期</li>",
+                escape_html(&task.title),
+                format_duration(task.focus_seconds),
+                task.completed_cycles,
+            );
+        }
+        let _ = writeln!(out, "</ol>");
+    }
+
+    let _ = writeln!(out, "</body>");
+    let _ = writeln!(out, "</html>");
+    out
+}
+
+/// 概览部分的各行（标签，值）
+fn summary_rows(report: &WeeklyReport) -> Vec<(&'static str, String)> {
+    vec![
+        ("总专注时间", format_duration(report.total_focus_seconds)),
+        ("完成周期", report.total_completed_cycles.to_string()),
+        ("目标达成", match report.goal_attainment_rate {
+            Some(rate) => format!("{}天（{}）", report.days_reached, format_percent(rate)),
+            None => "未设置目标".to_string(),
+        }),
+        ("最佳一天", best_or_worst(report.best_day.as_ref())),
+        ("最差一天", best_or_worst(report.worst_day.as_ref())),
+        ("平均微休息完成率", report.average_micro_break_completion
+            .map(format_percent)
+            .unwrap_or_else(|| "无微休息".to_string())),
+        ("中断次数", report.total_interruptions.to_string()),
+    ]
+}
+
+fn best_or_worst(day: Option<&DailyGoalProgress>) -> String {
+    match day {
+        Some(day) => format!("{}（{}）", day_label(day.date), format_duration(day.focus_seconds)),
+        None => "-".to_string(),
+    }
+}
+
+fn goal_status(day: &DailyGoalProgress) -> &'static str {
+    match (day.target, day.reached) {
+        (None, _) => "-",
+        (Some(_), true) => "已达成",
+        (Some(_), false) => "未达成",
+    }
+}
+
+fn day_label(date: NaiveDate) -> String {
+    format!("{} {}", date.format("%m-%d"), WEEKDAY_NAMES[date.weekday().num_days_from_monday() as usize])
+}
+
+/// 格式化时长，例如“5小时30分钟”
+fn format_duration(seconds: i64) -> String {
+    let minutes = seconds / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}分钟", m),
+        (h, 0) => format!("{}小时", h),
+        (h, m) => format!("{}小时{}分钟", h, m),
+    }
+}
+
+fn format_percent(value: f64) -> String {
+    format!("{:.0}%", value * 100.0)
+}
+
+/// 转义用户输入中会破坏Markdown结构的字符
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '#' | '|' | '<' | '>') {
+            out.push('\\');
+        }
+        out.push(if c == '\n' { ' ' } else { c });
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use super::*;
+    use crate::models::{InterruptionReasonCount, TaskFocusSummary};
+
+    /// 2024-06-03是周一
+    fn monday() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 6, 3).unwrap()
+    }
+
+    fn week(focus_minutes: [i64; 7]) -> Vec<DailyGoalProgress> {
+        focus_minutes
+            .iter()
+            .enumerate()
+            .map(|(offset, minutes)| DailyGoalProgress {
+                date: monday() + Duration::days(offset as i64),
+                goal_type: None,
+                target: None,
+                focus_seconds: minutes * 60,
+                completed_cycles: minutes / 90,
+                progress: None,
+                reached: false,
+                reached_at: None,
+            })
+            .collect()
+    }
+
+    fn report(days: Vec<DailyGoalProgress>, today: NaiveDate) -> WeeklyReport {
+        let (best_day, worst_day) = best_and_worst_days(&days, today);
+        WeeklyReport {
+            week_start: monday(),
+            week_end: monday() + Duration::days(6),
+            total_focus_seconds: days.iter().map(|day| day.focus_seconds).sum(),
+            total_completed_cycles: days.iter().map(|day| day.completed_cycles).sum(),
+            days_reached: 0,
+            goal_attainment_rate: None,
+            best_day,
+            worst_day,
+            average_micro_break_completion: None,
+            total_interruptions: 0,
+            interruption_reasons: Vec::new(),
+            top_tasks: Vec::new(),
+            days,
+            generated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn zero_session_week_has_no_best_or_worst_day() {
+        let days = week([0; 7]);
+        let (best, worst) = best_and_worst_days(&days, monday() + Duration::days(6));
+        assert!(best.is_none());
+        assert!(worst.is_none());
+
+        let report = report(days, monday() + Duration::days(6));
+        let markdown = render_markdown(&report);
+        assert!(markdown.contains("- **总专注时间**：0分钟"));
+        assert!(markdown.contains("- **最佳一天**：-"));
+        assert!(markdown.contains("- **目标达成**：未设置目标"));
+        assert!(markdown.contains("- **平均微休息完成率**：无微休息"));
+        assert!(markdown.contains("本周没有记录中断原因。"));
+        assert!(markdown.contains("本周没有关联任务的专注会话。"));
+
+        let html = render_html(&report);
+        assert!(html.contains("<p>本周没有记录中断原因。</p>"));
+        assert!(html.contains("<p>本周没有关联任务的专注会话。</p>"));
+    }
+
+    #[test]
+    fn ties_pick_the_earlier_day() {
+        let days = week([90, 180, 30, 180, 30, 0, 0]);
+        let (best, worst) = best_and_worst_days(&days, monday() + Duration::days(4));
+
+        assert_eq!(best.unwrap().date, monday() + Duration::days(1));
+        assert_eq!(worst.unwrap().date, monday() + Duration::days(2));
+    }
+
+    #[test]
+    fn worst_day_ignores_days_still_ahead() {
+        // 周三生成报告时，周四到周日还没有专注记录
+        let days = week([120, 60, 90, 0, 0, 0, 0]);
+        let (_, worst) = best_and_worst_days(&days, monday() + Duration::days(2));
+
+        assert_eq!(worst.unwrap().date, monday() + Duration::days(1));
+    }
+
+    #[test]
+    fn durations_and_day_labels_are_readable() {
+        assert_eq!(format_duration(0), "0分钟");
+        assert_eq!(format_duration(3600), "1小时");
+        assert_eq!(format_duration(19800), "5小时30分钟");
+        assert_eq!(day_label(monday() + Duration::days(6)), "06-09 周日");
+    }
+
+    #[test]
+    fn user_text_is_escaped() {
+        let mut report = report(week([90, 0, 0, 0, 0, 0, 0]), monday());
+        report.interruption_reasons = vec![InterruptionReasonCount {
+            reason: "*会议* | <同事>".to_string(),
+            count: 2,
+        }];
+        report.top_tasks = vec![TaskFocusSummary {
+            task_id: "task".to_string(),
+            title: "修复 #12\n[紧急]".to_string(),
+            focus_seconds: 5400,
+            completed_cycles: 1,
+        }];
+
+        let markdown = render_markdown(&report);
+        assert!(markdown.contains("1. \\*会议\\* \\| \\<同事\\>（2次）"));
+        assert!(markdown.contains("1. 修复 \\#12 \\[紧急\\]：1小时30分钟，1个周期"));
+
+        let html = render_html(&report);
+        assert!(html.contains("<li>*会议* | &lt;同事&gt;（2次）</li>"));
+    }
+}
@@ -0,0 +1,259 @@
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use tokio::task::JoinHandle;
+
+use crate::models::{AmbientBreakBehavior, AmbientSettings, AmbientSource};
+use super::{
+    decode_file_within, noise_loop, AudioClip, AudioError, AudioService, NoiseColor, MAX_SOUND_BYTES, MAX_SOUND_SECONDS,
+};
+
+/// 音量渐变的步进间隔
+const FADE_STEP: Duration = Duration::from_millis(50);
+/// 开始播放时的淡入时间
+const FADE_IN: Duration = Duration::from_secs(2);
+/// 微休息降低或恢复音量的渐变时间
+const DUCK_FADE: Duration = Duration::from_millis(500);
+/// 专注被提前结束时的淡出时间
+pub const AMBIENT_STOP_FADE: Duration = Duration::from_millis(1500);
+
+/// 背景音播放状态
+#[derive(Default)]
+struct AmbientState {
+    /// 正在播放的设置（未播放时为None）
+    settings: Option<AmbientSettings>,
+    /// 每次开始或停止播放时递增，用于丢弃过期的加载和渐变任务
+    generation: u64,
+    /// 当前背景音自身的音量
+    level: f32,
+    /// 微休息期间
+    ducked: bool,
+    /// 计时器暂停期间
+    paused: bool,
+    /// 已开始结束前的淡出
+    fading_out: bool,
+    fade: Option<JoinHandle<()>>,
+}
+
+/// 专注背景音：专注期间循环播放噪音或用户文件，微休息时降低音量或暂停，阶段结束前淡出
+pub struct AmbientPlayer {
+    audio: Arc<AudioService>,
+    state: Mutex<AmbientState>,
+}
+
+impl AmbientPlayer {
+    /// 创建背景音播放器
+    pub fn new(audio: Arc<AudioService>) -> Self {
+        Self {
+            audio,
+            state: Mutex::new(AmbientState::default()),
+        }
+    }
+
+    /// 按设置开始播放（替换正在播放的背景音）；设置未启用时停止播放
+    pub fn start(self: &Arc<Self>, settings: AmbientSettings) {
+        if !settings.enabled {
+            self.stop();
+            return;
+        }
+
+        let generation = {
+            let mut state = self.lock_state();
+            Self::cancel_fade(&mut state);
+            state.generation += 1;
+            state.settings = Some(settings.clone());
+            state.level = 0.0;
+            state.ducked = false;
+            state.paused = false;
+            state.fading_out = false;
+            state.generation
+        };
+        self.audio.stop_ambient();
+
+        // 解码文件或合成噪音较慢，放到阻塞线程中执行
+        let player = Arc::clone(self);
+        tokio::spawn(async move {
+            let clip = match tokio::task::spawn_blocking(move || Self::load(&settings)).await {
+                Ok(Ok(clip)) => clip,
+                Ok(Err(e)) => {
+                    eprintln!("Failed to load ambient sound: {}", e);
+                    return;
+                }
+                Err(e) => {
+                    eprintln!("Ambient sound loading task failed: {}", e);
+                    return;
+                }
+            };
+
+            {
+                let mut state = player.lock_state();
+                if state.generation != generation || state.fading_out {
+                    return;
+                }
+                if let Err(e) = player.audio.start_ambient(&clip, 0.0) {
+                    eprintln!("Failed to start ambient sound: {}", e);
+                    return;
+                }
+                state.level = 0.0;
+            }
+            player.apply(FADE_IN);
+        });
+    }
+
+    /// 进入微休息：按设置降低音量或暂停
+    pub fn duck(self: &Arc<Self>) {
+        self.lock_state().ducked = true;
+        self.apply(DUCK_FADE);
+    }
+
+    /// 微休息结束：恢复背景音
+    pub fn restore(self: &Arc<Self>) {
+        self.lock_state().ducked = false;
+        self.apply(DUCK_FADE);
+    }
+
+    /// 计时器暂停时暂停背景音
+    pub fn pause(self: &Arc<Self>) {
+        self.lock_state().paused = true;
+        self.apply(Duration::ZERO);
+    }
+
+    /// 计时器继续时继续背景音
+    pub fn resume(self: &Arc<Self>) {
+        self.lock_state().paused = false;
+        self.apply(Duration::ZERO);
+    }
+
+    /// 在指定时间内淡出后停止
+    pub fn fade_out(self: &Arc<Self>, duration: Duration) {
+        let mut state = self.lock_state();
+        if state.settings.is_none() || state.fading_out {
+            return;
+        }
+        state.fading_out = true;
+        drop(state);
+        self.fade_to(0.0, duration, true);
+    }
+
+    /// 立即停止
+    pub fn stop(&self) {
+        let mut state = self.lock_state();
+        Self::cancel_fade(&mut state);
+        state.generation += 1;
+        state.settings = None;
+        state.fading_out = false;
+        drop(state);
+        self.audio.stop_ambient();
+    }
+
+    /// 阶段结束前开始淡出的提前量（秒）；没有播放或已在淡出时为None
+    pub fn fade_out_lead(&self) -> Option<u64> {
+        let state = self.lock_state();
+        if state.fading_out {
+            return None;
+        }
+        state
+            .settings
+            .as_ref()
+            .map(|settings| settings.fade_out_seconds as u64)
+            .filter(|&seconds| seconds > 0)
+    }
+
+    /// 按当前状态应用音量与暂停
+    fn apply(self: &Arc<Self>, fade: Duration) {
+        let state = self.lock_state();
+        let Some(settings) = state.settings.as_ref() else {
+            return;
+        };
+        if state.fading_out {
+            return;
+        }
+
+        let pause_on_break = state.ducked && settings.micro_break_behavior == AmbientBreakBehavior::Pause;
+        let duck_on_break = state.ducked && settings.micro_break_behavior == AmbientBreakBehavior::Duck;
+        let target = if duck_on_break {
+            settings.volume * settings.duck_level
+        } else {
+            settings.volume
+        };
+        let paused = state.paused || pause_on_break;
+        drop(state);
+
+        self.audio.set_ambient_paused(paused);
+        self.fade_to(target, fade, false);
+    }
+
+    /// 把音量渐变到目标值；then_stop为true时渐变结束后停止播放
+    fn fade_to(self: &Arc<Self>, target: f32, duration: Duration, then_stop: bool) {
+        let mut state = self.lock_state();
+        Self::cancel_fade(&mut state);
+        let generation = state.generation;
+        let from = state.level;
+
+        let steps = (duration.as_millis() / FADE_STEP.as_millis()).max(1) as u32;
+        let player = Arc::clone(self);
+        state.fade = Some(tokio::spawn(async move {
+            for step in 1..=steps {
+                if duration > Duration::ZERO {
+                    tokio::time::sleep(FADE_STEP).await;
+                }
+                let level = from + (target - from) * step as f32 / steps as f32;
+                {
+                    let mut state = player.lock_state();
+                    if state.generation != generation {
+                        return;
+                    }
+                    state.level = level;
+                }
+                player.audio.set_ambient_level(level);
+            }
+
+            if then_stop {
+                let mut state = player.lock_state();
+                if state.generation != generation {
+                    return;
+                }
+                state.generation += 1;
+                state.settings = None;
+                state.fading_out = false;
+                state.fade = None;
+                drop(state);
+                player.audio.stop_ambient();
+            }
+        }));
+    }
+
+    fn cancel_fade(state: &mut AmbientState) {
+        if let Some(fade) = state.fade.take() {
+            fade.abort();
+        }
+    }
+
+    /// 加载背景音片段；文件按音效库的大小和时长上限加载，避免把长录音整个解码到内存
+    fn load(settings: &AmbientSettings) -> Result<AudioClip, AudioError> {
+        let color = match settings.source {
+            AmbientSource::WhiteNoise => NoiseColor::White,
+            AmbientSource::PinkNoise => NoiseColor::Pink,
+            AmbientSource::BrownNoise => NoiseColor::Brown,
+            AmbientSource::File => {
+                let path = settings
+                    .file_path
+                    .as_deref()
+                    .ok_or_else(|| AudioError::InvalidSound("未选择背景音文件".to_string()))?;
+                if std::fs::metadata(path)?.len() > MAX_SOUND_BYTES {
+                    return Err(AudioError::InvalidSound(format!(
+                        "背景音文件不能超过{}MB",
+                        MAX_SOUND_BYTES / 1024 / 1024
+                    )));
+                }
+                return decode_file_within(Path::new(path), Duration::from_secs(MAX_SOUND_SECONDS));
+            }
+        };
+        Ok(noise_loop(color))
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, AmbientState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
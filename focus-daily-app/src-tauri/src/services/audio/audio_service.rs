@@ -0,0 +1,411 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use chrono::Local;
+
+use crate::models::{self, AudioType, QuietHours, QuietHoursMode};
+use crate::services::events::{AudioConfig, AudioEvent, AudioEventHandler};
+use super::{decode_file, default_cue, AudioBackend, AudioClip, AudioError};
+
+/// 音频播放状态
+struct PlaybackState {
+    config: AudioConfig,
+    muted: bool,
+    /// 背景音自身的音量（没有播放背景音时为None）
+    ambient_level: Option<f32>,
+    /// 免打扰设置
+    quiet_hours: QuietHours,
+}
+
+/// 音频服务：解码提示音并通过输出后端播放
+pub struct AudioService {
+    backend: Arc<dyn AudioBackend>,
+    state: Mutex<PlaybackState>,
+    /// 已解码的提示音缓存（按文件路径）
+    cache: Mutex<HashMap<PathBuf, AudioClip>>,
+    /// 已合成的默认提示音缓存
+    defaults: Mutex<HashMap<AudioType, AudioClip>>,
+}
+
+impl AudioService {
+    /// 使用指定后端创建音频服务
+    pub fn new(backend: Arc<dyn AudioBackend>) -> Self {
+        Self {
+            backend,
+            state: Mutex::new(PlaybackState {
+                config: AudioConfig::default(),
+                muted: false,
+                ambient_level: None,
+                quiet_hours: QuietHours::default(),
+            }),
+            cache: Mutex::new(HashMap::new()),
+            defaults: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 处理音频事件
+    ///
+    /// 免打扰时段由发送提示音的一方按`quiet_mode`决定静音或改为界面通知，这里不再拦截。
+    pub fn handle(&self, event: &AudioEvent) -> Result<(), AudioError> {
+        match event {
+            AudioEvent::PlayFocusStart => self.play_cue(AudioType::FocusStart, None),
+            AudioEvent::PlayFocusEnd => self.play_cue(AudioType::FocusEnd, None),
+            AudioEvent::PlayLongBreakStart => self.play_cue(AudioType::LongBreakStart, None),
+            AudioEvent::PlayLongBreakEnd => self.play_cue(AudioType::LongBreakEnd, None),
+            AudioEvent::PlayMicroBreakStart => self.play_cue(AudioType::MicroBreakStart, None),
+            AudioEvent::PlayMicroBreakEnd => self.play_cue(AudioType::MicroBreakEnd, None),
+            AudioEvent::PlayFocusWarning => self.play_cue(AudioType::FocusWarning, None),
+            AudioEvent::PlayLongBreakWarning => self.play_cue(AudioType::LongBreakWarning, None),
+            AudioEvent::PlayNotification { .. } => {
+                let path = self.lock_state().config.notification_sound.clone();
+                match path {
+                    Some(path) => self.play_file(Path::new(&path), 1.0),
+                    None => Ok(()),
+                }
+            }
+            AudioEvent::SetVolume { level } => {
+                if !level.is_finite() {
+                    return Err(AudioError::InvalidVolume(*level));
+                }
+                self.lock_state().config.volume = level.clamp(0.0, 1.0);
+                self.refresh_ambient_volume();
+                Ok(())
+            }
+            AudioEvent::Mute => {
+                self.lock_state().muted = true;
+                self.backend.stop_all();
+                self.refresh_ambient_volume();
+                Ok(())
+            }
+            AudioEvent::Unmute => {
+                self.lock_state().muted = false;
+                self.refresh_ambient_volume();
+                Ok(())
+            }
+            AudioEvent::ConfigChanged { config } => {
+                if !config.volume.is_finite() {
+                    return Err(AudioError::InvalidVolume(config.volume));
+                }
+                let mut config = config.clone();
+                config.volume = config.volume.clamp(0.0, 1.0);
+                self.lock_state().config = config;
+                // 提示音文件可能已被替换
+                self.lock_cache().clear();
+                self.refresh_ambient_volume();
+                Ok(())
+            }
+        }
+    }
+
+    /// 播放阶段切换提示音
+    ///
+    /// 按audio_configs中的配置决定是否播放、音量和自定义文件；没有自定义文件时使用音频配置中的提示音，
+    /// 都未设置时播放内置合成的默认提示音。
+    pub fn play_cue(&self, cue: AudioType, config: Option<&models::AudioConfig>) -> Result<(), AudioError> {
+        if config.is_some_and(|config| !config.enabled) {
+            return Ok(());
+        }
+
+        let volume = config.map_or(1.0, |config| config.volume);
+        let path = config
+            .and_then(|config| config.file_path.clone())
+            .or_else(|| Self::cue_sound(&self.lock_state().config, cue).clone());
+        match path {
+            Some(path) => self.play_file(Path::new(&path), volume),
+            None => {
+                let clip = self.default_clip(cue);
+                self.play_clip(&clip, volume)
+            }
+        }
+    }
+
+    /// 以指定音量（在全局音量基础上）播放文件；音频被禁用或静音时忽略
+    pub fn play_file(&self, path: &Path, volume: f32) -> Result<(), AudioError> {
+        if self.output_volume().is_none() {
+            return Ok(());
+        }
+        let clip = self.load(path)?;
+        self.play_clip(&clip, volume)
+    }
+
+    /// 更新免打扰设置
+    pub fn set_quiet_hours(&self, quiet_hours: QuietHours) {
+        self.lock_state().quiet_hours = quiet_hours;
+    }
+
+    /// 当前处于免打扰时段时返回处理方式（按本地时间判断）
+    pub fn quiet_mode(&self) -> Option<QuietHoursMode> {
+        self.lock_state().quiet_hours.mode_at(Local::now().naive_local())
+    }
+
+    /// 循环播放背景音（level为背景音自身音量，叠加全局音量和静音状态）
+    pub fn start_ambient(&self, clip: &AudioClip, level: f32) -> Result<(), AudioError> {
+        self.lock_state().ambient_level = Some(level);
+        self.backend.start_loop(clip, self.ambient_output(level))
+    }
+
+    /// 调整背景音自身的音量
+    pub fn set_ambient_level(&self, level: f32) {
+        let mut state = self.lock_state();
+        if state.ambient_level.is_none() {
+            return;
+        }
+        state.ambient_level = Some(level);
+        drop(state);
+        self.backend.set_loop_volume(self.ambient_output(level));
+    }
+
+    /// 暂停或继续背景音
+    pub fn set_ambient_paused(&self, paused: bool) {
+        self.backend.set_loop_paused(paused);
+    }
+
+    /// 停止背景音
+    pub fn stop_ambient(&self) {
+        self.lock_state().ambient_level = None;
+        self.backend.stop_loop();
+    }
+
+    /// 全局音量或静音状态变化后重新计算背景音输出音量
+    fn refresh_ambient_volume(&self) {
+        let level = self.lock_state().ambient_level;
+        if let Some(level) = level {
+            self.backend.set_loop_volume(self.ambient_output(level));
+        }
+    }
+
+    /// 背景音的实际输出音量
+    fn ambient_output(&self, level: f32) -> f32 {
+        self.output_volume().map_or(0.0, |global_volume| (global_volume * level).clamp(0.0, 1.0))
+    }
+
+    /// 以指定音量（在全局音量基础上）播放音频片段；音频被禁用或静音时忽略
+    fn play_clip(&self, clip: &AudioClip, volume: f32) -> Result<(), AudioError> {
+        match self.output_volume() {
+            Some(global_volume) => self.backend.play(clip, (global_volume * volume).clamp(0.0, 1.0)),
+            None => Ok(()),
+        }
+    }
+
+    /// 内置的默认提示音（首次使用时合成）
+    fn default_clip(&self, cue: AudioType) -> AudioClip {
+        self.defaults
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(cue)
+            .or_insert_with(|| default_cue(cue))
+            .clone()
+    }
+
+    /// 提示音类型在音频配置中对应的文件
+    fn cue_sound(config: &AudioConfig, cue: AudioType) -> &Option<String> {
+        match cue {
+            AudioType::FocusStart => &config.focus_start_sound,
+            AudioType::FocusEnd => &config.focus_end_sound,
+            AudioType::LongBreakStart | AudioType::MicroBreakStart => &config.break_start_sound,
+            AudioType::LongBreakEnd | AudioType::MicroBreakEnd => &config.break_end_sound,
+            AudioType::FocusWarning | AudioType::LongBreakWarning => &config.notification_sound,
+        }
+    }
+
+    /// 当前的输出音量；音频被禁用或静音时为None
+    fn output_volume(&self) -> Option<f32> {
+        let state = self.lock_state();
+        (state.config.enabled && !state.muted).then_some(state.config.volume)
+    }
+
+    /// 读取并解码提示音文件（带缓存）
+    fn load(&self, path: &Path) -> Result<AudioClip, AudioError> {
+        if let Some(clip) = self.lock_cache().get(path) {
+            return Ok(clip.clone());
+        }
+        let clip = decode_file(path)?;
+        self.lock_cache().insert(path.to_path_buf(), clip.clone());
+        Ok(clip)
+    }
+
+    fn lock_state(&self) -> std::sync::MutexGuard<'_, PlaybackState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, HashMap<PathBuf, AudioClip>> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl AudioEventHandler for AudioService {
+    fn handle_audio_event(&self, event: &AudioEvent) -> Result<(), String> {
+        self.handle(event).map_err(|e| e.to_string())
+    }
+
+    fn quiet_mode(&self) -> Option<QuietHoursMode> {
+        AudioService::quiet_mode(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use super::*;
+    use crate::models::QuietHourRange;
+    use crate::services::audio::{noise_loop, NoiseColor, RecordingBackend, SYNTH_SAMPLE_RATE};
+    use crate::test_support::wav_bytes;
+
+    fn service_with_cue(dir: &Path) -> (Arc<RecordingBackend>, AudioService) {
+        let path = dir.join("focus_start.wav");
+        std::fs::write(&path, wav_bytes(8000, &[0, 16384, -16384, 0].repeat(2000))).unwrap();
+
+        let backend = Arc::new(RecordingBackend::new());
+        let service = AudioService::new(backend.clone());
+        let config = AudioConfig {
+            focus_start_sound: Some(path.display().to_string()),
+            volume: 0.5,
+            ..AudioConfig::default()
+        };
+        service.handle(&AudioEvent::ConfigChanged { config }).unwrap();
+        (backend, service)
+    }
+
+    #[test]
+    fn decodes_and_plays_configured_cue() {
+        let dir = tempfile::tempdir().unwrap();
+        let (backend, service) = service_with_cue(dir.path());
+
+        service.handle(&AudioEvent::PlayFocusStart).unwrap();
+        // 未配置文件的提示音播放内置合成音
+        service.handle(&AudioEvent::PlayFocusEnd).unwrap();
+
+        let played = backend.played();
+        assert_eq!(played.len(), 2);
+        assert_eq!(played[1].clip.sample_rate, SYNTH_SAMPLE_RATE);
+        assert_eq!(played[0].clip.sample_rate, 8000);
+        assert_eq!(played[0].clip.channels, 1);
+        assert_eq!(played[0].clip.frames(), 8000);
+        assert_eq!(played[0].clip.duration().as_secs(), 1);
+        assert!((played[0].volume - 0.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn volume_and_mute_apply_to_playback() {
+        let dir = tempfile::tempdir().unwrap();
+        let (backend, service) = service_with_cue(dir.path());
+
+        service.handle(&AudioEvent::SetVolume { level: 2.0 }).unwrap();
+        service.handle(&AudioEvent::PlayFocusStart).unwrap();
+        service.handle(&AudioEvent::Mute).unwrap();
+        service.handle(&AudioEvent::PlayFocusStart).unwrap();
+        service.handle(&AudioEvent::Unmute).unwrap();
+        service.handle(&AudioEvent::PlayFocusStart).unwrap();
+
+        let played = backend.played();
+        assert_eq!(played.len(), 2);
+        assert!((played[0].volume - 1.0).abs() < f32::EPSILON);
+        assert_eq!(backend.stop_count(), 1);
+        assert!(service.handle(&AudioEvent::SetVolume { level: f32::NAN }).is_err());
+    }
+
+    #[test]
+    fn default_cues_are_distinct() {
+        let cues = [
+            AudioType::FocusStart,
+            AudioType::FocusEnd,
+            AudioType::LongBreakStart,
+            AudioType::LongBreakEnd,
+            AudioType::MicroBreakStart,
+            AudioType::MicroBreakEnd,
+            AudioType::FocusWarning,
+            AudioType::LongBreakWarning,
+        ];
+        let clips: Vec<AudioClip> = cues.iter().map(|cue| default_cue(*cue)).collect();
+
+        for (i, clip) in clips.iter().enumerate() {
+            assert!(clip.duration().as_secs_f32() > 0.1 && clip.duration().as_secs_f32() < 2.0);
+            assert!((clip.peak_dbfs() + 6.0).abs() < 0.1);
+            for other in &clips[i + 1..] {
+                assert_ne!(clip.samples, other.samples);
+            }
+        }
+    }
+
+    #[test]
+    fn ambient_loop_follows_global_volume_and_mute() {
+        let backend = Arc::new(RecordingBackend::new());
+        let service = AudioService::new(backend.clone());
+        let clip = noise_loop(NoiseColor::Pink);
+        assert!((clip.peak_dbfs() + 6.0).abs() < 0.1);
+
+        service.handle(&AudioEvent::SetVolume { level: 0.5 }).unwrap();
+        service.start_ambient(&clip, 0.4).unwrap();
+        assert!(backend.ambient().clip.is_some_and(|looped| looped.samples == clip.samples));
+        assert!((backend.ambient().volume - 0.2).abs() < 1e-6);
+
+        service.set_ambient_level(0.8);
+        assert!((backend.ambient().volume - 0.4).abs() < 1e-6);
+        service.handle(&AudioEvent::Mute).unwrap();
+        assert_eq!(backend.ambient().volume, 0.0);
+        service.handle(&AudioEvent::Unmute).unwrap();
+        assert!((backend.ambient().volume - 0.4).abs() < 1e-6);
+
+        service.set_ambient_paused(true);
+        assert!(backend.ambient().paused);
+        service.stop_ambient();
+        assert!(backend.ambient().clip.is_none());
+        // 停止后调整音量不再影响输出
+        service.set_ambient_level(1.0);
+        assert_eq!(backend.ambient().volume, 0.0);
+    }
+
+    #[test]
+    fn quiet_hours_mode_follows_ranges() {
+        let range = |weekday, start_time: &str, end_time: &str| QuietHourRange {
+            weekday,
+            start_time: start_time.to_string(),
+            end_time: end_time.to_string(),
+        };
+        // 周五22:00到次日07:00
+        let overnight = QuietHours {
+            enabled: true,
+            mode: QuietHoursMode::VisualOnly,
+            ranges: vec![range(4, "22:00", "07:00")],
+            updated_at: None,
+        };
+        let at = |date: &str| NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M").unwrap();
+        assert_eq!(overnight.mode_at(at("2026-10-16 23:30")), Some(QuietHoursMode::VisualOnly));
+        assert_eq!(overnight.mode_at(at("2026-10-17 06:59")), Some(QuietHoursMode::VisualOnly));
+        assert_eq!(overnight.mode_at(at("2026-10-17 07:00")), None);
+        assert_eq!(overnight.mode_at(at("2026-10-16 21:59")), None);
+        assert_eq!(overnight.mode_at(at("2026-10-15 23:30")), None);
+        assert_eq!(QuietHours { enabled: false, ..overnight }.mode_at(at("2026-10-16 23:30")), None);
+
+        // 全天免打扰：只报告处理方式，是否播放由发送提示音的一方决定
+        let backend = Arc::new(RecordingBackend::new());
+        let service = AudioService::new(backend.clone());
+        service.set_quiet_hours(QuietHours {
+            enabled: true,
+            mode: QuietHoursMode::Mute,
+            ranges: (0..7).flat_map(|day| [range(day, "00:00", "12:00"), range(day, "12:00", "00:00")]).collect(),
+            updated_at: None,
+        });
+        assert_eq!(service.quiet_mode(), Some(QuietHoursMode::Mute));
+        service.handle(&AudioEvent::PlayFocusStart).unwrap();
+        assert_eq!(backend.played().len(), 1);
+
+        service.set_quiet_hours(QuietHours::default());
+        assert_eq!(service.quiet_mode(), None);
+    }
+
+    #[test]
+    fn rejects_unsupported_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cue.txt");
+        std::fs::write(&path, b"not audio").unwrap();
+        let service = AudioService::new(Arc::new(RecordingBackend::new()));
+
+        assert!(matches!(service.play_file(&path, 1.0), Err(AudioError::UnsupportedFormat(_))));
+        let corrupt = dir.path().join("cue.wav");
+        std::fs::write(&corrupt, b"RIFF0000WAVE").unwrap();
+        assert!(service.play_file(&corrupt, 1.0).is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use rodio::buffer::SamplesBuffer;
+use rodio::{OutputStream, OutputStreamHandle, Sink, Source};
+use serde::{Deserialize, Serialize};
+
+use super::{AudioClip, AudioError};
+
+/// 选择输出后端的环境变量（取值：system / null）
+pub const AUDIO_BACKEND_ENV: &str = "FOCUS_DAILY_AUDIO_BACKEND";
+
+/// 音频输出后端
+pub trait AudioBackend: Send + Sync {
+    /// 以指定音量（0.0–1.0）播放音频片段，不等待播放结束
+    fn play(&self, clip: &AudioClip, volume: f32) -> Result<(), AudioError>;
+
+    /// 停止所有正在播放的提示音（不影响背景音）
+    fn stop_all(&self);
+
+    /// 循环播放背景音，替换正在播放的背景音
+    fn start_loop(&self, clip: &AudioClip, volume: f32) -> Result<(), AudioError>;
+
+    /// 调整背景音音量
+    fn set_loop_volume(&self, volume: f32);
+
+    /// 暂停或继续背景音
+    fn set_loop_paused(&self, paused: bool);
+
+    /// 停止背景音
+    fn stop_loop(&self);
+}
+
+/// 输出后端类型
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AudioBackendKind {
+    System, // 系统默认输出设备
+    Null,   // 不输出声音
+}
+
+impl AudioBackendKind {
+    /// 从环境变量读取后端类型，未设置时使用系统输出
+    pub fn from_env() -> Self {
+        match std::env::var(AUDIO_BACKEND_ENV).as_deref() {
+            Ok("null") => AudioBackendKind::Null,
+            _ => AudioBackendKind::System,
+        }
+    }
+
+    /// 创建后端；系统输出设备不可用时退化为静音后端
+    pub fn create(self) -> Arc<dyn AudioBackend> {
+        match self {
+            AudioBackendKind::System => match SystemBackend::new() {
+                Ok(backend) => Arc::new(backend),
+                Err(e) => {
+                    eprintln!("Audio output unavailable, falling back to null backend: {}", e);
+                    Arc::new(NullBackend)
+                }
+            },
+            AudioBackendKind::Null => Arc::new(NullBackend),
+        }
+    }
+}
+
+/// 静音后端：丢弃所有声音
+pub struct NullBackend;
+
+impl AudioBackend for NullBackend {
+    fn play(&self, _clip: &AudioClip, _volume: f32) -> Result<(), AudioError> {
+        Ok(())
+    }
+
+    fn stop_all(&self) {}
+
+    fn start_loop(&self, _clip: &AudioClip, _volume: f32) -> Result<(), AudioError> {
+        Ok(())
+    }
+
+    fn set_loop_volume(&self, _volume: f32) {}
+
+    fn set_loop_paused(&self, _paused: bool) {}
+
+    fn stop_loop(&self) {}
+}
+
+/// 一次播放记录
+#[cfg(test)]
+#[derive(Debug, Clone)]
+pub struct PlayedClip {
+    pub clip: AudioClip,
+    pub volume: f32,
+}
+
+/// 背景音的播放状态记录
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+pub struct LoopState {
+    pub clip: Option<AudioClip>,
+    pub volume: f32,
+    pub paused: bool,
+}
+
+/// 记录播放请求而不输出声音的后端（用于测试）
+#[cfg(test)]
+#[derive(Default)]
+pub struct RecordingBackend {
+    played: Mutex<Vec<PlayedClip>>,
+    stop_count: Mutex<usize>,
+    ambient: Mutex<LoopState>,
+}
+
+#[cfg(test)]
+impl RecordingBackend {
+    /// 创建新的记录后端
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 已播放的片段（按播放顺序）
+    pub fn played(&self) -> Vec<PlayedClip> {
+        self.played.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// stop_all被调用的次数
+    pub fn stop_count(&self) -> usize {
+        *self.stop_count.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 当前背景音状态
+    pub fn ambient(&self) -> LoopState {
+        self.ambient.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+#[cfg(test)]
+impl AudioBackend for RecordingBackend {
+    fn play(&self, clip: &AudioClip, volume: f32) -> Result<(), AudioError> {
+        self.played
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(PlayedClip { clip: clip.clone(), volume });
+        Ok(())
+    }
+
+    fn stop_all(&self) {
+        *self.stop_count.lock().unwrap_or_else(|e| e.into_inner()) += 1;
+    }
+
+    fn start_loop(&self, clip: &AudioClip, volume: f32) -> Result<(), AudioError> {
+        *self.ambient.lock().unwrap_or_else(|e| e.into_inner()) = LoopState {
+            clip: Some(clip.clone()),
+            volume,
+            paused: false,
+        };
+        Ok(())
+    }
+
+    fn set_loop_volume(&self, volume: f32) {
+        self.ambient.lock().unwrap_or_else(|e| e.into_inner()).volume = volume;
+    }
+
+    fn set_loop_paused(&self, paused: bool) {
+        self.ambient.lock().unwrap_or_else(|e| e.into_inner()).paused = paused;
+    }
+
+    fn stop_loop(&self) {
+        *self.ambient.lock().unwrap_or_else(|e| e.into_inner()) = LoopState::default();
+    }
+}
+
+/// 系统默认输出设备
+///
+/// 输出流不能跨线程移动，由专用线程持有直到后端被释放。
+pub struct SystemBackend {
+    handle: OutputStreamHandle,
+    sinks: Mutex<Vec<Sink>>,
+    ambient: Mutex<Option<Sink>>,
+    _shutdown: mpsc::Sender<()>,
+}
+
+impl SystemBackend {
+    /// 打开系统默认输出设备
+    pub fn new() -> Result<Self, AudioError> {
+        let (handle_tx, handle_rx) = mpsc::channel();
+        let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>();
+
+        thread::Builder::new()
+            .name("audio-output".to_string())
+            .spawn(move || match OutputStream::try_default() {
+                Ok((_stream, handle)) => {
+                    let _ = handle_tx.send(Ok(handle));
+                    // 发送端被释放时返回，随后释放输出流
+                    let _ = shutdown_rx.recv();
+                }
+                Err(e) => {
+                    let _ = handle_tx.send(Err(AudioError::Backend(e.to_string())));
+                }
+            })?;
+
+        let handle = handle_rx
+            .recv()
+            .map_err(|_| AudioError::Backend("音频输出线程意外退出".to_string()))??;
+
+        Ok(Self {
+            handle,
+            sinks: Mutex::new(Vec::new()),
+            ambient: Mutex::new(None),
+            _shutdown: shutdown_tx,
+        })
+    }
+}
+
+impl AudioBackend for SystemBackend {
+    fn play(&self, clip: &AudioClip, volume: f32) -> Result<(), AudioError> {
+        let sink = Sink::try_new(&self.handle).map_err(|e| AudioError::Backend(e.to_string()))?;
+        sink.set_volume(volume);
+        sink.append(SamplesBuffer::new(clip.channels, clip.sample_rate, clip.samples.to_vec()));
+
+        let mut sinks = self.sinks.lock().unwrap_or_else(|e| e.into_inner());
+        sinks.retain(|sink| !sink.empty());
+        sinks.push(sink);
+        Ok(())
+    }
+
+    fn stop_all(&self) {
+        let mut sinks = self.sinks.lock().unwrap_or_else(|e| e.into_inner());
+        for sink in sinks.drain(..) {
+            sink.stop();
+        }
+    }
+
+    fn start_loop(&self, clip: &AudioClip, volume: f32) -> Result<(), AudioError> {
+        let sink = Sink::try_new(&self.handle).map_err(|e| AudioError::Backend(e.to_string()))?;
+        sink.set_volume(volume);
+        sink.append(SamplesBuffer::new(clip.channels, clip.sample_rate, clip.samples.to_vec()).repeat_infinite());
+
+        let previous = self.ambient.lock().unwrap_or_else(|e| e.into_inner()).replace(sink);
+        if let Some(previous) = previous {
+            previous.stop();
+        }
+        Ok(())
+    }
+
+    fn set_loop_volume(&self, volume: f32) {
+        if let Some(sink) = self.ambient.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            sink.set_volume(volume);
+        }
+    }
+
+    fn set_loop_paused(&self, paused: bool) {
+        if let Some(sink) = self.ambient.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            if paused {
+                sink.pause();
+            } else {
+                sink.play();
+            }
+        }
+    }
+
+    fn stop_loop(&self) {
+        if let Some(sink) = self.ambient.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            sink.stop();
+        }
+    }
+}
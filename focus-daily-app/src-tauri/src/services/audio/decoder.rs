@@ -0,0 +1,209 @@
+use std::fs::File;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CodecParameters, DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::{MediaSource, MediaSourceStream};
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use super::AudioError;
+
+/// 支持的提示音文件扩展名
+pub const SUPPORTED_EXTENSIONS: [&str; 3] = ["wav", "ogg", "mp3"];
+
+/// 解码后的音频片段（交错排列的f32 PCM样本）
+#[derive(Debug, Clone)]
+pub struct AudioClip {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub samples: Arc<[f32]>,
+}
+
+impl AudioClip {
+    /// 由PCM样本创建音频片段
+    pub fn new(sample_rate: u32, channels: u16, samples: Vec<f32>) -> Self {
+        Self {
+            sample_rate,
+            channels,
+            samples: samples.into(),
+        }
+    }
+
+    /// 每个声道的帧数
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels.max(1) as usize
+    }
+
+    /// 播放时长
+    pub fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.frames() as f64 / self.sample_rate.max(1) as f64)
+    }
+
+    /// 峰值电平（dBFS，静音时为负无穷）
+    pub fn peak_dbfs(&self) -> f32 {
+        let peak = self.samples.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+        20.0 * peak.log10()
+    }
+
+    /// 平均响度（RMS，dBFS，静音时为负无穷）
+    pub fn rms_dbfs(&self) -> f32 {
+        if self.samples.is_empty() {
+            return f32::NEG_INFINITY;
+        }
+        let sum: f64 = self.samples.iter().map(|sample| (*sample as f64).powi(2)).sum();
+        let rms = (sum / self.samples.len() as f64).sqrt();
+        20.0 * rms.log10() as f32
+    }
+}
+
+/// 解码WAV/OGG/MP3文件
+pub fn decode_file(path: &Path) -> Result<AudioClip, AudioError> {
+    open_and_decode(path, None)
+}
+
+/// 解码WAV/OGG/MP3文件，超过最大时长时不解码完整个文件就返回错误
+///
+/// 文件头声明了帧数时在解码前检查，否则在解码超过最大时长时停止。
+pub fn decode_file_within(path: &Path, max_duration: Duration) -> Result<AudioClip, AudioError> {
+    open_and_decode(path, Some(max_duration))
+}
+
+fn open_and_decode(path: &Path, max_duration: Option<Duration>) -> Result<AudioClip, AudioError> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    if !SUPPORTED_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(AudioError::UnsupportedFormat(path.display().to_string()));
+    }
+
+    let file = File::open(path)?;
+    decode_source(Box::new(file), &extension, max_duration)
+}
+
+fn decode_source(
+    source: Box<dyn MediaSource>,
+    extension: &str,
+    max_duration: Option<Duration>,
+) -> Result<AudioClip, AudioError> {
+    let stream = MediaSourceStream::new(source, Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension(extension);
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| AudioError::UnsupportedFormat(e.to_string()))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| AudioError::Decode("没有可播放的音轨".to_string()))?;
+    let track_id = track.id;
+
+    let too_long = |seconds: f64| max_duration.is_some_and(|max| seconds > max.as_secs_f64());
+    let too_long_error = || {
+        let max_seconds = max_duration.map_or(0, |max| max.as_secs());
+        AudioError::InvalidSound(format!("时长不能超过{}秒", max_seconds))
+    };
+    if declared_seconds(&track.codec_params).is_some_and(too_long) {
+        return Err(too_long_error());
+    }
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| AudioError::UnsupportedFormat(e.to_string()))?;
+
+    let mut samples = Vec::new();
+    let mut spec = None;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            // 读到文件末尾
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(SymphoniaError::ResetRequired) => break,
+            Err(e) => return Err(AudioError::Decode(e.to_string())),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                let decoded_spec = *decoded.spec();
+                let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, decoded_spec);
+                buffer.copy_interleaved_ref(decoded);
+                samples.extend_from_slice(buffer.samples());
+                spec.get_or_insert(decoded_spec);
+
+                let frames = samples.len() / decoded_spec.channels.count().max(1);
+                if too_long(frames as f64 / decoded_spec.rate.max(1) as f64) {
+                    return Err(too_long_error());
+                }
+            }
+            // 跳过损坏的数据包
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(AudioError::Decode(e.to_string())),
+        }
+    }
+
+    let spec = spec.ok_or_else(|| AudioError::Decode("音频文件不包含任何样本".to_string()))?;
+    Ok(AudioClip::new(spec.rate, spec.channels.count() as u16, samples))
+}
+
+/// 文件头声明的音轨时长（秒），未声明帧数时为None
+fn declared_seconds(params: &CodecParameters) -> Option<f64> {
+    let frames = params.n_frames?;
+    match (params.time_base, params.sample_rate) {
+        (Some(time_base), _) => {
+            let time = time_base.calc_time(frames);
+            Some(time.seconds as f64 + time.frac)
+        }
+        (None, Some(rate)) if rate > 0 => Some(frames as f64 / rate as f64),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::test_support::wav_bytes;
+
+    fn decode_wav(bytes: Vec<u8>, max_duration: Option<Duration>) -> Result<AudioClip, AudioError> {
+        decode_source(Box::new(Cursor::new(bytes)), "wav", max_duration)
+    }
+
+    #[test]
+    fn decodes_clip_within_max_duration() {
+        let clip = decode_wav(wav_bytes(8000, &[1000; 16000]), Some(Duration::from_secs(3))).unwrap();
+        assert_eq!(clip.frames(), 16000);
+        assert_eq!(clip.duration(), Duration::from_secs(2));
+    }
+
+    #[test]
+    fn rejects_declared_duration_before_decoding() {
+        // 文件头声明31秒，实际只有1秒数据：解码前按声明的帧数拒绝
+        let mut bytes = wav_bytes(8000, &[1000; 8000]);
+        let declared_len = 31u32 * 8000 * 2;
+        bytes[4..8].copy_from_slice(&(36 + declared_len).to_le_bytes());
+        bytes[40..44].copy_from_slice(&declared_len.to_le_bytes());
+
+        let result = decode_wav(bytes, Some(Duration::from_secs(30)));
+        assert!(matches!(result, Err(AudioError::InvalidSound(_))), "超过时长上限时应拒绝: {:?}", result);
+    }
+
+    #[test]
+    fn decodes_long_clip_without_limit() {
+        let clip = decode_wav(wav_bytes(8000, &[1000; 8000 * 31]), None).unwrap();
+        assert_eq!(clip.duration(), Duration::from_secs(31));
+    }
+}
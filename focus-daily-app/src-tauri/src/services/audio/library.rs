@@ -0,0 +1,220 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use uuid::Uuid;
+
+use crate::models::CustomSound;
+use crate::services::storage::{NewCustomSound, SoundStore};
+use super::{decode_file_within, AudioError, SUPPORTED_EXTENSIONS};
+
+/// 音效文件大小上限（字节）
+pub const MAX_SOUND_BYTES: u64 = 5 * 1024 * 1024;
+/// 音效时长上限（秒）
+pub const MAX_SOUND_SECONDS: u64 = 30;
+/// 峰值低于该电平视为无声（dBFS）
+const MIN_PEAK_DBFS: f32 = -45.0;
+/// 平均响度高于该电平视为过响（dBFS）
+const MAX_RMS_DBFS: f32 = -3.0;
+
+/// 音效文件的校验结果
+struct SoundAnalysis {
+    format: String,
+    size_bytes: u64,
+    duration_ms: u64,
+    loudness_db: f32,
+    peak_db: f32,
+}
+
+/// 自定义音效库：把用户的音效文件复制到应用目录中统一管理
+pub struct SoundLibrary {
+    store: SoundStore,
+    dir: PathBuf,
+}
+
+impl SoundLibrary {
+    /// 创建音效库（dir为应用管理的音效目录）
+    pub fn new(store: SoundStore, dir: PathBuf) -> Self {
+        Self { store, dir }
+    }
+
+    /// 校验并导入音效文件；名称为空时使用文件名
+    pub async fn import(&self, source: &Path, name: Option<String>) -> Result<CustomSound, AudioError> {
+        let name = name
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .or_else(|| source.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+            .ok_or_else(|| AudioError::InvalidSound("音效名称不能为空".to_string()))?;
+
+        // 解码会读取整个文件，放到阻塞线程中执行
+        let source = source.to_path_buf();
+        let dir = self.dir.clone();
+        let id = Uuid::new_v4().to_string();
+        let target_id = id.clone();
+        let (analysis, target) = tokio::task::spawn_blocking(move || -> Result<_, AudioError> {
+            let analysis = Self::analyze(&source)?;
+            std::fs::create_dir_all(&dir)?;
+            let target = dir.join(format!("{}.{}", target_id, analysis.format));
+            std::fs::copy(&source, &target)?;
+            Ok((analysis, target))
+        })
+        .await
+        .map_err(|e| AudioError::Backend(format!("音效导入任务失败: {}", e)))??;
+
+        let sound = NewCustomSound {
+            id,
+            name,
+            file_path: target.display().to_string(),
+            format: analysis.format,
+            size_bytes: analysis.size_bytes,
+            duration_ms: analysis.duration_ms,
+            loudness_db: analysis.loudness_db,
+            peak_db: analysis.peak_db,
+        };
+        match self.store.insert_sound(&sound).await {
+            Ok(sound) => Ok(sound),
+            Err(e) => {
+                let _ = std::fs::remove_file(&target);
+                Err(e.into())
+            }
+        }
+    }
+
+    /// 列出音效库中的音效
+    pub async fn list(&self) -> Result<Vec<CustomSound>, AudioError> {
+        Ok(self.store.list_sounds().await?)
+    }
+
+    /// 获取单个音效
+    pub async fn get(&self, sound_id: &str) -> Result<CustomSound, AudioError> {
+        Ok(self.store.get_sound(sound_id).await?)
+    }
+
+    /// 删除音效及其文件；仍被提示音或背景音使用时拒绝删除
+    pub async fn delete(&self, sound_id: &str) -> Result<(), AudioError> {
+        let sound = self.store.delete_sound(sound_id).await?;
+        match std::fs::remove_file(&sound.file_path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// 校验格式、大小、时长和响度
+    fn analyze(source: &Path) -> Result<SoundAnalysis, AudioError> {
+        let format = source
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase)
+            .filter(|ext| SUPPORTED_EXTENSIONS.contains(&ext.as_str()))
+            .ok_or_else(|| AudioError::UnsupportedFormat(format!("仅支持 {}", SUPPORTED_EXTENSIONS.join("/"))))?;
+
+        let size_bytes = std::fs::metadata(source)?.len();
+        if size_bytes > MAX_SOUND_BYTES {
+            return Err(AudioError::InvalidSound(format!("文件不能超过{}MB", MAX_SOUND_BYTES / 1024 / 1024)));
+        }
+
+        // 超过最大时长的文件在解码完成前就被拒绝
+        let clip = decode_file_within(source, Duration::from_secs(MAX_SOUND_SECONDS))?;
+        let duration = clip.duration();
+
+        let peak_db = clip.peak_dbfs();
+        let loudness_db = clip.rms_dbfs();
+        if peak_db < MIN_PEAK_DBFS {
+            return Err(AudioError::InvalidSound("音量过低或没有声音".to_string()));
+        }
+        if loudness_db > MAX_RMS_DBFS {
+            return Err(AudioError::InvalidSound("音量过高，请降低响度后再导入".to_string()));
+        }
+
+        Ok(SoundAnalysis {
+            format,
+            size_bytes,
+            duration_ms: duration.as_millis() as u64,
+            loudness_db,
+            peak_db,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{migrated_pool, wav_bytes};
+
+    /// 交替正负采样的方波（幅度决定响度）
+    fn square_wave(sample_rate: u32, seconds: u32, amplitude: i16) -> Vec<u8> {
+        let samples: Vec<i16> = (0..sample_rate * seconds)
+            .map(|i| if i % 2 == 0 { amplitude } else { -amplitude })
+            .collect();
+        wav_bytes(sample_rate, &samples)
+    }
+
+    fn write_source(dir: &tempfile::TempDir, file_name: &str, bytes: &[u8]) -> PathBuf {
+        let path = dir.path().join(file_name);
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    fn library_files(dir: &Path) -> usize {
+        std::fs::read_dir(dir).map_or(0, |entries| entries.count())
+    }
+
+    #[test]
+    fn analyze_rejects_invalid_sounds() {
+        let dir = tempfile::tempdir().unwrap();
+        let rejected = |file_name: &str, bytes: &[u8]| SoundLibrary::analyze(&write_source(&dir, file_name, bytes)).err();
+
+        assert!(matches!(rejected("bell.txt", &square_wave(8000, 1, 8000)), Some(AudioError::UnsupportedFormat(_))));
+        let oversized = vec![0u8; MAX_SOUND_BYTES as usize + 1];
+        assert!(matches!(rejected("large.wav", &oversized), Some(AudioError::InvalidSound(_))));
+        let too_long = square_wave(8000, MAX_SOUND_SECONDS as u32 + 1, 8000);
+        assert!(matches!(rejected("long.wav", &too_long), Some(AudioError::InvalidSound(_))));
+        assert!(matches!(rejected("silent.wav", &square_wave(8000, 1, 0)), Some(AudioError::InvalidSound(_))));
+        assert!(matches!(rejected("loud.wav", &square_wave(8000, 1, i16::MAX)), Some(AudioError::InvalidSound(_))));
+    }
+
+    #[test]
+    fn analyze_measures_valid_sound() {
+        let dir = tempfile::tempdir().unwrap();
+        let analysis = SoundLibrary::analyze(&write_source(&dir, "Bell.WAV", &square_wave(8000, 2, 8000))).unwrap();
+
+        assert_eq!(analysis.format, "wav");
+        assert_eq!(analysis.duration_ms, 2000);
+        assert!((analysis.peak_db - analysis.loudness_db).abs() < 0.1);
+        assert!(analysis.peak_db < -11.0 && analysis.peak_db > -13.0);
+    }
+
+    #[tokio::test]
+    async fn import_copies_file_and_delete_removes_it() {
+        let (_db_dir, pool) = migrated_pool().await;
+        let sources = tempfile::tempdir().unwrap();
+        let library_dir = tempfile::tempdir().unwrap();
+        let library = SoundLibrary::new(SoundStore::new(pool), library_dir.path().to_path_buf());
+
+        let source = write_source(&sources, "bell.wav", &square_wave(8000, 1, 8000));
+        let sound = library.import(&source, None).await.unwrap();
+        assert_eq!(sound.name, "bell");
+        assert!(Path::new(&sound.file_path).starts_with(library_dir.path()));
+        assert_eq!(library_files(library_dir.path()), 1);
+
+        library.delete(&sound.id).await.unwrap();
+        assert_eq!(library_files(library_dir.path()), 0);
+        assert!(library.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_insert_removes_copied_file() {
+        let (_db_dir, pool) = migrated_pool().await;
+        let sources = tempfile::tempdir().unwrap();
+        let library_dir = tempfile::tempdir().unwrap();
+        // 让保存音效记录失败
+        sqlx::query("DROP TABLE custom_sounds").execute(&pool).await.unwrap();
+        let library = SoundLibrary::new(SoundStore::new(pool), library_dir.path().to_path_buf());
+
+        let source = write_source(&sources, "bell.wav", &square_wave(8000, 1, 8000));
+        let result = library.import(&source, Some("铃声".to_string())).await;
+
+        assert!(matches!(result, Err(AudioError::Storage(_))));
+        assert_eq!(library_files(library_dir.path()), 0);
+        assert!(source.exists());
+    }
+}
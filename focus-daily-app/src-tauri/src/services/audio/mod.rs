@@ -0,0 +1,36 @@
+pub mod decoder;
+pub mod backend;
+pub mod audio_service;
+pub mod library;
+pub mod synth;
+pub mod ambient;
+
+pub use decoder::*;
+pub use backend::*;
+pub use audio_service::*;
+pub use library::*;
+pub use synth::*;
+pub use ambient::*;
+
+use thiserror::Error;
+
+use crate::services::storage::StorageError;
+
+/// 音频服务错误
+#[derive(Debug, Error)]
+pub enum AudioError {
+    #[error("读取音频文件失败: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("不支持的音频格式: {0}")]
+    UnsupportedFormat(String),
+    #[error("音频解码失败: {0}")]
+    Decode(String),
+    #[error("音频输出失败: {0}")]
+    Backend(String),
+    #[error("无效的音量: {0}")]
+    InvalidVolume(f32),
+    #[error("音效文件不符合要求: {0}")]
+    InvalidSound(String),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
@@ -0,0 +1,178 @@
+use std::f32::consts::TAU;
+
+use rand::Rng;
+
+use crate::models::AudioType;
+use super::AudioClip;
+
+/// 合成提示音的采样率
+pub const SYNTH_SAMPLE_RATE: u32 = 44_100;
+/// 合成提示音的峰值电平（约-6 dBFS）
+const PEAK_LEVEL: f32 = 0.5;
+
+/// 单个音符：起始时间、频率、时长与衰减时间常数（秒）
+struct Note {
+    start: f32,
+    frequency: f32,
+    duration: f32,
+    decay: f32,
+}
+
+const fn note(start: f32, frequency: f32, duration: f32, decay: f32) -> Note {
+    Note { start, frequency, duration, decay }
+}
+
+// 音高（Hz）
+const E4: f32 = 329.63;
+const A4: f32 = 440.00;
+const C5: f32 = 523.25;
+const E5: f32 = 659.25;
+const G5: f32 = 783.99;
+const A5: f32 = 880.00;
+const C6: f32 = 1046.50;
+
+/// 各提示音的音符序列：开始类上行、结束类下行，休息类音色更低更柔和，预警为短促重复音
+fn pattern(cue: AudioType) -> Vec<Note> {
+    match cue {
+        AudioType::FocusStart => vec![
+            note(0.00, C5, 0.35, 0.12),
+            note(0.12, E5, 0.35, 0.12),
+            note(0.24, G5, 0.60, 0.20),
+        ],
+        AudioType::FocusEnd => vec![
+            note(0.00, G5, 0.35, 0.12),
+            note(0.12, E5, 0.35, 0.12),
+            note(0.24, C5, 0.80, 0.30),
+        ],
+        AudioType::LongBreakStart => vec![
+            note(0.00, E4, 0.90, 0.35),
+            note(0.30, A4, 1.20, 0.45),
+        ],
+        AudioType::LongBreakEnd => vec![
+            note(0.00, A4, 0.30, 0.10),
+            note(0.10, E5, 0.30, 0.10),
+            note(0.20, A5, 0.30, 0.10),
+            note(0.30, C6, 0.70, 0.25),
+        ],
+        AudioType::MicroBreakStart => vec![note(0.00, A5, 0.60, 0.18)],
+        AudioType::MicroBreakEnd => vec![
+            note(0.00, A5, 0.25, 0.08),
+            note(0.18, A5, 0.50, 0.15),
+        ],
+        AudioType::FocusWarning => vec![
+            note(0.00, A5, 0.12, 0.05),
+            note(0.20, A5, 0.12, 0.05),
+        ],
+        AudioType::LongBreakWarning => vec![
+            note(0.00, A4, 0.15, 0.06),
+            note(0.22, A4, 0.15, 0.06),
+            note(0.44, A4, 0.15, 0.06),
+        ],
+    }
+}
+
+/// 合成提示音类型对应的默认提示音（单声道PCM）
+pub fn default_cue(cue: AudioType) -> AudioClip {
+    let notes = pattern(cue);
+    let length = notes.iter().map(|note| note.start + note.duration).fold(0.0, f32::max);
+    let rate = SYNTH_SAMPLE_RATE as f32;
+    let mut samples = vec![0.0f32; (length * rate).ceil() as usize];
+
+    for note in &notes {
+        let offset = (note.start * rate) as usize;
+        let count = (note.duration * rate) as usize;
+        for (i, sample) in samples[offset..].iter_mut().take(count).enumerate() {
+            *sample += chime(note, i as f32 / rate);
+        }
+    }
+
+    // 归一化到统一的峰值电平
+    let peak = samples.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+    if peak > 0.0 {
+        let gain = PEAK_LEVEL / peak;
+        samples.iter_mut().for_each(|sample| *sample *= gain);
+    }
+
+    AudioClip::new(SYNTH_SAMPLE_RATE, 1, samples)
+}
+
+/// 钟声音色：基频加少量泛音，快速起音后指数衰减，结尾淡出避免爆音
+fn chime(note: &Note, t: f32) -> f32 {
+    let attack = (t / 0.005).min(1.0);
+    let release = ((note.duration - t) / 0.02).clamp(0.0, 1.0);
+    let envelope = attack * release * (-t / note.decay).exp();
+    let tone = (TAU * note.frequency * t).sin()
+        + 0.3 * (TAU * note.frequency * 2.0 * t).sin()
+        + 0.1 * (TAU * note.frequency * 3.0 * t).sin();
+    tone * envelope
+}
+
+/// 背景噪音的颜色
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseColor {
+    White,
+    Pink,
+    Brown,
+}
+
+/// 背景噪音循环片段的时长（秒）
+const NOISE_LOOP_SECONDS: f32 = 10.0;
+/// 循环首尾交叉淡化的时长（秒），避免循环接缝处的爆音
+const NOISE_CROSSFADE_SECONDS: f32 = 0.5;
+
+/// 合成可无缝循环播放的背景噪音（单声道PCM）
+pub fn noise_loop(color: NoiseColor) -> AudioClip {
+    let rate = SYNTH_SAMPLE_RATE as f32;
+    let crossfade = (NOISE_CROSSFADE_SECONDS * rate) as usize;
+    let length = (NOISE_LOOP_SECONDS * rate) as usize;
+    let mut rng = rand::thread_rng();
+    let mut white = || rng.gen_range(-1.0f32..1.0);
+
+    let mut samples: Vec<f32> = match color {
+        NoiseColor::White => (0..length + crossfade).map(|_| white()).collect(),
+        NoiseColor::Pink => {
+            // Paul Kellet的粉红噪音滤波器
+            let mut b = [0.0f32; 7];
+            (0..length + crossfade)
+                .map(|_| {
+                    let w = white();
+                    b[0] = 0.99886 * b[0] + w * 0.0555179;
+                    b[1] = 0.99332 * b[1] + w * 0.0750759;
+                    b[2] = 0.96900 * b[2] + w * 0.153852;
+                    b[3] = 0.86650 * b[3] + w * 0.3104856;
+                    b[4] = 0.55000 * b[4] + w * 0.5329522;
+                    b[5] = -0.7616 * b[5] - w * 0.0168980;
+                    let pink = b.iter().sum::<f32>() + w * 0.5362;
+                    b[6] = w * 0.115926;
+                    pink
+                })
+                .collect()
+        }
+        NoiseColor::Brown => {
+            // 带泄漏的积分，避免直流漂移
+            let mut last = 0.0f32;
+            (0..length + crossfade)
+                .map(|_| {
+                    last = (last + 0.02 * white()) / 1.02;
+                    last
+                })
+                .collect()
+        }
+    };
+
+    // 把多出的尾部淡入到开头，使片段首尾相接
+    let (body, tail) = samples.split_at_mut(length);
+    for (i, (head, tail)) in body.iter_mut().zip(tail.iter()).enumerate() {
+        let fade = i as f32 / crossfade as f32;
+        *head = *head * fade + *tail * (1.0 - fade);
+    }
+    samples.truncate(length);
+
+    let peak = samples.iter().fold(0.0f32, |peak, sample| peak.max(sample.abs()));
+    if peak > 0.0 {
+        let gain = PEAK_LEVEL / peak;
+        samples.iter_mut().for_each(|sample| *sample *= gain);
+    }
+
+    AudioClip::new(SYNTH_SAMPLE_RATE, 1, samples)
+}
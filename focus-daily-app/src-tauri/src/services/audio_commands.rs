@@ -0,0 +1,314 @@
+use std::path::Path;
+use sqlx::sqlite::SqlitePool;
+use tauri::{AppHandle, Manager, State};
+
+use crate::models::{
+    AmbientSettings, AudioConfig, AudioType, CustomSound, QuietHours, QuietHoursMode, UpdateAmbientSettings,
+    UpdateAudioConfig, UpdateQuietHours,
+};
+use crate::services::audio::SoundLibrary;
+use crate::services::storage::{AmbientStore, AudioConfigStore, QuietHoursStore, SoundStore, DEFAULT_PROFILE_ID};
+use crate::services::timer_commands::{AudioServiceState, DatabaseState, TimerManagerState};
+
+/// 音效库在应用数据目录中的子目录
+const SOUND_LIBRARY_DIR: &str = "sounds";
+
+/// 列出全部提示音配置
+#[tauri::command]
+pub async fn list_audio_cue_configs(
+    database: State<'_, DatabaseState>,
+) -> Result<Vec<AudioConfig>, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        AudioConfigStore::new(pool.clone())
+            .list_configs()
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 更新提示音配置（启用状态、音量、预警提前时间）
+#[tauri::command]
+pub async fn update_audio_cue_config(
+    database: State<'_, DatabaseState>,
+    config_type: AudioType,
+    input: UpdateAudioConfig,
+) -> Result<AudioConfig, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        AudioConfigStore::new(pool.clone())
+            .update_config(config_type, &input)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 应用管理的音效库
+fn sound_library(app_handle: &AppHandle, pool: &SqlitePool) -> Result<SoundLibrary, String> {
+    let dir = app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("无法获取应用数据目录: {}", e))?
+        .join(SOUND_LIBRARY_DIR);
+    Ok(SoundLibrary::new(SoundStore::new(pool.clone()), dir))
+}
+
+/// 导入自定义音效（校验格式、大小、时长和响度后复制到音效库）
+#[tauri::command]
+pub async fn import_custom_sound(
+    app_handle: AppHandle,
+    database: State<'_, DatabaseState>,
+    source_path: String,
+    name: Option<String>,
+) -> Result<CustomSound, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        sound_library(&app_handle, pool)?
+            .import(Path::new(&source_path), name)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 列出音效库中的自定义音效（包含引用计数）
+#[tauri::command]
+pub async fn list_custom_sounds(
+    app_handle: AppHandle,
+    database: State<'_, DatabaseState>,
+) -> Result<Vec<CustomSound>, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        sound_library(&app_handle, pool)?
+            .list()
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 试听自定义音效
+#[tauri::command]
+pub async fn preview_custom_sound(
+    app_handle: AppHandle,
+    database: State<'_, DatabaseState>,
+    audio_service: State<'_, AudioServiceState>,
+    sound_id: String,
+) -> Result<(), String> {
+    let database_guard = database.read().await;
+    let Some(pool) = database_guard.as_ref() else {
+        return Err("数据库未连接".to_string());
+    };
+    let sound = sound_library(&app_handle, pool)?
+        .get(&sound_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    drop(database_guard);
+
+    let audio_guard = audio_service.read().await;
+    if let Some(service) = audio_guard.clone() {
+        tokio::task::spawn_blocking(move || service.play_file(Path::new(&sound.file_path), 1.0))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())
+    } else {
+        Err("音频服务未初始化".to_string())
+    }
+}
+
+/// 删除自定义音效（仍被提示音或背景音使用时拒绝）
+#[tauri::command]
+pub async fn delete_custom_sound(
+    app_handle: AppHandle,
+    database: State<'_, DatabaseState>,
+    sound_id: String,
+) -> Result<(), String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        sound_library(&app_handle, pool)?
+            .delete(&sound_id)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 为提示音指定自定义音效；sound_id为空时恢复默认提示音
+#[tauri::command]
+pub async fn assign_custom_sound(
+    database: State<'_, DatabaseState>,
+    config_type: AudioType,
+    sound_id: Option<String>,
+) -> Result<AudioConfig, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        let file_path = match sound_id {
+            Some(sound_id) => Some(
+                SoundStore::new(pool.clone())
+                    .get_sound(&sound_id)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .file_path,
+            ),
+            None => None,
+        };
+        AudioConfigStore::new(pool.clone())
+            .assign_file(config_type, file_path.as_deref())
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 未指定档案时使用定时器当前的背景音档案
+async fn resolve_ambient_profile(timer_manager: &TimerManagerState, profile_id: Option<String>) -> String {
+    match profile_id.filter(|id| !id.trim().is_empty()) {
+        Some(profile_id) => profile_id,
+        None => match timer_manager.read().await.as_ref() {
+            Some(manager) => manager.get_ambient_profile().await,
+            None => DEFAULT_PROFILE_ID.to_string(),
+        },
+    }
+}
+
+/// 获取专注背景音设置
+#[tauri::command]
+pub async fn get_ambient_settings(
+    database: State<'_, DatabaseState>,
+    timer_manager: State<'_, TimerManagerState>,
+    profile_id: Option<String>,
+) -> Result<AmbientSettings, String> {
+    let profile_id = resolve_ambient_profile(&timer_manager, profile_id).await;
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        AmbientStore::new(pool.clone())
+            .get_settings(&profile_id)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 更新专注背景音设置；修改的是当前档案且正在专注时立即生效
+#[tauri::command]
+pub async fn update_ambient_settings(
+    database: State<'_, DatabaseState>,
+    timer_manager: State<'_, TimerManagerState>,
+    profile_id: Option<String>,
+    input: UpdateAmbientSettings,
+) -> Result<AmbientSettings, String> {
+    let profile_id = resolve_ambient_profile(&timer_manager, profile_id).await;
+    let database_guard = database.read().await;
+
+    let settings = if let Some(pool) = database_guard.as_ref() {
+        AmbientStore::new(pool.clone())
+            .update_settings(&profile_id, input)
+            .await
+            .map_err(|e| e.to_string())?
+    } else {
+        return Err("数据库未连接".to_string());
+    };
+    drop(database_guard);
+
+    if let Some(manager) = timer_manager.read().await.as_ref() {
+        if manager.get_ambient_profile().await == settings.profile_id {
+            manager.refresh_ambient().await;
+        }
+    }
+
+    Ok(settings)
+}
+
+/// 切换定时器使用的背景音设置档案
+#[tauri::command]
+pub async fn set_ambient_profile(
+    timer_manager: State<'_, TimerManagerState>,
+    profile_id: String,
+) -> Result<(), String> {
+    let profile_id = profile_id.trim().to_string();
+    if profile_id.is_empty() {
+        return Err("档案ID不能为空".to_string());
+    }
+
+    let manager_guard = timer_manager.read().await;
+
+    if let Some(manager) = manager_guard.as_ref() {
+        manager.set_ambient_profile(profile_id).await;
+        Ok(())
+    } else {
+        Err("定时器管理器未初始化".to_string())
+    }
+}
+
+/// 获取免打扰设置
+#[tauri::command]
+pub async fn get_quiet_hours(
+    database: State<'_, DatabaseState>,
+) -> Result<QuietHours, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        QuietHoursStore::new(pool.clone())
+            .get_quiet_hours()
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 更新免打扰设置并立即应用到音频服务
+#[tauri::command]
+pub async fn update_quiet_hours(
+    database: State<'_, DatabaseState>,
+    audio_service: State<'_, AudioServiceState>,
+    input: UpdateQuietHours,
+) -> Result<QuietHours, String> {
+    let database_guard = database.read().await;
+
+    let quiet_hours = if let Some(pool) = database_guard.as_ref() {
+        QuietHoursStore::new(pool.clone())
+            .update_quiet_hours(input)
+            .await
+            .map_err(|e| e.to_string())?
+    } else {
+        return Err("数据库未连接".to_string());
+    };
+    drop(database_guard);
+
+    if let Some(service) = audio_service.read().await.as_ref() {
+        service.set_quiet_hours(quiet_hours.clone());
+    }
+
+    Ok(quiet_hours)
+}
+
+/// 当前是否处于免打扰时段（返回处理方式，不在时段内时为null）
+#[tauri::command]
+pub async fn get_quiet_mode(
+    audio_service: State<'_, AudioServiceState>,
+) -> Result<Option<QuietHoursMode>, String> {
+    let audio_guard = audio_service.read().await;
+
+    if let Some(service) = audio_guard.as_ref() {
+        Ok(service.quiet_mode())
+    } else {
+        Err("音频服务未初始化".to_string())
+    }
+}
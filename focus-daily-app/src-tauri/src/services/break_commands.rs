@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use tauri::State;
+
+use crate::models::LongBreakAdherence;
+use crate::services::storage::SessionStore;
+use crate::services::timer_commands::DatabaseState;
+
+/// 获取时间范围内专注会话之后的长休息执行情况
+#[tauri::command]
+pub async fn get_long_break_stats(
+    database: State<'_, DatabaseState>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<LongBreakAdherence, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        SessionStore::new(pool.clone())
+            .long_break_adherence(&start, &end)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
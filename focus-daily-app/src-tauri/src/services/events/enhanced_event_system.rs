@@ -5,8 +5,12 @@ use tokio::sync::{Mutex, RwLock, mpsc};
 use tauri::{AppHandle, Emitter};
 use serde::{Serialize, Deserialize};
 use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqlitePool;
 
+use crate::models::{AudioType, QuietHoursMode};
+use crate::services::storage::{EventLogRetention, EventLogStore, StorageError};
 use crate::services::timer::timer_state::TimerEvent;
+use super::retry_queue::{DeadLetter, DeadLetterReason, QueueOverflowPolicy, RetryQueue};
 
 /// 事件优先级
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -17,6 +21,29 @@ pub enum EventPriority {
     Critical = 3,
 }
 
+impl EventPriority {
+    /// 数据库中存储的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventPriority::Low => "Low",
+            EventPriority::Normal => "Normal",
+            EventPriority::High => "High",
+            EventPriority::Critical => "Critical",
+        }
+    }
+
+    /// 从数据库名称解析
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "Low" => Some(EventPriority::Low),
+            "Normal" => Some(EventPriority::Normal),
+            "High" => Some(EventPriority::High),
+            "Critical" => Some(EventPriority::Critical),
+            _ => None,
+        }
+    }
+}
+
 /// 增强的事件包装器
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnhancedEvent {
@@ -37,7 +64,7 @@ pub struct EnhancedEvent {
 }
 
 /// 事件类别
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum EventCategory {
     Timer,         // 定时器事件
     MicroBreak,    // 微休息事件
@@ -47,6 +74,58 @@ pub enum EventCategory {
     Analytics,     // 分析事件
 }
 
+impl EventCategory {
+    /// 数据库中存储的名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventCategory::Timer => "Timer",
+            EventCategory::MicroBreak => "MicroBreak",
+            EventCategory::Audio => "Audio",
+            EventCategory::System => "System",
+            EventCategory::Notification => "Notification",
+            EventCategory::Analytics => "Analytics",
+        }
+    }
+
+    /// 从数据库名称解析
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "Timer" => Some(EventCategory::Timer),
+            "MicroBreak" => Some(EventCategory::MicroBreak),
+            "Audio" => Some(EventCategory::Audio),
+            "System" => Some(EventCategory::System),
+            "Notification" => Some(EventCategory::Notification),
+            "Analytics" => Some(EventCategory::Analytics),
+            _ => None,
+        }
+    }
+}
+
+/// 未指定时每页的事件数量
+const DEFAULT_HISTORY_PAGE_SIZE: usize = 100;
+/// 每页事件数量上限
+const MAX_HISTORY_PAGE_SIZE: usize = 500;
+
+/// 事件历史查询条件（时间范围为[start, end)）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventHistoryQuery {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub category: Option<EventCategory>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// 事件历史的一页（最新的在前）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventHistoryPage {
+    pub events: Vec<EnhancedEvent>,
+    /// 符合条件的事件总数
+    pub total: u64,
+    pub offset: usize,
+    pub limit: usize,
+}
+
 /// 事件处理结果
 #[derive(Debug, Clone)]
 pub enum EventProcessResult {
@@ -70,11 +149,14 @@ pub struct EventStats {
 }
 
 /// 增强的事件管理器
+#[derive(Clone)]
 pub struct EnhancedEventManager {
     /// Tauri应用句柄
     app_handle: AppHandle,
-    /// 事件队列 (按优先级排序)
-    event_queue: Arc<Mutex<VecDeque<EnhancedEvent>>>,
+    /// 等待重试的事件队列（按优先级取出）
+    retry_queue: Arc<RetryQueue>,
+    /// 无法送达的死信事件
+    dead_letters: Arc<RwLock<VecDeque<DeadLetter>>>,
     /// 事件历史记录
     event_history: Arc<RwLock<VecDeque<EnhancedEvent>>>,
     /// 事件统计
@@ -83,13 +165,38 @@ pub struct EnhancedEventManager {
     event_sender: mpsc::UnboundedSender<EnhancedEvent>,
     /// 配置参数
     config: EventManagerConfig,
+    /// 事件去重器
+    deduplicator: Arc<Mutex<EventDeduplicator>>,
+    /// 事件日志存储（未连接数据库时为None，只保留内存中的历史记录）
+    event_log: Option<EventLogStore>,
+    /// 音频事件处理器（负责实际播放）
+    audio_handler: Option<Arc<dyn AudioEventHandler>>,
+}
+
+/// 事件处理任务共享的状态
+#[derive(Clone)]
+struct EventPipeline {
+    app_handle: AppHandle,
+    retry_queue: Arc<RetryQueue>,
+    dead_letters: Arc<RwLock<VecDeque<DeadLetter>>>,
+    event_history: Arc<RwLock<VecDeque<EnhancedEvent>>>,
+    stats: Arc<RwLock<EventStats>>,
+    deduplicator: Arc<Mutex<EventDeduplicator>>,
+    event_log: Option<EventLogStore>,
+    config: EventManagerConfig,
 }
 
 /// 事件管理器配置
 #[derive(Debug, Clone)]
 pub struct EventManagerConfig {
-    /// 最大队列大小
+    /// 重试队列最大长度
     pub max_queue_size: usize,
+    /// 重试队列已满时的处理策略
+    pub overflow_policy: QueueOverflowPolicy,
+    /// 重试退避的最大延迟
+    pub max_retry_delay: Duration,
+    /// 最多保留的死信数量
+    pub max_dead_letters: usize,
     /// 最大历史记录数量
     pub max_history_size: usize,
     /// 批处理大小
@@ -102,28 +209,99 @@ pub struct EventManagerConfig {
     pub event_ttl: Duration,
     /// 是否启用事件去重
     pub enable_deduplication: bool,
+    /// 各类别的去重窗口（窗口内相同的事件只发送一次；未配置的类别不去重）
+    pub dedup_windows: HashMap<EventCategory, Duration>,
+    /// 事件日志的保留策略
+    pub event_log_retention: EventLogRetention,
 }
 
 impl Default for EventManagerConfig {
     fn default() -> Self {
         Self {
             max_queue_size: 1000,
+            overflow_policy: QueueOverflowPolicy::DropLowestPriority,
+            max_retry_delay: Duration::from_secs(30),
+            max_dead_letters: 200,
             max_history_size: 500,
             batch_size: 10,
             batch_interval: Duration::from_millis(100),
             max_retries: 3,
             event_ttl: Duration::from_secs(60),
             enable_deduplication: true,
+            // Tick每秒发送一次，定时器类事件的窗口必须小于1秒
+            dedup_windows: HashMap::from([
+                (EventCategory::Timer, Duration::from_millis(500)),
+                (EventCategory::MicroBreak, Duration::from_secs(1)),
+                (EventCategory::Audio, Duration::from_secs(1)),
+                (EventCategory::System, Duration::from_millis(500)),
+                (EventCategory::Notification, Duration::from_secs(5)),
+                (EventCategory::Analytics, Duration::from_secs(1)),
+            ]),
+            event_log_retention: EventLogRetention::default(),
         }
     }
 }
 
+/// 去重键：完整的事件内容
+///
+/// 只有内容完全相同的事件才视为重复，音量、音频配置、调整时长等携带新状态的事件不会被误丢弃。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct DedupKey(String);
+
+impl DedupKey {
+    fn of(event: &TimerEvent) -> Self {
+        // 序列化失败（例如音量为NaN）时退回调试输出，两者都包含全部字段
+        Self(serde_json::to_string(event).unwrap_or_else(|_| format!("{:?}", event)))
+    }
+}
+
+/// 基于滑动窗口的事件去重器
+///
+/// 记录每个去重键最近一次被发送的时间，窗口内再次出现的相同事件被丢弃。
+struct EventDeduplicator {
+    windows: HashMap<EventCategory, Duration>,
+    last_sent: HashMap<DedupKey, DateTime<Utc>>,
+}
+
+impl EventDeduplicator {
+    fn new(windows: HashMap<EventCategory, Duration>) -> Self {
+        Self {
+            windows,
+            last_sent: HashMap::new(),
+        }
+    }
+
+    /// 检查事件是否为窗口内的重复事件；不重复时记录本次发送
+    fn check(&mut self, event: &EnhancedEvent) -> bool {
+        let Some(window) = self.windows.get(&event.category).copied() else {
+            return false;
+        };
+
+        // 清理所有窗口都已过期的记录
+        let longest = self.windows.values().max().copied().unwrap_or(Duration::ZERO);
+        let age = |at: &DateTime<Utc>| event.timestamp.signed_duration_since(*at).to_std().unwrap_or(Duration::ZERO);
+        self.last_sent.retain(|_, at| age(at) < longest);
+
+        let key = DedupKey::of(&event.event);
+        if self.last_sent.get(&key).is_some_and(|at| age(at) < window) {
+            return true;
+        }
+        self.last_sent.insert(key, event.timestamp);
+        false
+    }
+}
+
 /// 音频事件处理器特征 (修复为非async版本)
-pub trait AudioEventHandler {
+pub trait AudioEventHandler: Send + Sync {
     fn handle_audio_event(&self, event: &AudioEvent) -> Result<(), String>;
+
+    /// 当前是否处于免打扰时段及其处理方式
+    fn quiet_mode(&self) -> Option<QuietHoursMode> {
+        None
+    }
 }
 
-/// 音频事件类型 (为Week 4准备)
+/// 音频事件类型
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AudioEvent {
     PlayFocusStart,
@@ -132,6 +310,8 @@ pub enum AudioEvent {
     PlayLongBreakEnd,
     PlayMicroBreakStart,
     PlayMicroBreakEnd,
+    PlayFocusWarning,
+    PlayLongBreakWarning,
     PlayNotification { sound_type: String },
     SetVolume { level: f32 },
     Mute,
@@ -139,7 +319,40 @@ pub enum AudioEvent {
     ConfigChanged { config: AudioConfig },
 }
 
-/// 音频配置 (为Week 4准备)
+impl AudioEvent {
+    /// 是否为播放提示音的事件（免打扰时段内会被拦截）
+    pub fn is_playback(&self) -> bool {
+        matches!(
+            self,
+            AudioEvent::PlayFocusStart
+                | AudioEvent::PlayFocusEnd
+                | AudioEvent::PlayLongBreakStart
+                | AudioEvent::PlayLongBreakEnd
+                | AudioEvent::PlayMicroBreakStart
+                | AudioEvent::PlayMicroBreakEnd
+                | AudioEvent::PlayFocusWarning
+                | AudioEvent::PlayLongBreakWarning
+                | AudioEvent::PlayNotification { .. }
+        )
+    }
+}
+
+impl From<AudioType> for AudioEvent {
+    fn from(cue: AudioType) -> Self {
+        match cue {
+            AudioType::FocusStart => AudioEvent::PlayFocusStart,
+            AudioType::FocusEnd => AudioEvent::PlayFocusEnd,
+            AudioType::LongBreakStart => AudioEvent::PlayLongBreakStart,
+            AudioType::LongBreakEnd => AudioEvent::PlayLongBreakEnd,
+            AudioType::MicroBreakStart => AudioEvent::PlayMicroBreakStart,
+            AudioType::MicroBreakEnd => AudioEvent::PlayMicroBreakEnd,
+            AudioType::FocusWarning => AudioEvent::PlayFocusWarning,
+            AudioType::LongBreakWarning => AudioEvent::PlayLongBreakWarning,
+        }
+    }
+}
+
+/// 音频配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AudioConfig {
     pub enabled: bool,
@@ -151,48 +364,89 @@ pub struct AudioConfig {
     pub notification_sound: Option<String>,
 }
 
+impl Default for AudioConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            volume: 1.0,
+            focus_start_sound: None,
+            focus_end_sound: None,
+            break_start_sound: None,
+            break_end_sound: None,
+            notification_sound: None,
+        }
+    }
+}
+
 impl EnhancedEventManager {
     /// 创建新的增强事件管理器
-    pub fn new(app_handle: AppHandle, config: Option<EventManagerConfig>) -> Self {
+    pub fn new(app_handle: AppHandle, config: Option<EventManagerConfig>, database: Option<SqlitePool>) -> Self {
         let config = config.unwrap_or_default();
         let (sender, receiver) = mpsc::unbounded_channel();
         
         let manager = Self {
             app_handle: app_handle.clone(),
-            event_queue: Arc::new(Mutex::new(VecDeque::new())),
+            retry_queue: Arc::new(RetryQueue::new(config.max_queue_size, config.overflow_policy)),
+            dead_letters: Arc::new(RwLock::new(VecDeque::new())),
             event_history: Arc::new(RwLock::new(VecDeque::new())),
             stats: Arc::new(RwLock::new(EventStats::default())),
             event_sender: sender,
+            deduplicator: Arc::new(Mutex::new(EventDeduplicator::new(config.dedup_windows.clone()))),
+            event_log: database.map(|pool| EventLogStore::new(pool).with_retention(config.event_log_retention)),
             config,
+            audio_handler: None,
         };
         
         // 启动事件处理任务
         manager.start_event_processor(receiver);
+
+        // 启动时按保留策略清理事件日志（之后每次写入时清理）
+        if let Some(event_log) = manager.event_log.clone() {
+            tokio::spawn(async move {
+                if let Err(e) = event_log.prune().await {
+                    eprintln!("Failed to prune event log: {}", e);
+                }
+            });
+        }
         
         manager
     }
     
+    /// 设置音频事件处理器
+    pub fn with_audio_handler(mut self, handler: Arc<dyn AudioEventHandler>) -> Self {
+        self.audio_handler = Some(handler);
+        self
+    }
+    
     /// 发送事件
     pub async fn emit_event(&self, event: TimerEvent, priority: Option<EventPriority>) -> Result<(), String> {
-        let enhanced_event = EnhancedEvent {
-            id: uuid::Uuid::new_v4().to_string(),
-            priority: priority.unwrap_or(EventPriority::Normal),
-            timestamp: Utc::now(),
-            category: self.categorize_event(&event),
-            retry_count: 0,
-            persist: self.should_persist_event(&event),
-            event,
-        };
-        
-        self.event_sender.send(enhanced_event)
+        self.event_sender.send(Self::enhance(event, priority))
             .map_err(|e| format!("Failed to send event: {}", e))?;
         
         Ok(())
     }
     
-    /// 发送音频事件 (简化实现，移除async handler调用)
+    /// 发送音频事件：先交给音频处理器播放，再通知前端
     pub async fn emit_audio_event(&self, audio_event: AudioEvent, priority: Option<EventPriority>) -> Result<(), String> {
-        // 直接转换为TimerEvent并发送到前端
+        // 免打扰时段内不播放提示音，按设置静音或改为界面通知
+        if audio_event.is_playback() {
+            match self.audio_handler.as_ref().and_then(|handler| handler.quiet_mode()) {
+                Some(QuietHoursMode::Mute) => return Ok(()),
+                Some(QuietHoursMode::VisualOnly) => {
+                    return self.emit_event(TimerEvent::VisualCue { event: audio_event }, priority).await;
+                }
+                None => {}
+            }
+        }
+
+        if let Some(handler) = self.audio_handler.clone() {
+            let event = audio_event.clone();
+            // 解码可能读取磁盘，避免阻塞异步运行时
+            tokio::task::spawn_blocking(move || handler.handle_audio_event(&event))
+                .await
+                .map_err(|e| format!("Audio handler panicked: {}", e))??;
+        }
+        
         let timer_event = self.audio_event_to_timer_event(audio_event);
         self.emit_event(timer_event, priority).await?;
         
@@ -212,23 +466,59 @@ impl EnhancedEventManager {
         self.stats.read().await.clone()
     }
     
-    /// 获取事件历史
-    pub async fn get_event_history(&self, limit: Option<usize>) -> Vec<EnhancedEvent> {
-        let history = self.event_history.read().await;
-        let limit = limit.unwrap_or(100).min(history.len());
-        history.iter().rev().take(limit).cloned().collect()
+    /// 分页获取事件历史
+    ///
+    /// 连接数据库时合并事件日志中的持久化事件和内存中最近的未持久化事件（例如Tick、暂停）；
+    /// 否则只在内存中最近的事件里筛选。内存中只保留最近max_history_size个事件。
+    pub async fn get_event_history(&self, query: EventHistoryQuery) -> Result<EventHistoryPage, String> {
+        Self::query_history(self.event_log.as_ref(), &self.event_history, &query)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn query_history(
+        event_log: Option<&EventLogStore>,
+        history: &RwLock<VecDeque<EnhancedEvent>>,
+        query: &EventHistoryQuery,
+    ) -> Result<EventHistoryPage, StorageError> {
+        let limit = query.limit.unwrap_or(DEFAULT_HISTORY_PAGE_SIZE).clamp(1, MAX_HISTORY_PAGE_SIZE);
+        let offset = query.offset.unwrap_or(0);
+
+        // 持久化的事件以事件日志为准，内存中只取未持久化的事件，避免重复
+        let mut events: Vec<EnhancedEvent> = history
+            .read()
+            .await
+            .iter()
+            .filter(|event| event_log.is_none() || !event.persist)
+            .filter(|event| query.start.is_none_or(|start| event.timestamp >= start))
+            .filter(|event| query.end.is_none_or(|end| event.timestamp < end))
+            .filter(|event| query.category.is_none_or(|category| event.category == category))
+            .cloned()
+            .collect();
+        let mut total = events.len() as u64;
+
+        // 合并后的前offset + limit个事件只可能来自事件日志中最新的offset + limit个事件
+        if let Some(event_log) = event_log {
+            let (persisted, persisted_total) = event_log.latest(query, offset + limit).await?;
+            events.extend(persisted);
+            total += persisted_total;
+        }
+        events.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| b.id.cmp(&a.id)));
+
+        Ok(EventHistoryPage {
+            events: events.into_iter().skip(offset).take(limit).collect(),
+            total,
+            offset,
+            limit,
+        })
     }
     
-    /// 清理过期事件
+    /// 清理过期事件：等待重试超过有效期的事件转入死信
     pub async fn cleanup_expired_events(&self) {
-        let now = Utc::now();
-        let ttl = self.config.event_ttl;
-        
-        // 清理队列中的过期事件
-        let mut queue = self.event_queue.lock().await;
-        queue.retain(|event| {
-            now.signed_duration_since(event.timestamp).to_std().unwrap_or(Duration::ZERO) < ttl
-        });
+        let pipeline = self.pipeline();
+        for event in pipeline.retry_queue.take_expired(self.config.event_ttl).await {
+            Self::dead_letter(&pipeline, event, DeadLetterReason::Expired, None).await;
+        }
         
         // 清理历史记录
         let mut history = self.event_history.write().await;
@@ -237,23 +527,47 @@ impl EnhancedEventManager {
         }
     }
     
-    /// 获取队列状态
+    /// 获取重试队列状态（排队数量, 容量）
     pub async fn get_queue_status(&self) -> (usize, usize) {
-        let queue = self.event_queue.lock().await;
-        (queue.len(), self.config.max_queue_size)
+        (self.retry_queue.len().await, self.retry_queue.capacity())
+    }
+    
+    /// 获取死信事件（最新的在前）
+    pub async fn get_dead_letters(&self, limit: Option<usize>) -> Vec<DeadLetter> {
+        let dead_letters = self.dead_letters.read().await;
+        let limit = limit.unwrap_or(100).min(dead_letters.len());
+        dead_letters.iter().rev().take(limit).cloned().collect()
+    }
+    
+    /// 清空死信事件，返回清除的数量
+    pub async fn clear_dead_letters(&self) -> usize {
+        let mut dead_letters = self.dead_letters.write().await;
+        let count = dead_letters.len();
+        dead_letters.clear();
+        count
+    }
+    
+    /// 事件处理任务共享的状态
+    fn pipeline(&self) -> EventPipeline {
+        EventPipeline {
+            app_handle: self.app_handle.clone(),
+            retry_queue: Arc::clone(&self.retry_queue),
+            dead_letters: Arc::clone(&self.dead_letters),
+            event_history: Arc::clone(&self.event_history),
+            stats: Arc::clone(&self.stats),
+            deduplicator: Arc::clone(&self.deduplicator),
+            event_log: self.event_log.clone(),
+            config: self.config.clone(),
+        }
     }
     
-    /// 启动事件处理器
+    /// 启动事件处理器和重试队列消费者
     fn start_event_processor(&self, mut receiver: mpsc::UnboundedReceiver<EnhancedEvent>) {
-        let app_handle = self.app_handle.clone();
-        let event_queue = Arc::clone(&self.event_queue);
-        let event_history = Arc::clone(&self.event_history);
-        let stats = Arc::clone(&self.stats);
-        let config = self.config.clone();
+        let pipeline = self.pipeline();
         
         tokio::spawn(async move {
             let mut batch = Vec::new();
-            let mut batch_timer = tokio::time::interval(config.batch_interval);
+            let mut batch_timer = tokio::time::interval(pipeline.config.batch_interval);
             
             loop {
                 tokio::select! {
@@ -262,45 +576,39 @@ impl EnhancedEventManager {
                         batch.push(event);
                         
                         // 如果批次达到大小限制，立即处理
-                        if batch.len() >= config.batch_size {
-                            Self::process_event_batch(
-                                &app_handle,
-                                &mut batch,
-                                &event_queue,
-                                &event_history,
-                                &stats,
-                                &config
-                            ).await;
+                        if batch.len() >= pipeline.config.batch_size {
+                            Self::process_event_batch(&pipeline, &mut batch, false).await;
                         }
                     }
                     
                     // 批处理定时器
                     _ = batch_timer.tick() => {
                         if !batch.is_empty() {
-                            Self::process_event_batch(
-                                &app_handle,
-                                &mut batch,
-                                &event_queue,
-                                &event_history,
-                                &stats,
-                                &config
-                            ).await;
+                            Self::process_event_batch(&pipeline, &mut batch, false).await;
                         }
                     }
                 }
             }
         });
+        
+        let pipeline = self.pipeline();
+        tokio::spawn(async move {
+            loop {
+                pipeline.retry_queue.wait().await;
+                
+                for event in pipeline.retry_queue.take_expired(pipeline.config.event_ttl).await {
+                    Self::dead_letter(&pipeline, event, DeadLetterReason::Expired, None).await;
+                }
+                let mut ready = pipeline.retry_queue.pop_ready().await;
+                Self::process_event_batch(&pipeline, &mut ready, true).await;
+            }
+        });
     }
     
     /// 处理事件批次
-    async fn process_event_batch(
-        app_handle: &AppHandle,
-        batch: &mut Vec<EnhancedEvent>,
-        event_queue: &Arc<Mutex<VecDeque<EnhancedEvent>>>,
-        event_history: &Arc<RwLock<VecDeque<EnhancedEvent>>>,
-        stats: &Arc<RwLock<EventStats>>,
-        config: &EventManagerConfig,
-    ) {
+    ///
+    /// 重试队列消费者处理的批次中再次重试时不等待队列空间（只有消费者会腾出空间），队列已满时转入死信。
+    async fn process_event_batch(pipeline: &EventPipeline, batch: &mut Vec<EnhancedEvent>, from_retry_queue: bool) {
         if batch.is_empty() {
             return;
         }
@@ -311,42 +619,48 @@ impl EnhancedEventManager {
         // 处理每个事件
         for event in batch.drain(..) {
             let start_time = Instant::now();
-            let result = Self::process_single_event(app_handle, &event).await;
+            let result = Self::process_single_event(pipeline, &event).await;
             let processing_time = start_time.elapsed();
             
             // 更新统计信息
-            Self::update_stats(stats, &event, &result, processing_time).await;
+            Self::update_stats(&pipeline.stats, &event, &result, processing_time).await;
             
             // 根据处理结果决定下一步
             match result {
                 EventProcessResult::Success => {
-                    // 成功处理，添加到历史记录
-                    let mut history = event_history.write().await;
-                    history.push_back(event);
-                    if history.len() > config.max_history_size {
-                        history.pop_front();
-                    }
+                    Self::record_delivered(
+                        pipeline.event_log.as_ref(),
+                        &pipeline.event_history,
+                        pipeline.config.max_history_size,
+                        event,
+                    ).await;
                 }
                 EventProcessResult::Retry(delay) => {
-                    if event.retry_count < config.max_retries {
-                        // 重新排队，增加重试计数
+                    if event.retry_count < pipeline.config.max_retries {
+                        // 指数退避后由重试队列消费者重新处理
+                        let backoff = delay
+                            .saturating_mul(2u32.saturating_pow(event.retry_count))
+                            .min(pipeline.config.max_retry_delay);
                         let mut retry_event = event;
                         retry_event.retry_count += 1;
                         
-                        // 延迟后重新加入队列
-                        let queue = Arc::clone(event_queue);
-                        tokio::spawn(async move {
-                            tokio::time::sleep(delay).await;
-                            let mut q = queue.lock().await;
-                            q.push_back(retry_event);
-                        });
+                        let dropped = if from_retry_queue {
+                            pipeline.retry_queue.try_push(retry_event, backoff).await
+                        } else {
+                            pipeline.retry_queue.push(retry_event, backoff).await
+                        };
+                        if let Some(dropped) = dropped {
+                            Self::dead_letter(pipeline, dropped, DeadLetterReason::Overflow, None).await;
+                        }
                     } else {
-                        // 超过最大重试次数，记录失败
+                        // 超过最大重试次数，转入死信
                         eprintln!("Event {} failed after {} retries", event.id, event.retry_count);
+                        Self::dead_letter(pipeline, event, DeadLetterReason::RetriesExhausted, None).await;
                     }
                 }
                 EventProcessResult::Failed(error) => {
                     eprintln!("Event {} processing failed: {}", event.id, error);
+                    Self::dead_letter(pipeline, event, DeadLetterReason::Failed, Some(error)).await;
                 }
                 EventProcessResult::Discard => {
                     // 事件被丢弃，无需处理
@@ -355,15 +669,57 @@ impl EnhancedEventManager {
         }
     }
     
+    /// 记录已送达的事件：需要持久化的写入事件日志，并加入内存中的历史记录
+    async fn record_delivered(
+        event_log: Option<&EventLogStore>,
+        history: &RwLock<VecDeque<EnhancedEvent>>,
+        max_history_size: usize,
+        event: EnhancedEvent,
+    ) {
+        if let (true, Some(event_log)) = (event.persist, event_log) {
+            if let Err(e) = event_log.append(&event, &Self::get_event_name(&event.event)).await {
+                eprintln!("Failed to persist event {}: {}", event.id, e);
+            }
+        }
+
+        let mut history = history.write().await;
+        history.push_back(event);
+        if history.len() > max_history_size {
+            history.pop_front();
+        }
+    }
+    
+    /// 把无法送达的事件记入死信
+    ///
+    /// 处理失败已在处理结果中计入失败数；用尽重试计为失败，过期和溢出计为丢弃。
+    async fn dead_letter(pipeline: &EventPipeline, event: EnhancedEvent, reason: DeadLetterReason, error: Option<String>) {
+        match reason {
+            DeadLetterReason::RetriesExhausted => pipeline.stats.write().await.failed_events += 1,
+            DeadLetterReason::Expired | DeadLetterReason::Overflow => pipeline.stats.write().await.discarded_events += 1,
+            DeadLetterReason::Failed => {}
+        }
+        
+        let mut dead_letters = pipeline.dead_letters.write().await;
+        dead_letters.push_back(DeadLetter {
+            event,
+            reason,
+            error,
+            dead_at: Utc::now(),
+        });
+        while dead_letters.len() > pipeline.config.max_dead_letters {
+            dead_letters.pop_front();
+        }
+    }
+    
     /// 处理单个事件
-    async fn process_single_event(app_handle: &AppHandle, event: &EnhancedEvent) -> EventProcessResult {
+    async fn process_single_event(pipeline: &EventPipeline, event: &EnhancedEvent) -> EventProcessResult {
         // 事件去重检查
-        if Self::is_duplicate_event(event).await {
+        if pipeline.config.enable_deduplication && Self::is_duplicate_event(&pipeline.deduplicator, event).await {
             return EventProcessResult::Discard;
         }
         
         // 发送到前端
-        match Self::emit_to_frontend(app_handle, event).await {
+        match Self::emit_to_frontend(&pipeline.app_handle, event).await {
             Ok(_) => EventProcessResult::Success,
             Err(e) => {
                 // 根据错误类型决定是否重试
@@ -427,13 +783,14 @@ impl EnhancedEventManager {
     }
     
     /// 检查是否为重复事件
-    async fn is_duplicate_event(_event: &EnhancedEvent) -> bool {
-        // TODO: 实现事件去重逻辑
-        false
+    ///
+    /// 重试的事件已经通过过去重检查，不再重复判断。
+    async fn is_duplicate_event(deduplicator: &Arc<Mutex<EventDeduplicator>>, event: &EnhancedEvent) -> bool {
+        event.retry_count == 0 && deduplicator.lock().await.check(event)
     }
     
     /// 获取事件名称
-    fn get_event_name(event: &TimerEvent) -> String {
+    pub(crate) fn get_event_name(event: &TimerEvent) -> String {
         match event {
             TimerEvent::Started { .. } => "timer-started".to_string(),
             TimerEvent::Paused { .. } => "timer-paused".to_string(),
@@ -447,21 +804,40 @@ impl EnhancedEventManager {
             TimerEvent::MicroBreakSkipLimitReached { .. } => "micro-break-skip-limit-reached".to_string(),
             TimerEvent::MicroBreakScheduleUpdated { .. } => "micro-break-schedule-updated".to_string(),
             TimerEvent::MicroBreakStatsUpdated { .. } => "micro-break-stats-updated".to_string(),
+            TimerEvent::DurationAdjusted { .. } => "duration-adjusted".to_string(),
+            TimerEvent::PlanProgress { .. } => "plan-progress".to_string(),
+            TimerEvent::PlanCompleted { .. } => "plan-completed".to_string(),
+            TimerEvent::Audio { .. } => "audio-event".to_string(),
+            TimerEvent::VisualCue { .. } => "visual-cue".to_string(),
             TimerEvent::PhaseChanged { .. } => "phase-changed".to_string(),
             TimerEvent::FatigueWarning { .. } => "fatigue-warning".to_string(),
             TimerEvent::EfficiencyFeedback { .. } => "efficiency-feedback".to_string(),
         }
     }
     
+    /// 为事件补充ID、优先级、类别等处理信息
+    fn enhance(event: TimerEvent, priority: Option<EventPriority>) -> EnhancedEvent {
+        EnhancedEvent {
+            id: uuid::Uuid::new_v4().to_string(),
+            priority: priority.unwrap_or(EventPriority::Normal),
+            timestamp: Utc::now(),
+            category: Self::categorize_event(&event),
+            retry_count: 0,
+            persist: Self::should_persist_event(&event),
+            event,
+        }
+    }
+    
     /// 事件分类
-    fn categorize_event(&self, event: &TimerEvent) -> EventCategory {
+    fn categorize_event(event: &TimerEvent) -> EventCategory {
         match event {
             TimerEvent::Started { .. } |
             TimerEvent::Paused { .. } |
             TimerEvent::Resumed { .. } |
             TimerEvent::Tick { .. } |
             TimerEvent::Completed { .. } |
-            TimerEvent::Reset { .. } => EventCategory::Timer,
+            TimerEvent::Reset { .. } |
+            TimerEvent::DurationAdjusted { .. } => EventCategory::Timer,
             
             TimerEvent::MicroBreakTriggered { .. } |
             TimerEvent::MicroBreakSkipped { .. } |
@@ -470,38 +846,31 @@ impl EnhancedEventManager {
             TimerEvent::MicroBreakScheduleUpdated { .. } |
             TimerEvent::MicroBreakStatsUpdated { .. } => EventCategory::MicroBreak,
             
-            TimerEvent::PhaseChanged { .. } => EventCategory::System,
-            TimerEvent::FatigueWarning { .. } => EventCategory::Notification,
+            TimerEvent::Audio { .. } => EventCategory::Audio,
+            TimerEvent::PhaseChanged { .. } |
+            TimerEvent::PlanProgress { .. } |
+            TimerEvent::PlanCompleted { .. } => EventCategory::System,
+            TimerEvent::FatigueWarning { .. } |
+            TimerEvent::VisualCue { .. } => EventCategory::Notification,
             TimerEvent::EfficiencyFeedback { .. } => EventCategory::Analytics,
         }
     }
     
     /// 判断是否需要持久化事件
-    fn should_persist_event(&self, event: &TimerEvent) -> bool {
+    fn should_persist_event(event: &TimerEvent) -> bool {
         match event {
             TimerEvent::Started { .. } |
             TimerEvent::Completed { .. } |
             TimerEvent::MicroBreakCompleted { .. } |
-            TimerEvent::EfficiencyFeedback { .. } => true,
+            TimerEvent::EfficiencyFeedback { .. } |
+            TimerEvent::PlanCompleted { .. } => true,
             _ => false,
         }
     }
     
     /// 转换音频事件为定时器事件
     fn audio_event_to_timer_event(&self, audio_event: AudioEvent) -> TimerEvent {
-        // 创建一个表示音频事件的TimerEvent
-        // 这里使用PhaseChanged作为载体，实际应用中可能需要扩展TimerEvent
-        match audio_event {
-            AudioEvent::PlayFocusStart => TimerEvent::PhaseChanged {
-                from: crate::services::timer::timer_state::SessionPhase::Focus,
-                to: crate::services::timer::timer_state::SessionPhase::Focus,
-            },
-            // 其他音频事件的转换...
-            _ => TimerEvent::PhaseChanged {
-                from: crate::services::timer::timer_state::SessionPhase::Focus,
-                to: crate::services::timer::timer_state::SessionPhase::Focus,
-            },
-        }
+        TimerEvent::Audio { event: audio_event }
     }
 }
 
@@ -518,4 +887,196 @@ impl Default for EventStats {
             last_updated: Utc::now(),
         }
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::services::timer::timer_state::SessionPhase;
+    use crate::test_support::migrated_pool;
+
+    fn at(millis: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 3, 9, 0, 0).unwrap() + chrono::Duration::milliseconds(millis)
+    }
+
+    fn enhanced(event: TimerEvent, category: EventCategory, timestamp: DateTime<Utc>) -> EnhancedEvent {
+        EnhancedEvent {
+            id: uuid::Uuid::new_v4().to_string(),
+            event,
+            priority: EventPriority::Normal,
+            timestamp,
+            category,
+            retry_count: 0,
+            persist: false,
+        }
+    }
+
+    fn reset(phase: SessionPhase) -> TimerEvent {
+        TimerEvent::Reset { phase }
+    }
+
+    fn audio(event: AudioEvent) -> TimerEvent {
+        TimerEvent::Audio { event }
+    }
+
+    fn deduplicator() -> EventDeduplicator {
+        EventDeduplicator::new(EventManagerConfig::default().dedup_windows)
+    }
+
+    #[test]
+    fn duplicate_is_dropped_only_within_window() {
+        let mut dedup = deduplicator();
+        let check = |dedup: &mut EventDeduplicator, millis| {
+            dedup.check(&enhanced(reset(SessionPhase::Focus), EventCategory::Timer, at(millis)))
+        };
+
+        assert!(!check(&mut dedup, 0));
+        assert!(check(&mut dedup, 400), "500毫秒窗口内的相同事件应被丢弃");
+        // 窗口从上一次实际发送算起，被丢弃的事件不延长窗口
+        assert!(!check(&mut dedup, 600), "窗口过期后应再次发送");
+        assert!(check(&mut dedup, 1000));
+    }
+
+    #[test]
+    fn windows_are_configured_per_category() {
+        let mut dedup = EventDeduplicator::new(HashMap::from([
+            (EventCategory::Timer, Duration::from_millis(500)),
+            (EventCategory::Notification, Duration::from_secs(5)),
+        ]));
+        let timer = reset(SessionPhase::Focus);
+        let notification = audio(AudioEvent::PlayNotification { sound_type: "goal".to_string() });
+        let unconfigured = reset(SessionPhase::LongBreak);
+
+        assert!(!dedup.check(&enhanced(timer.clone(), EventCategory::Timer, at(0))));
+        assert!(!dedup.check(&enhanced(notification.clone(), EventCategory::Notification, at(0))));
+        assert!(!dedup.check(&enhanced(unconfigured.clone(), EventCategory::Analytics, at(0))));
+
+        assert!(!dedup.check(&enhanced(timer, EventCategory::Timer, at(2000))), "定时器窗口为500毫秒");
+        assert!(dedup.check(&enhanced(notification, EventCategory::Notification, at(2000))), "通知窗口为5秒");
+        assert!(!dedup.check(&enhanced(unconfigured, EventCategory::Analytics, at(100))), "未配置窗口的类别不去重");
+    }
+
+    #[test]
+    fn events_with_different_payloads_are_not_duplicates() {
+        let mut dedup = deduplicator();
+        let volume = |level| enhanced(audio(AudioEvent::SetVolume { level }), EventCategory::Audio, at(0));
+
+        assert!(!dedup.check(&volume(0.3)));
+        assert!(!dedup.check(&volume(0.6)), "不同音量的事件不是重复事件");
+        assert!(dedup.check(&volume(0.3)));
+
+        let config_changed = |volume| {
+            let config = AudioConfig { volume, ..AudioConfig::default() };
+            enhanced(audio(AudioEvent::ConfigChanged { config }), EventCategory::Audio, at(100))
+        };
+        assert!(!dedup.check(&config_changed(0.5)));
+        assert!(!dedup.check(&config_changed(0.8)), "不同配置的事件不是重复事件");
+
+        let cue = |event| enhanced(TimerEvent::VisualCue { event }, EventCategory::Notification, at(200));
+        assert!(!dedup.check(&cue(AudioEvent::PlayFocusEnd)));
+        assert!(!dedup.check(&cue(AudioEvent::PlayNotification { sound_type: "goal".to_string() })));
+        assert!(!dedup.check(&cue(AudioEvent::PlayNotification { sound_type: "streak".to_string() })));
+    }
+
+    fn completed(session_id: &str) -> TimerEvent {
+        TimerEvent::Completed { phase: SessionPhase::Focus, session_id: session_id.to_string() }
+    }
+
+    fn tick(elapsed: u64) -> TimerEvent {
+        TimerEvent::Tick { phase: SessionPhase::Focus, remaining: 60 - elapsed, elapsed, progress: 0.0 }
+    }
+
+    /// 按指定时间（距今的分钟数）生成事件，是否持久化由事件类型决定
+    fn enhanced_ago(event: TimerEvent, minutes_ago: i64) -> EnhancedEvent {
+        let mut event = EnhancedEventManager::enhance(event, None);
+        event.timestamp = Utc::now() - chrono::Duration::minutes(minutes_ago);
+        event
+    }
+
+    #[tokio::test]
+    async fn delivered_persistent_event_is_written_to_event_log() {
+        let (_dir, pool) = migrated_pool().await;
+        let event_log = EventLogStore::new(pool);
+        let history = RwLock::new(VecDeque::new());
+
+        let flagged = EnhancedEventManager::enhance(completed("s1"), None);
+        let unflagged = EnhancedEventManager::enhance(tick(1), None);
+        assert!(flagged.persist);
+        assert!(!unflagged.persist);
+
+        EnhancedEventManager::record_delivered(Some(&event_log), &history, 10, flagged.clone()).await;
+        EnhancedEventManager::record_delivered(Some(&event_log), &history, 10, unflagged).await;
+
+        let (logged, total) = event_log.latest(&EventHistoryQuery::default(), 10).await.unwrap();
+        assert_eq!(total, 1, "只有需要持久化的事件写入事件日志");
+        assert_eq!(logged[0].id, flagged.id);
+        assert!(matches!(&logged[0].event, TimerEvent::Completed { session_id, .. } if session_id == "s1"));
+        assert_eq!(history.read().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn history_merges_event_log_with_recent_unpersisted_events() {
+        let (_dir, pool) = migrated_pool().await;
+        let event_log = EventLogStore::new(pool);
+        let history = RwLock::new(VecDeque::new());
+
+        // 从旧到新：completed(50) tick(40) completed(30) tick(20) completed(10)
+        let events = [
+            enhanced_ago(completed("s1"), 50),
+            enhanced_ago(tick(1), 40),
+            enhanced_ago(completed("s2"), 30),
+            enhanced_ago(tick(2), 20),
+            enhanced_ago(completed("s3"), 10),
+        ];
+        let ids_newest_first: Vec<String> = events.iter().rev().map(|event| event.id.clone()).collect();
+        for event in events {
+            EnhancedEventManager::record_delivered(Some(&event_log), &history, 10, event).await;
+        }
+        // 内存中的历史记录已被截断时，持久化的事件仍可从事件日志查询
+        history.write().await.retain(|event| !event.persist);
+
+        let query = |offset, limit| EventHistoryQuery { offset: Some(offset), limit: Some(limit), ..EventHistoryQuery::default() };
+        let page_ids = |page: &EventHistoryPage| page.events.iter().map(|event| event.id.clone()).collect::<Vec<_>>();
+
+        let page = EnhancedEventManager::query_history(Some(&event_log), &history, &query(0, 10)).await.unwrap();
+        assert_eq!(page.total, 5);
+        assert_eq!(page_ids(&page), ids_newest_first);
+
+        let page = EnhancedEventManager::query_history(Some(&event_log), &history, &query(1, 2)).await.unwrap();
+        assert_eq!(page_ids(&page), ids_newest_first[1..3]);
+
+        let timer_since = EventHistoryQuery {
+            start: Some(Utc::now() - chrono::Duration::minutes(45)),
+            category: Some(EventCategory::Timer),
+            ..EventHistoryQuery::default()
+        };
+        let page = EnhancedEventManager::query_history(Some(&event_log), &history, &timer_since).await.unwrap();
+        assert_eq!(page.total, 4);
+        assert_eq!(page_ids(&page), ids_newest_first[..4]);
+    }
+
+    #[tokio::test]
+    async fn history_page_size_is_clamped() {
+        let history = RwLock::new(VecDeque::from([
+            enhanced_ago(tick(1), 2),
+            enhanced_ago(tick(2), 1),
+        ]));
+        let query = |limit| EventHistoryQuery { limit, ..EventHistoryQuery::default() };
+
+        let page = EnhancedEventManager::query_history(None, &history, &query(Some(0))).await.unwrap();
+        assert_eq!((page.limit, page.events.len(), page.total), (1, 1, 2));
+
+        let page = EnhancedEventManager::query_history(None, &history, &query(Some(10_000))).await.unwrap();
+        assert_eq!(page.limit, MAX_HISTORY_PAGE_SIZE);
+
+        let page = EnhancedEventManager::query_history(None, &history, &query(None)).await.unwrap();
+        assert_eq!(page.limit, DEFAULT_HISTORY_PAGE_SIZE);
+        assert_eq!(page.events.len(), 2);
+
+        let past_end = EventHistoryQuery { offset: Some(5), ..EventHistoryQuery::default() };
+        let page = EnhancedEventManager::query_history(None, &history, &past_end).await.unwrap();
+        assert!(page.events.is_empty());
+        assert_eq!(page.total, 2);
+    }
+}
@@ -1,8 +1,10 @@
 pub mod timer_events;
 pub mod enhanced_event_system;
+pub mod retry_queue;
 pub mod performance_monitor;
 
 // 重新导出主要接口
 pub use timer_events::*;
 pub use enhanced_event_system::*;
+pub use retry_queue::*;
 pub use performance_monitor::*; 
\ No newline at end of file
@@ -0,0 +1,315 @@
+use std::time::Duration;
+use tokio::sync::{Mutex, Notify};
+use tokio::time::Instant;
+use serde::{Serialize, Deserialize};
+use chrono::{DateTime, Utc};
+
+use super::enhanced_event_system::EnhancedEvent;
+
+/// 队列已满时的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QueueOverflowPolicy {
+    DropLowestPriority, // 丢弃优先级最低的事件（新事件优先级不高于队列中最低者时丢弃新事件）
+    Block,              // 等待队列腾出空间
+}
+
+/// 事件进入死信的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DeadLetterReason {
+    RetriesExhausted, // 超过最大重试次数
+    Failed,           // 不可重试的处理失败
+    Expired,          // 等待重试期间超过事件有效期
+    Overflow,         // 队列已满被丢弃
+}
+
+/// 死信记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub event: EnhancedEvent,
+    pub reason: DeadLetterReason,
+    pub error: Option<String>,
+    pub dead_at: DateTime<Utc>,
+}
+
+/// 等待重试的事件
+struct QueuedEvent {
+    event: EnhancedEvent,
+    /// 可以重试的时间
+    ready_at: Instant,
+    /// 入队序号，同优先级按先后处理
+    seq: u64,
+}
+
+#[derive(Default)]
+struct QueueState {
+    entries: Vec<QueuedEvent>,
+    next_seq: u64,
+}
+
+/// 有界的事件重试优先队列
+///
+/// 事件带有可重试时间，到期后按优先级从高到低取出；队列已满时按溢出策略处理。
+pub struct RetryQueue {
+    state: Mutex<QueueState>,
+    capacity: usize,
+    policy: QueueOverflowPolicy,
+    /// 有新事件入队时唤醒消费者
+    enqueued: Notify,
+    /// 有事件出队时唤醒等待空间的生产者
+    dequeued: Notify,
+}
+
+impl RetryQueue {
+    /// 创建指定容量的重试队列
+    pub fn new(capacity: usize, policy: QueueOverflowPolicy) -> Self {
+        Self {
+            state: Mutex::new(QueueState::default()),
+            capacity: capacity.max(1),
+            policy,
+            enqueued: Notify::new(),
+            dequeued: Notify::new(),
+        }
+    }
+
+    /// 在delay之后重试事件；返回因队列已满被丢弃的事件（Block策略下等待队列腾出空间）
+    pub async fn push(&self, mut event: EnhancedEvent, delay: Duration) -> Option<EnhancedEvent> {
+        loop {
+            // 先注册通知再检查容量，避免错过检查与等待之间的出队
+            let dequeued = self.dequeued.notified();
+            match self.try_enqueue(event, delay).await {
+                Ok(dropped) => return dropped,
+                Err(rejected) => {
+                    event = rejected;
+                    dequeued.await;
+                }
+            }
+        }
+    }
+
+    /// 在delay之后重试事件，从不等待；Block策略下队列已满时直接返回新事件
+    ///
+    /// 供队列的消费者使用：只有消费者会腾出空间，消费者等待空间会永远阻塞。
+    pub async fn try_push(&self, event: EnhancedEvent, delay: Duration) -> Option<EnhancedEvent> {
+        self.try_enqueue(event, delay).await.unwrap_or_else(Some)
+    }
+
+    /// 入队并返回被丢弃的事件；Block策略下队列已满时交还新事件
+    async fn try_enqueue(&self, event: EnhancedEvent, delay: Duration) -> Result<Option<EnhancedEvent>, EnhancedEvent> {
+        let mut state = self.state.lock().await;
+
+        let dropped = if state.entries.len() < self.capacity {
+            None
+        } else {
+            match self.policy {
+                QueueOverflowPolicy::Block => return Err(event),
+                QueueOverflowPolicy::DropLowestPriority => {
+                    // 优先级最低的事件中最早入队的一个
+                    let lowest = state
+                        .entries
+                        .iter()
+                        .enumerate()
+                        .min_by_key(|(_, entry)| (entry.event.priority, entry.seq))
+                        .map(|(index, entry)| (index, entry.event.priority));
+                    match lowest {
+                        Some((index, priority)) if priority < event.priority => {
+                            Some(state.entries.swap_remove(index).event)
+                        }
+                        _ => return Ok(Some(event)),
+                    }
+                }
+            }
+        };
+
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.entries.push(QueuedEvent {
+            event,
+            ready_at: Instant::now() + delay,
+            seq,
+        });
+        drop(state);
+
+        self.enqueued.notify_one();
+        Ok(dropped)
+    }
+
+    /// 取出所有已到重试时间的事件（按优先级从高到低）
+    pub async fn pop_ready(&self) -> Vec<EnhancedEvent> {
+        let now = Instant::now();
+        let mut state = self.state.lock().await;
+
+        let (mut ready, pending): (Vec<_>, Vec<_>) = state.entries.drain(..).partition(|entry| entry.ready_at <= now);
+        state.entries = pending;
+        drop(state);
+
+        if !ready.is_empty() {
+            self.dequeued.notify_waiters();
+        }
+        ready.sort_by(|a, b| b.event.priority.cmp(&a.event.priority).then(a.seq.cmp(&b.seq)));
+        ready.into_iter().map(|entry| entry.event).collect()
+    }
+
+    /// 移除创建时间超过ttl的事件
+    pub async fn take_expired(&self, ttl: Duration) -> Vec<EnhancedEvent> {
+        let now = Utc::now();
+        let mut state = self.state.lock().await;
+
+        let (expired, alive): (Vec<_>, Vec<_>) = state.entries.drain(..).partition(|entry| {
+            now.signed_duration_since(entry.event.timestamp).to_std().unwrap_or(Duration::ZERO) >= ttl
+        });
+        state.entries = alive;
+        drop(state);
+
+        if !expired.is_empty() {
+            self.dequeued.notify_waiters();
+        }
+        expired.into_iter().map(|entry| entry.event).collect()
+    }
+
+    /// 等待到最早的事件可以重试，或有新事件入队（可能提前返回，调用方取出时需重新判断）
+    pub async fn wait(&self) {
+        let next_ready = self.state.lock().await.entries.iter().map(|entry| entry.ready_at).min();
+        match next_ready {
+            Some(ready_at) => {
+                tokio::select! {
+                    _ = tokio::time::sleep_until(ready_at) => {}
+                    _ = self.enqueued.notified() => {}
+                }
+            }
+            None => self.enqueued.notified().await,
+        }
+    }
+
+    /// 当前排队的事件数量
+    pub async fn len(&self) -> usize {
+        self.state.lock().await.entries.len()
+    }
+
+    /// 队列容量
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::services::events::{EventCategory, EventPriority};
+    use crate::services::timer::timer_state::{SessionPhase, TimerEvent};
+
+    fn event(id: &str, priority: EventPriority) -> EnhancedEvent {
+        EnhancedEvent {
+            id: id.to_string(),
+            event: TimerEvent::Reset { phase: SessionPhase::Focus },
+            priority,
+            timestamp: Utc::now(),
+            category: EventCategory::Timer,
+            retry_count: 1,
+            persist: false,
+        }
+    }
+
+    fn ids(events: &[EnhancedEvent]) -> Vec<&str> {
+        events.iter().map(|event| event.id.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn ready_events_pop_by_priority_then_arrival() {
+        let queue = RetryQueue::new(10, QueueOverflowPolicy::DropLowestPriority);
+        queue.push(event("low", EventPriority::Low), Duration::ZERO).await;
+        queue.push(event("high-1", EventPriority::High), Duration::ZERO).await;
+        queue.push(event("normal", EventPriority::Normal), Duration::ZERO).await;
+        queue.push(event("high-2", EventPriority::High), Duration::ZERO).await;
+
+        assert_eq!(ids(&queue.pop_ready().await), ["high-1", "high-2", "normal", "low"]);
+        assert_eq!(queue.len().await, 0);
+    }
+
+    #[tokio::test]
+    async fn full_queue_drops_oldest_lowest_priority_event() {
+        let queue = RetryQueue::new(2, QueueOverflowPolicy::DropLowestPriority);
+        queue.push(event("low-1", EventPriority::Low), Duration::ZERO).await;
+        queue.push(event("low-2", EventPriority::Low), Duration::ZERO).await;
+
+        // 新事件优先级更高：丢弃最低优先级中最早入队的事件
+        let dropped = queue.push(event("normal", EventPriority::Normal), Duration::ZERO).await;
+        assert_eq!(dropped.map(|event| event.id), Some("low-1".to_string()));
+
+        // 新事件优先级不高于队列中最低者：丢弃新事件
+        let dropped = queue.push(event("low-3", EventPriority::Low), Duration::ZERO).await;
+        assert_eq!(dropped.map(|event| event.id), Some("low-3".to_string()));
+
+        assert_eq!(ids(&queue.pop_ready().await), ["normal", "low-2"]);
+    }
+
+    #[tokio::test]
+    async fn events_wait_until_ready_at() {
+        let queue = RetryQueue::new(10, QueueOverflowPolicy::DropLowestPriority);
+        queue.push(event("later", EventPriority::Critical), Duration::from_millis(200)).await;
+        queue.push(event("now", EventPriority::Low), Duration::ZERO).await;
+
+        assert_eq!(ids(&queue.pop_ready().await), ["now"]);
+        assert!(queue.pop_ready().await.is_empty(), "未到重试时间的事件不应取出");
+
+        // wait可能被入队通知提前唤醒，按消费者的方式循环等待
+        let ready = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                queue.wait().await;
+                let ready = queue.pop_ready().await;
+                if !ready.is_empty() {
+                    return ready;
+                }
+            }
+        })
+        .await
+        .expect("应在重试时间到达时取出");
+        assert_eq!(ids(&ready), ["later"]);
+    }
+
+    #[tokio::test]
+    async fn take_expired_removes_only_events_older_than_ttl() {
+        let queue = RetryQueue::new(10, QueueOverflowPolicy::DropLowestPriority);
+        let mut stale = event("stale", EventPriority::High);
+        stale.timestamp = Utc::now() - chrono::Duration::seconds(120);
+        queue.push(stale, Duration::from_secs(60)).await;
+        queue.push(event("fresh", EventPriority::Low), Duration::from_secs(60)).await;
+
+        assert_eq!(ids(&queue.take_expired(Duration::from_secs(60)).await), ["stale"]);
+        assert!(queue.take_expired(Duration::from_secs(60)).await.is_empty());
+        assert_eq!(queue.len().await, 1);
+    }
+
+    #[tokio::test]
+    async fn blocked_push_resumes_after_dequeue() {
+        let queue = Arc::new(RetryQueue::new(1, QueueOverflowPolicy::Block));
+        queue.push(event("first", EventPriority::Normal), Duration::ZERO).await;
+
+        let producer = tokio::spawn({
+            let queue = Arc::clone(&queue);
+            async move { queue.push(event("second", EventPriority::Critical), Duration::ZERO).await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!producer.is_finished(), "队列已满时Block策略应等待");
+
+        assert_eq!(ids(&queue.pop_ready().await), ["first"]);
+        let dropped = tokio::time::timeout(Duration::from_secs(2), producer)
+            .await
+            .expect("出队后应唤醒等待的生产者")
+            .unwrap();
+        assert!(dropped.is_none());
+        assert_eq!(ids(&queue.pop_ready().await), ["second"]);
+    }
+
+    #[tokio::test]
+    async fn try_push_never_waits_on_full_blocking_queue() {
+        let queue = RetryQueue::new(1, QueueOverflowPolicy::Block);
+        queue.push(event("first", EventPriority::Low), Duration::ZERO).await;
+
+        let retry = queue.try_push(event("retry", EventPriority::Critical), Duration::ZERO);
+        let rejected = tokio::time::timeout(Duration::from_secs(1), retry).await.expect("try_push不应等待");
+        assert_eq!(rejected.map(|event| event.id), Some("retry".to_string()));
+        assert_eq!(ids(&queue.pop_ready().await), ["first"]);
+    }
+}
@@ -52,6 +52,21 @@ impl TimerEventEmitter {
             TimerEvent::MicroBreakStatsUpdated { .. } => {
                 self.app_handle.emit("micro-break-stats-updated", &event)?;
             }
+            TimerEvent::DurationAdjusted { .. } => {
+                self.app_handle.emit("duration-adjusted", &event)?;
+            }
+            TimerEvent::PlanProgress { .. } => {
+                self.app_handle.emit("plan-progress", &event)?;
+            }
+            TimerEvent::PlanCompleted { .. } => {
+                self.app_handle.emit("plan-completed", &event)?;
+            }
+            TimerEvent::Audio { .. } => {
+                self.app_handle.emit("audio-event", &event)?;
+            }
+            TimerEvent::VisualCue { .. } => {
+                self.app_handle.emit("visual-cue", &event)?;
+            }
             TimerEvent::PhaseChanged { .. } => {
                 self.app_handle.emit("phase-changed", &event)?;
             }
@@ -0,0 +1,110 @@
+use chrono::{Datelike, Local, NaiveDate};
+use sqlx::sqlite::SqlitePool;
+use tauri::State;
+
+use crate::models::{DailyGoal, GoalType, RangeStats, StreakStats};
+use crate::services::storage::{GoalStore, StreakStore};
+use crate::services::timer_commands::DatabaseState;
+
+/// 设置某个星期几的每日目标（weekday：0为周一，6为周日）
+#[tauri::command]
+pub async fn set_daily_goal(
+    database: State<'_, DatabaseState>,
+    weekday: u32,
+    goal_type: GoalType,
+    target: i32,
+) -> Result<DailyGoal, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        let store = GoalStore::new(pool.clone());
+        let goal = store.set_daily_goal(weekday, goal_type, target).await.map_err(|e| e.to_string())?;
+        refresh_after_goal_change(pool, &store, weekday).await?;
+        Ok(goal)
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 删除某个星期几的每日目标
+#[tauri::command]
+pub async fn remove_daily_goal(
+    database: State<'_, DatabaseState>,
+    weekday: u32,
+) -> Result<(), String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        let store = GoalStore::new(pool.clone());
+        store.remove_daily_goal(weekday).await.map_err(|e| e.to_string())?;
+        refresh_after_goal_change(pool, &store, weekday).await
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 列出按星期配置的每日目标
+#[tauri::command]
+pub async fn list_daily_goals(
+    database: State<'_, DatabaseState>,
+) -> Result<Vec<DailyGoal>, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        GoalStore::new(pool.clone())
+            .list_daily_goals()
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 获取日期范围（含首尾，本地日期）内的专注统计与目标达成情况
+#[tauri::command]
+pub async fn get_range_stats(
+    database: State<'_, DatabaseState>,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<RangeStats, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        GoalStore::new(pool.clone())
+            .range_stats(start_date, end_date)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 获取连续达成天数与最近7/30天的目标一致性得分
+#[tauri::command]
+pub async fn get_streak_stats(
+    database: State<'_, DatabaseState>,
+) -> Result<StreakStats, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        StreakStore::new(pool.clone())
+            .get_streak_stats(Local::now().date_naive())
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 目标配置变化后刷新今天的目标历史快照（若受影响）并全量重算连续天数
+async fn refresh_after_goal_change(pool: &SqlitePool, store: &GoalStore, weekday: u32) -> Result<(), String> {
+    let today = Local::now().date_naive();
+    if today.weekday().num_days_from_monday() == weekday {
+        store.record_day_progress(today).await.map_err(|e| e.to_string())?;
+    }
+    StreakStore::new(pool.clone())
+        .recompute(today)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
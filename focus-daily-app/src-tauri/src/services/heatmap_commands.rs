@@ -0,0 +1,32 @@
+use chrono::{DateTime, Local, Utc};
+use tauri::State;
+
+use crate::models::FocusHeatmap;
+use crate::services::analytics::build_heatmap;
+use crate::services::storage::SessionStore;
+use crate::services::timer_commands::DatabaseState;
+
+/// 获取时间范围内按星期 × 小时（本地时间）统计的专注分钟与平均质量评分
+#[tauri::command]
+pub async fn get_focus_heatmap(
+    database: State<'_, DatabaseState>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<FocusHeatmap, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        let spans = SessionStore::new(pool.clone())
+            .focus_spans(&start, &end)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(FocusHeatmap {
+            start,
+            end,
+            cells: build_heatmap(&spans, &Local),
+        })
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
@@ -0,0 +1,135 @@
+use chrono::{DateTime, Utc};
+use tauri::State;
+
+use crate::models::{LabelFocusStats, Project, SessionLabels, Tag};
+use crate::services::storage::LabelStore;
+use crate::services::timer_commands::DatabaseState;
+
+/// 创建项目
+#[tauri::command]
+pub async fn create_project(
+    database: State<'_, DatabaseState>,
+    name: String,
+    parent_id: Option<String>,
+) -> Result<Project, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        LabelStore::new(pool.clone())
+            .create_project(&name, parent_id.as_deref())
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 列出全部项目
+#[tauri::command]
+pub async fn list_projects(
+    database: State<'_, DatabaseState>,
+) -> Result<Vec<Project>, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        LabelStore::new(pool.clone())
+            .list_projects()
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 列出全部标签
+#[tauri::command]
+pub async fn list_tags(
+    database: State<'_, DatabaseState>,
+) -> Result<Vec<Tag>, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        LabelStore::new(pool.clone())
+            .list_tags()
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 设置会话的项目和标签（会话进行中或完成后均可调用）
+#[tauri::command]
+pub async fn set_session_labels(
+    database: State<'_, DatabaseState>,
+    session_id: String,
+    project_id: Option<String>,
+    tags: Vec<String>,
+) -> Result<SessionLabels, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        LabelStore::new(pool.clone())
+            .set_session_labels(&session_id, project_id.as_deref(), &tags)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 获取会话的项目和标签
+#[tauri::command]
+pub async fn get_session_labels(
+    database: State<'_, DatabaseState>,
+    session_id: String,
+) -> Result<SessionLabels, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        LabelStore::new(pool.clone())
+            .get_session_labels(&session_id)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 按标签统计时间范围内的专注数据
+#[tauri::command]
+pub async fn get_tag_stats(
+    database: State<'_, DatabaseState>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<LabelFocusStats>, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        LabelStore::new(pool.clone())
+            .tag_stats(&start, &end)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 按项目统计时间范围内的专注数据
+#[tauri::command]
+pub async fn get_project_stats(
+    database: State<'_, DatabaseState>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<Vec<LabelFocusStats>, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        LabelStore::new(pool.clone())
+            .project_stats(&start, &end)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
@@ -1,5 +1,26 @@
 pub mod timer;
 pub mod events;
+pub mod audio;
+pub mod storage;
+pub mod analytics;
 pub mod timer_commands;
+pub mod task_commands;
+pub mod label_commands;
+pub mod rating_commands;
+pub mod goal_commands;
+pub mod report_commands;
+pub mod heatmap_commands;
+pub mod break_commands;
+pub mod sequence_commands;
+pub mod audio_commands;
 
-pub use timer_commands::*; 
\ No newline at end of file
+pub use timer_commands::*;
+pub use task_commands::*;
+pub use label_commands::*;
+pub use rating_commands::*;
+pub use goal_commands::*;
+pub use report_commands::*;
+pub use heatmap_commands::*;
+pub use break_commands::*;
+pub use sequence_commands::*;
+pub use audio_commands::*;
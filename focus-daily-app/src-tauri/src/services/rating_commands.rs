@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use tauri::State;
+
+use crate::models::{RatingCorrelationStats, SessionRating};
+use crate::services::analytics::correlate_ratings;
+use crate::services::storage::RatingStore;
+use crate::services::timer_commands::DatabaseState;
+
+/// 评价专注会话（评分1-5、分心次数、备注）
+#[tauri::command]
+pub async fn rate_session(
+    database: State<'_, DatabaseState>,
+    session_id: String,
+    score: i32,
+    distractions: i32,
+    note: Option<String>,
+) -> Result<SessionRating, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        RatingStore::new(pool.clone())
+            .rate_session(&session_id, score, distractions, note)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 获取会话评价
+#[tauri::command]
+pub async fn get_session_rating(
+    database: State<'_, DatabaseState>,
+    session_id: String,
+) -> Result<SessionRating, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        RatingStore::new(pool.clone())
+            .get_rating(&session_id)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 获取评分与微休息完成率、中断次数的相关性统计
+#[tauri::command]
+pub async fn get_rating_stats(
+    database: State<'_, DatabaseState>,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<RatingCorrelationStats, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        let samples = RatingStore::new(pool.clone())
+            .rated_samples(&start, &end)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(correlate_ratings(&samples))
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
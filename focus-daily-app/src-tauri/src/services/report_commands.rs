@@ -0,0 +1,71 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, Local, NaiveDate};
+use tauri::{AppHandle, Manager, State};
+
+use crate::models::{SavedWeeklyReport, WeeklyReport};
+use crate::services::analytics::{render_html, render_markdown};
+use crate::services::storage::ReportStore;
+use crate::services::timer_commands::DatabaseState;
+
+/// 报告默认保存在应用数据目录下的子目录
+const REPORTS_DIR: &str = "reports";
+
+/// 生成每周回顾报告，并以JSON、Markdown和HTML保存到本地目录
+///
+/// `week_of`为该周内任意一天，默认为上一周；`output_dir`默认为应用数据目录下的reports。
+#[tauri::command]
+pub async fn generate_weekly_report(
+    app_handle: AppHandle,
+    database: State<'_, DatabaseState>,
+    week_of: Option<NaiveDate>,
+    output_dir: Option<String>,
+) -> Result<SavedWeeklyReport, String> {
+    let database_guard = database.read().await;
+
+    let Some(pool) = database_guard.as_ref() else {
+        return Err("数据库未连接".to_string());
+    };
+
+    let date = week_of.unwrap_or_else(|| Local::now().date_naive() - Duration::days(7));
+    let report = ReportStore::new(pool.clone())
+        .weekly_report(date)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let dir = match output_dir {
+        Some(dir) => PathBuf::from(dir),
+        None => app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| format!("无法获取应用数据目录: {}", e))?
+            .join(REPORTS_DIR),
+    };
+    save_weekly_report(&dir, report)
+}
+
+/// 将报告写入目录，文件名为`weekly-<周一日期>.{json,md,html}`
+fn save_weekly_report(dir: &Path, report: WeeklyReport) -> Result<SavedWeeklyReport, String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("无法创建报告目录: {}", e))?;
+
+    let base = format!("weekly-{}", report.week_start);
+    let json_path = dir.join(format!("{}.json", base));
+    let markdown_path = dir.join(format!("{}.md", base));
+    let html_path = dir.join(format!("{}.html", base));
+
+    let json = serde_json::to_string_pretty(&report).map_err(|e| format!("报告序列化失败: {}", e))?;
+    for (path, content) in [
+        (&json_path, json),
+        (&markdown_path, render_markdown(&report)),
+        (&html_path, render_html(&report)),
+    ] {
+        std::fs::write(path, content).map_err(|e| format!("无法写入报告 {}: {}", path.display(), e))?;
+    }
+
+    Ok(SavedWeeklyReport {
+        report,
+        json_path: json_path.to_string_lossy().into_owned(),
+        markdown_path: markdown_path.to_string_lossy().into_owned(),
+        html_path: html_path.to_string_lossy().into_owned(),
+    })
+}
@@ -0,0 +1,103 @@
+use tauri::State;
+
+use crate::models::{PhaseSequence, SequenceStep};
+use crate::services::storage::SequenceStore;
+use crate::services::timer::FocusSessionOptions;
+use crate::services::timer_commands::{DatabaseState, TimerManagerState};
+
+/// 创建阶段序列
+#[tauri::command]
+pub async fn create_phase_sequence(
+    database: State<'_, DatabaseState>,
+    name: String,
+    steps: Vec<SequenceStep>,
+) -> Result<PhaseSequence, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        SequenceStore::new(pool.clone())
+            .create_sequence(&name, steps)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 更新阶段序列
+#[tauri::command]
+pub async fn update_phase_sequence(
+    database: State<'_, DatabaseState>,
+    sequence_id: String,
+    name: String,
+    steps: Vec<SequenceStep>,
+) -> Result<PhaseSequence, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        SequenceStore::new(pool.clone())
+            .update_sequence(&sequence_id, &name, steps)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 删除阶段序列
+#[tauri::command]
+pub async fn delete_phase_sequence(
+    database: State<'_, DatabaseState>,
+    sequence_id: String,
+) -> Result<(), String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        SequenceStore::new(pool.clone())
+            .delete_sequence(&sequence_id)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 列出全部阶段序列（包括内置的90/20序列）
+#[tauri::command]
+pub async fn list_phase_sequences(
+    timer_manager: State<'_, TimerManagerState>,
+) -> Result<Vec<PhaseSequence>, String> {
+    let manager_guard = timer_manager.read().await;
+
+    if let Some(manager) = manager_guard.as_ref() {
+        manager.list_phase_sequences().await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("定时器管理器未初始化".to_string())
+    }
+}
+
+/// 按阶段序列开始计划（可选关联任务、项目和标签）
+#[tauri::command]
+pub async fn start_phase_sequence(
+    timer_manager: State<'_, TimerManagerState>,
+    sequence_id: String,
+    task_id: Option<String>,
+    project_id: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<String, String> {
+    let manager_guard = timer_manager.read().await;
+
+    if let Some(manager) = manager_guard.as_ref() {
+        let options = FocusSessionOptions {
+            task_id,
+            project_id,
+            tags: tags.unwrap_or_default(),
+            flow_mode: None,
+        };
+        manager.start_phase_sequence(&sequence_id, options).await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("定时器管理器未初始化".to_string())
+    }
+}
@@ -0,0 +1,224 @@
+use std::path::Path;
+
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+
+use crate::database::now_timestamp;
+use crate::models::{AmbientBreakBehavior, AmbientSettings, AmbientSource, UpdateAmbientSettings};
+use crate::services::audio::MAX_SOUND_BYTES;
+use super::StorageError;
+
+/// 默认设置档案（迁移中插入的用户设置）
+pub const DEFAULT_PROFILE_ID: &str = "default_settings";
+
+/// 淡出时间上限（秒）
+const MAX_FADE_OUT_SECONDS: u32 = 120;
+
+/// 专注背景音设置存储（ambient_settings表，按设置档案保存）
+#[derive(Clone)]
+pub struct AmbientStore {
+    pool: SqlitePool,
+}
+
+impl AmbientStore {
+    /// 创建新的背景音设置存储
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// 获取档案的背景音设置（未保存过时返回默认值）
+    pub async fn get_settings(&self, profile_id: &str) -> Result<AmbientSettings, StorageError> {
+        let row = sqlx::query(
+            "SELECT profile_id, enabled, source, file_path, volume, micro_break_behavior, duck_level,
+                    fade_out_seconds, updated_at
+             FROM ambient_settings WHERE profile_id = ?",
+        )
+        .bind(profile_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => Self::row_to_settings(&row),
+            None => Ok(AmbientSettings::defaults_for(profile_id)),
+        }
+    }
+
+    /// 更新档案的背景音设置
+    pub async fn update_settings(&self, profile_id: &str, input: UpdateAmbientSettings) -> Result<AmbientSettings, StorageError> {
+        let mut settings = self.get_settings(profile_id).await?;
+
+        if let Some(enabled) = input.enabled {
+            settings.enabled = enabled;
+        }
+        if let Some(source) = input.source {
+            settings.source = source;
+        }
+        if let Some(file_path) = input.file_path {
+            settings.file_path = Some(file_path).filter(|path| !path.trim().is_empty());
+        }
+        if let Some(volume) = input.volume {
+            settings.volume = volume;
+        }
+        if let Some(behavior) = input.micro_break_behavior {
+            settings.micro_break_behavior = behavior;
+        }
+        if let Some(duck_level) = input.duck_level {
+            settings.duck_level = duck_level;
+        }
+        if let Some(fade_out_seconds) = input.fade_out_seconds {
+            settings.fade_out_seconds = fade_out_seconds;
+        }
+        self.validate(&settings).await?;
+
+        sqlx::query(
+            "INSERT INTO ambient_settings (profile_id, enabled, source, file_path, volume, micro_break_behavior,
+                                           duck_level, fade_out_seconds, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(profile_id) DO UPDATE SET
+                enabled = excluded.enabled,
+                source = excluded.source,
+                file_path = excluded.file_path,
+                volume = excluded.volume,
+                micro_break_behavior = excluded.micro_break_behavior,
+                duck_level = excluded.duck_level,
+                fade_out_seconds = excluded.fade_out_seconds,
+                updated_at = excluded.updated_at",
+        )
+        .bind(profile_id)
+        .bind(settings.enabled)
+        .bind(settings.source.as_str())
+        .bind(&settings.file_path)
+        .bind(settings.volume as f64)
+        .bind(settings.micro_break_behavior.as_str())
+        .bind(settings.duck_level as f64)
+        .bind(settings.fade_out_seconds as i64)
+        .bind(now_timestamp())
+        .execute(&self.pool)
+        .await?;
+
+        self.get_settings(profile_id).await
+    }
+
+    /// 校验设置；文件声源只能使用音效库中的音效（导入时已限制大小和时长）
+    async fn validate(&self, settings: &AmbientSettings) -> Result<(), StorageError> {
+        if !(0.0..=1.0).contains(&settings.volume) {
+            return Err(StorageError::InvalidInput("背景音音量必须在0到1之间".to_string()));
+        }
+        if !(0.0..=1.0).contains(&settings.duck_level) {
+            return Err(StorageError::InvalidInput("降低音量比例必须在0到1之间".to_string()));
+        }
+        if settings.fade_out_seconds > MAX_FADE_OUT_SECONDS {
+            return Err(StorageError::InvalidInput(format!("淡出时间不能超过{}秒", MAX_FADE_OUT_SECONDS)));
+        }
+        if settings.source == AmbientSource::File {
+            let path = settings.file_path.as_deref()
+                .ok_or_else(|| StorageError::InvalidInput("请选择背景音文件".to_string()))?;
+            let in_library: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM custom_sounds WHERE file_path = ?)")
+                .bind(path)
+                .fetch_one(&self.pool)
+                .await?;
+            if !in_library {
+                return Err(StorageError::InvalidInput("背景音文件需要先导入音效库".to_string()));
+            }
+            match std::fs::metadata(Path::new(path)) {
+                Ok(metadata) if metadata.is_file() && metadata.len() <= MAX_SOUND_BYTES => {}
+                Ok(metadata) if metadata.is_file() => {
+                    return Err(StorageError::InvalidInput(format!(
+                        "背景音文件不能超过{}MB",
+                        MAX_SOUND_BYTES / 1024 / 1024
+                    )));
+                }
+                _ => return Err(StorageError::InvalidInput(format!("背景音文件不存在: {}", path))),
+            }
+        }
+        Ok(())
+    }
+
+    fn row_to_settings(row: &SqliteRow) -> Result<AmbientSettings, StorageError> {
+        let source: String = row.try_get("source")?;
+        let behavior: String = row.try_get("micro_break_behavior")?;
+
+        Ok(AmbientSettings {
+            profile_id: row.try_get("profile_id")?,
+            enabled: row.try_get("enabled")?,
+            source: AmbientSource::parse(&source)
+                .ok_or_else(|| StorageError::InvalidInput(format!("未知的背景音声源: {}", source)))?,
+            file_path: row.try_get("file_path")?,
+            volume: row.try_get::<f64, _>("volume")? as f32,
+            micro_break_behavior: AmbientBreakBehavior::parse(&behavior)
+                .ok_or_else(|| StorageError::InvalidInput(format!("未知的微休息处理方式: {}", behavior)))?,
+            duck_level: row.try_get::<f64, _>("duck_level")? as f32,
+            fade_out_seconds: row.try_get::<i64, _>("fade_out_seconds")?.max(0) as u32,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::storage::{NewCustomSound, SoundStore};
+    use crate::test_support::migrated_pool;
+
+    fn use_file(path: &Path) -> UpdateAmbientSettings {
+        UpdateAmbientSettings {
+            enabled: Some(true),
+            source: Some(AmbientSource::File),
+            file_path: Some(path.display().to_string()),
+            volume: None,
+            micro_break_behavior: None,
+            duck_level: None,
+            fade_out_seconds: None,
+        }
+    }
+
+    async fn add_to_library(pool: &SqlitePool, path: &Path) {
+        SoundStore::new(pool.clone())
+            .insert_sound(&NewCustomSound {
+                id: "rain".to_string(),
+                name: "雨声".to_string(),
+                file_path: path.display().to_string(),
+                format: "wav".to_string(),
+                size_bytes: 1024,
+                duration_ms: 2000,
+                loudness_db: -18.0,
+                peak_db: -6.0,
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn file_source_must_come_from_sound_library() {
+        let (dir, pool) = migrated_pool().await;
+        let store = AmbientStore::new(pool.clone());
+        let path = dir.path().join("rain.wav");
+        std::fs::write(&path, [0u8; 1024]).unwrap();
+
+        let result = store.update_settings(DEFAULT_PROFILE_ID, use_file(&path)).await;
+        assert!(matches!(result, Err(StorageError::InvalidInput(_))));
+
+        add_to_library(&pool, &path).await;
+        let settings = store.update_settings(DEFAULT_PROFILE_ID, use_file(&path)).await.unwrap();
+        assert_eq!(settings.source, AmbientSource::File);
+        assert_eq!(settings.file_path, Some(path.display().to_string()));
+    }
+
+    #[tokio::test]
+    async fn oversized_or_missing_file_is_rejected() {
+        let (dir, pool) = migrated_pool().await;
+        let store = AmbientStore::new(pool.clone());
+        let path = dir.path().join("rain.wav");
+        add_to_library(&pool, &path).await;
+
+        // 文件已被删除
+        let result = store.update_settings(DEFAULT_PROFILE_ID, use_file(&path)).await;
+        assert!(matches!(result, Err(StorageError::InvalidInput(_))));
+
+        // 导入后被替换成超过上限的文件
+        std::fs::write(&path, vec![0u8; MAX_SOUND_BYTES as usize + 1]).unwrap();
+        let result = store.update_settings(DEFAULT_PROFILE_ID, use_file(&path)).await;
+        assert!(matches!(result, Err(StorageError::InvalidInput(_))));
+        assert_eq!(store.get_settings(DEFAULT_PROFILE_ID).await.unwrap().source, AmbientSource::BrownNoise);
+    }
+}
@@ -0,0 +1,121 @@
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+
+use crate::database::now_timestamp;
+use crate::models::{AudioConfig, AudioType, UpdateAudioConfig};
+use super::StorageError;
+
+/// 预警提示音最长提前时间（秒）
+const MAX_LEAD_SECONDS: u32 = 3600;
+
+/// 提示音配置存储（audio_configs表）
+#[derive(Clone)]
+pub struct AudioConfigStore {
+    pool: SqlitePool,
+}
+
+impl AudioConfigStore {
+    /// 创建新的提示音配置存储
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// 获取指定类型的提示音配置（没有配置时为None）
+    pub async fn get_config(&self, config_type: AudioType) -> Result<Option<AudioConfig>, StorageError> {
+        let row = sqlx::query(
+            "SELECT id, config_type, file_path, is_default, volume, enabled, lead_seconds, created_at, updated_at
+             FROM audio_configs WHERE config_type = ? ORDER BY updated_at DESC LIMIT 1",
+        )
+        .bind(config_type.as_str())
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.as_ref().map(Self::row_to_config).transpose()
+    }
+
+    /// 列出全部提示音配置
+    pub async fn list_configs(&self) -> Result<Vec<AudioConfig>, StorageError> {
+        let rows = sqlx::query(
+            "SELECT id, config_type, file_path, is_default, volume, enabled, lead_seconds, created_at, updated_at
+             FROM audio_configs ORDER BY created_at, id",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_config).collect()
+    }
+
+    /// 更新提示音的启用状态、音量和预警提前时间
+    pub async fn update_config(&self, config_type: AudioType, input: &UpdateAudioConfig) -> Result<AudioConfig, StorageError> {
+        if input.volume.is_some_and(|volume| !(0.0..=1.0).contains(&volume)) {
+            return Err(StorageError::InvalidInput("音量必须在0到1之间".to_string()));
+        }
+        if let Some(lead_seconds) = input.lead_seconds {
+            if config_type.default_lead_seconds().is_none() {
+                return Err(StorageError::InvalidInput(format!("{} 不是预警提示音", config_type.as_str())));
+            }
+            if lead_seconds == 0 || lead_seconds > MAX_LEAD_SECONDS {
+                return Err(StorageError::InvalidInput(format!("预警提前时间必须在1到{}秒之间", MAX_LEAD_SECONDS)));
+            }
+        }
+
+        let result = sqlx::query(
+            "UPDATE audio_configs SET
+                volume = COALESCE(?, volume),
+                enabled = COALESCE(?, enabled),
+                lead_seconds = COALESCE(?, lead_seconds),
+                updated_at = ?
+             WHERE config_type = ?",
+        )
+        .bind(input.volume.map(f64::from))
+        .bind(input.enabled)
+        .bind(input.lead_seconds.map(i64::from))
+        .bind(now_timestamp())
+        .bind(config_type.as_str())
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound(format!("提示音配置 {}", config_type.as_str())));
+        }
+
+        self.get_config(config_type)
+            .await?
+            .ok_or_else(|| StorageError::NotFound(format!("提示音配置 {}", config_type.as_str())))
+    }
+
+    /// 为提示音指定自定义文件；为None时恢复默认提示音
+    pub async fn assign_file(&self, config_type: AudioType, file_path: Option<&str>) -> Result<AudioConfig, StorageError> {
+        let result = sqlx::query("UPDATE audio_configs SET file_path = ?, is_default = ?, updated_at = ? WHERE config_type = ?")
+            .bind(file_path)
+            .bind(file_path.is_none())
+            .bind(now_timestamp())
+            .bind(config_type.as_str())
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound(format!("提示音配置 {}", config_type.as_str())));
+        }
+
+        self.get_config(config_type)
+            .await?
+            .ok_or_else(|| StorageError::NotFound(format!("提示音配置 {}", config_type.as_str())))
+    }
+
+    fn row_to_config(row: &SqliteRow) -> Result<AudioConfig, StorageError> {
+        let config_type: String = row.try_get("config_type")?;
+        let config_type = AudioType::parse(&config_type)
+            .ok_or_else(|| StorageError::InvalidInput(format!("未知的提示音类型: {}", config_type)))?;
+
+        Ok(AudioConfig {
+            id: row.try_get("id")?,
+            config_type,
+            file_path: row.try_get("file_path")?,
+            is_default: row.try_get("is_default")?,
+            volume: row.try_get::<f64, _>("volume")? as f32,
+            enabled: row.try_get("enabled")?,
+            lead_seconds: row.try_get::<Option<i64>, _>("lead_seconds")?.map(|seconds| seconds.max(0) as u32),
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
@@ -0,0 +1,241 @@
+use chrono::Utc;
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+
+use crate::database::format_timestamp;
+use crate::services::events::{EnhancedEvent, EventCategory, EventHistoryQuery, EventPriority};
+use super::StorageError;
+
+/// 查询条件：?1起始时间、?2结束时间、?3类别，均可为空
+const FILTER: &str = "(?1 IS NULL OR created_at >= ?1)
+       AND (?2 IS NULL OR created_at < ?2)
+       AND (?3 IS NULL OR category = ?3)";
+
+/// 事件日志的保留策略：超过保留时长或超出行数上限的最旧事件在写入时清理
+#[derive(Debug, Clone, Copy)]
+pub struct EventLogRetention {
+    /// 事件保留时长
+    pub max_age: chrono::Duration,
+    /// 最多保留的事件数量
+    pub max_rows: usize,
+}
+
+impl Default for EventLogRetention {
+    fn default() -> Self {
+        Self {
+            max_age: chrono::Duration::days(90),
+            max_rows: 10_000,
+        }
+    }
+}
+
+/// 事件日志存储（event_log表，保存需要持久化的事件）
+#[derive(Clone)]
+pub struct EventLogStore {
+    pool: SqlitePool,
+    retention: EventLogRetention,
+}
+
+impl EventLogStore {
+    /// 创建新的事件日志存储（使用默认保留策略）
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            pool,
+            retention: EventLogRetention::default(),
+        }
+    }
+
+    /// 设置保留策略
+    pub fn with_retention(mut self, retention: EventLogRetention) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// 追加事件，并按保留策略清理旧事件
+    pub async fn append(&self, event: &EnhancedEvent, event_name: &str) -> Result<(), StorageError> {
+        let payload = serde_json::to_string(&event.event)
+            .map_err(|e| StorageError::InvalidInput(format!("事件无法序列化: {}", e)))?;
+
+        sqlx::query(
+            "INSERT OR IGNORE INTO event_log (id, event_name, category, priority, payload, created_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&event.id)
+        .bind(event_name)
+        .bind(event.category.as_str())
+        .bind(event.priority.as_str())
+        .bind(payload)
+        .bind(format_timestamp(&event.timestamp))
+        .execute(&self.pool)
+        .await?;
+
+        self.prune().await?;
+        Ok(())
+    }
+
+    /// 删除超过保留时长的事件和超出行数上限的最旧事件，返回删除的数量
+    pub async fn prune(&self) -> Result<u64, StorageError> {
+        let cutoff = Utc::now() - self.retention.max_age;
+        let result = sqlx::query(
+            "DELETE FROM event_log
+             WHERE created_at < ?
+                OR id IN (SELECT id FROM event_log ORDER BY created_at DESC, id DESC LIMIT -1 OFFSET ?)",
+        )
+        .bind(format_timestamp(&cutoff))
+        .bind(self.retention.max_rows as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// 符合时间范围和类别条件的最新count个事件（最新的在前）及事件总数
+    ///
+    /// 忽略查询中的分页参数，由调用方与内存中的事件合并后分页。
+    pub async fn latest(&self, query: &EventHistoryQuery, count: usize) -> Result<(Vec<EnhancedEvent>, u64), StorageError> {
+        let start = query.start.as_ref().map(format_timestamp);
+        let end = query.end.as_ref().map(format_timestamp);
+        let category = query.category.map(|category| category.as_str());
+
+        let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM event_log WHERE {}", FILTER))
+            .bind(&start)
+            .bind(&end)
+            .bind(category)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let rows = sqlx::query(&format!(
+            "SELECT id, category, priority, payload, created_at
+             FROM event_log
+             WHERE {}
+             ORDER BY created_at DESC, id DESC
+             LIMIT ?4",
+            FILTER
+        ))
+        .bind(&start)
+        .bind(&end)
+        .bind(category)
+        .bind(count as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let events = rows.iter().map(Self::row_to_event).collect::<Result<_, _>>()?;
+        Ok((events, total.max(0) as u64))
+    }
+
+    fn row_to_event(row: &SqliteRow) -> Result<EnhancedEvent, StorageError> {
+        let category: String = row.try_get("category")?;
+        let priority: String = row.try_get("priority")?;
+        let payload: String = row.try_get("payload")?;
+
+        Ok(EnhancedEvent {
+            id: row.try_get("id")?,
+            event: serde_json::from_str(&payload)
+                .map_err(|e| StorageError::InvalidInput(format!("无法解析事件内容: {}", e)))?,
+            priority: EventPriority::parse(&priority)
+                .ok_or_else(|| StorageError::InvalidInput(format!("未知的事件优先级: {}", priority)))?,
+            timestamp: row.try_get("created_at")?,
+            category: EventCategory::parse(&category)
+                .ok_or_else(|| StorageError::InvalidInput(format!("未知的事件类别: {}", category)))?,
+            retry_count: 0,
+            persist: true,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Duration};
+
+    use super::*;
+    use crate::services::timer::timer_state::{SessionPhase, TimerEvent};
+    use crate::test_support::migrated_pool;
+
+    fn event(id: &str, category: EventCategory, timestamp: DateTime<Utc>) -> EnhancedEvent {
+        EnhancedEvent {
+            id: id.to_string(),
+            event: TimerEvent::Completed { phase: SessionPhase::Focus, session_id: id.to_string() },
+            priority: EventPriority::Normal,
+            timestamp,
+            category,
+            retry_count: 0,
+            persist: true,
+        }
+    }
+
+    fn ids(events: &[EnhancedEvent]) -> Vec<&str> {
+        events.iter().map(|event| event.id.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn latest_filters_by_time_range_and_category() {
+        let (_dir, pool) = migrated_pool().await;
+        let store = EventLogStore::new(pool);
+        let base = Utc::now() - Duration::hours(1);
+        for (id, category, minutes) in [
+            ("a", EventCategory::Timer, 0),
+            ("b", EventCategory::Analytics, 10),
+            ("c", EventCategory::Timer, 20),
+            ("d", EventCategory::Timer, 30),
+        ] {
+            store.append(&event(id, category, base + Duration::minutes(minutes)), "timer-completed").await.unwrap();
+        }
+
+        let (events, total) = store.latest(&EventHistoryQuery::default(), 2).await.unwrap();
+        assert_eq!(ids(&events), ["d", "c"]);
+        assert_eq!(total, 4);
+
+        // 时间范围为[start, end)
+        let range = EventHistoryQuery {
+            start: Some(base + Duration::minutes(10)),
+            end: Some(base + Duration::minutes(30)),
+            ..EventHistoryQuery::default()
+        };
+        let (events, total) = store.latest(&range, 10).await.unwrap();
+        assert_eq!(ids(&events), ["c", "b"]);
+        assert_eq!(total, 2);
+
+        let timer_only = EventHistoryQuery { category: Some(EventCategory::Timer), ..range };
+        let (events, total) = store.latest(&timer_only, 10).await.unwrap();
+        assert_eq!(ids(&events), ["c"]);
+        assert_eq!(total, 1);
+
+        let (events, _) = store.latest(&EventHistoryQuery::default(), 0).await.unwrap();
+        assert!(events.is_empty());
+    }
+
+    #[tokio::test]
+    async fn append_removes_events_older_than_max_age() {
+        let (_dir, pool) = migrated_pool().await;
+        let store = EventLogStore::new(pool).with_retention(EventLogRetention {
+            max_age: Duration::days(1),
+            max_rows: 100,
+        });
+        let now = Utc::now();
+
+        store.append(&event("old", EventCategory::Timer, now - Duration::days(2)), "timer-completed").await.unwrap();
+        store.append(&event("recent", EventCategory::Timer, now - Duration::hours(1)), "timer-completed").await.unwrap();
+
+        let (events, total) = store.latest(&EventHistoryQuery::default(), 10).await.unwrap();
+        assert_eq!(ids(&events), ["recent"]);
+        assert_eq!(total, 1);
+    }
+
+    #[tokio::test]
+    async fn append_keeps_only_newest_rows_within_cap() {
+        let (_dir, pool) = migrated_pool().await;
+        let store = EventLogStore::new(pool).with_retention(EventLogRetention {
+            max_age: Duration::days(90),
+            max_rows: 3,
+        });
+        let base = Utc::now() - Duration::hours(1);
+        for (index, id) in ["e1", "e2", "e3", "e4", "e5"].into_iter().enumerate() {
+            store.append(&event(id, EventCategory::Timer, base + Duration::minutes(index as i64)), "timer-completed").await.unwrap();
+        }
+
+        let (events, total) = store.latest(&EventHistoryQuery::default(), 10).await.unwrap();
+        assert_eq!(ids(&events), ["e5", "e4", "e3"]);
+        assert_eq!(total, 3);
+        assert_eq!(store.prune().await.unwrap(), 0);
+    }
+}
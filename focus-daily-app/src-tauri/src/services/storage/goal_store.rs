@@ -0,0 +1,469 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, TimeZone, Utc};
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+
+use crate::database::{format_timestamp, now_timestamp};
+use crate::models::{DailyGoal, DailyGoalProgress, GoalType, RangeStats};
+use super::StorageError;
+
+/// 单次范围统计允许的最大天数
+const MAX_RANGE_DAYS: i64 = 366;
+
+/// 目标历史中保存的当天目标快照
+struct GoalSnapshot {
+    goal_type: GoalType,
+    target: i32,
+    reached_at: Option<DateTime<Utc>>,
+}
+
+/// 每日目标存储（daily_goals表与goal_history表）
+///
+/// 日期均为本地日期；一天的专注数据按会话开始时间落在本地日界内计算。
+#[derive(Clone)]
+pub struct GoalStore {
+    pool: SqlitePool,
+}
+
+impl GoalStore {
+    /// 创建新的目标存储
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// 设置某个星期几的每日目标（weekday：0为周一，6为周日）
+    pub async fn set_daily_goal(&self, weekday: u32, goal_type: GoalType, target: i32) -> Result<DailyGoal, StorageError> {
+        if weekday > 6 {
+            return Err(StorageError::InvalidInput("星期必须在0-6之间（0为周一）".to_string()));
+        }
+        if target < 1 {
+            return Err(StorageError::InvalidInput("目标值至少为1".to_string()));
+        }
+        if goal_type == GoalType::FocusMinutes && target > 24 * 60 {
+            return Err(StorageError::InvalidInput("专注分钟目标不能超过一天".to_string()));
+        }
+
+        let now = now_timestamp();
+        sqlx::query(
+            "INSERT INTO daily_goals (weekday, goal_type, target, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT(weekday) DO UPDATE SET
+                 goal_type = excluded.goal_type,
+                 target = excluded.target,
+                 updated_at = excluded.updated_at",
+        )
+        .bind(weekday)
+        .bind(goal_type.as_str())
+        .bind(target)
+        .bind(&now)
+        .bind(&now)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_daily_goal(weekday)
+            .await?
+            .ok_or_else(|| StorageError::NotFound(format!("星期 {} 的目标", weekday)))
+    }
+
+    /// 删除某个星期几的每日目标
+    pub async fn remove_daily_goal(&self, weekday: u32) -> Result<(), StorageError> {
+        let result = sqlx::query("DELETE FROM daily_goals WHERE weekday = ?")
+            .bind(weekday)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound(format!("星期 {} 的目标", weekday)));
+        }
+        Ok(())
+    }
+
+    /// 获取某个星期几的每日目标
+    pub async fn get_daily_goal(&self, weekday: u32) -> Result<Option<DailyGoal>, StorageError> {
+        let row = sqlx::query("SELECT * FROM daily_goals WHERE weekday = ?")
+            .bind(weekday)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.as_ref().map(Self::row_to_goal).transpose()
+    }
+
+    /// 列出全部每日目标（按星期排序）
+    pub async fn list_daily_goals(&self) -> Result<Vec<DailyGoal>, StorageError> {
+        let rows = sqlx::query("SELECT * FROM daily_goals ORDER BY weekday")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(Self::row_to_goal).collect()
+    }
+
+    /// 重新计算某天的目标进度并写入目标历史
+    ///
+    /// 返回当天进度以及目标是否在这次计算中首次达成。
+    pub async fn record_day_progress(&self, date: NaiveDate) -> Result<(DailyGoalProgress, bool), StorageError> {
+        let (start, end) = local_day_bounds(date);
+        let mut tx = self.pool.begin().await?;
+
+        let totals = sqlx::query(
+            "SELECT COALESCE(SUM(duration_seconds + COALESCE(overtime_seconds, 0)), 0) AS focus_seconds,
+                    COUNT(*) AS completed_cycles
+             FROM focus_sessions
+             WHERE session_type IN ('Focus', 'OpenFocus') AND completed = 1 AND start_time >= ? AND start_time < ?",
+        )
+        .bind(format_timestamp(&start))
+        .bind(format_timestamp(&end))
+        .fetch_one(&mut *tx)
+        .await?;
+        let focus_seconds: i64 = totals.try_get("focus_seconds")?;
+        let completed_cycles: i64 = totals.try_get("completed_cycles")?;
+
+        let previous = sqlx::query("SELECT reached, reached_at FROM goal_history WHERE date = ?")
+            .bind(date)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let (was_reached, previous_reached_at) = match previous {
+            Some(row) => (row.try_get::<bool, _>("reached")?, row.try_get::<Option<DateTime<Utc>>, _>("reached_at")?),
+            None => (false, None),
+        };
+
+        let goal = sqlx::query("SELECT * FROM daily_goals WHERE weekday = ?")
+            .bind(date.weekday().num_days_from_monday())
+            .fetch_optional(&mut *tx)
+            .await?
+            .as_ref()
+            .map(Self::row_to_goal)
+            .transpose()?;
+
+        let Some(goal) = goal else {
+            // 当天没有目标时不保留历史快照
+            sqlx::query("DELETE FROM goal_history WHERE date = ?")
+                .bind(date)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            return Ok((build_progress(date, None, focus_seconds, completed_cycles), false));
+        };
+
+        let progress = goal_progress(goal.goal_type, goal.target, focus_seconds, completed_cycles);
+        let reached = progress >= 1.0;
+        let reached_at = if reached {
+            Some(previous_reached_at.unwrap_or_else(Utc::now))
+        } else {
+            None
+        };
+
+        sqlx::query(
+            "INSERT INTO goal_history
+                 (date, goal_type, target, focus_seconds, completed_cycles, reached, reached_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(date) DO UPDATE SET
+                 goal_type = excluded.goal_type,
+                 target = excluded.target,
+                 focus_seconds = excluded.focus_seconds,
+                 completed_cycles = excluded.completed_cycles,
+                 reached = excluded.reached,
+                 reached_at = excluded.reached_at,
+                 updated_at = excluded.updated_at",
+        )
+        .bind(date)
+        .bind(goal.goal_type.as_str())
+        .bind(goal.target)
+        .bind(focus_seconds)
+        .bind(completed_cycles)
+        .bind(reached)
+        .bind(reached_at.as_ref().map(format_timestamp))
+        .bind(now_timestamp())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        let snapshot = GoalSnapshot {
+            goal_type: goal.goal_type,
+            target: goal.target,
+            reached_at,
+        };
+        let progress = build_progress(date, Some(&snapshot), focus_seconds, completed_cycles);
+        Ok((progress, reached && !was_reached))
+    }
+
+    /// 统计日期范围（含首尾）内每天的专注数据与目标达成情况
+    ///
+    /// 有历史快照的日期使用当天记录的目标，其余日期使用当前按星期配置的目标；
+    /// 早于该目标创建日期的日子视为没有目标。
+    pub async fn range_stats(&self, start_date: NaiveDate, end_date: NaiveDate) -> Result<RangeStats, StorageError> {
+        if end_date < start_date {
+            return Err(StorageError::InvalidInput("结束日期不能早于开始日期".to_string()));
+        }
+        if (end_date - start_date).num_days() >= MAX_RANGE_DAYS {
+            return Err(StorageError::InvalidInput(format!("统计范围不能超过{}天", MAX_RANGE_DAYS)));
+        }
+
+        let (start, _) = local_day_bounds(start_date);
+        let (_, end) = local_day_bounds(end_date);
+
+        let rows = sqlx::query(
+            "SELECT start_time, duration_seconds + COALESCE(overtime_seconds, 0) AS duration_seconds FROM focus_sessions
+             WHERE session_type IN ('Focus', 'OpenFocus') AND completed = 1 AND start_time >= ? AND start_time < ?",
+        )
+        .bind(format_timestamp(&start))
+        .bind(format_timestamp(&end))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut totals: HashMap<NaiveDate, (i64, i64)> = HashMap::new();
+        for row in &rows {
+            let start_time: DateTime<Utc> = row.try_get("start_time")?;
+            let duration_seconds: i64 = row.try_get("duration_seconds")?;
+            let entry = totals.entry(start_time.with_timezone(&Local).date_naive()).or_default();
+            entry.0 += duration_seconds;
+            entry.1 += 1;
+        }
+
+        let history_rows = sqlx::query("SELECT date, goal_type, target, reached_at FROM goal_history WHERE date >= ? AND date <= ?")
+            .bind(start_date)
+            .bind(end_date)
+            .fetch_all(&self.pool)
+            .await?;
+        let mut history = HashMap::new();
+        for row in &history_rows {
+            let goal_type: String = row.try_get("goal_type")?;
+            history.insert(row.try_get::<NaiveDate, _>("date")?, GoalSnapshot {
+                goal_type: GoalType::parse(&goal_type)
+                    .ok_or_else(|| StorageError::InvalidInput(format!("未知的目标类型: {}", goal_type)))?,
+                target: row.try_get("target")?,
+                reached_at: row.try_get("reached_at")?,
+            });
+        }
+
+        let goals: HashMap<u32, DailyGoal> = self
+            .list_daily_goals()
+            .await?
+            .into_iter()
+            .map(|goal| (goal.weekday, goal))
+            .collect();
+
+        let mut days = Vec::new();
+        let mut date = start_date;
+        while date <= end_date {
+            let (focus_seconds, completed_cycles) = totals.get(&date).copied().unwrap_or_default();
+            let snapshot = history.remove(&date).or_else(|| {
+                goals.get(&date.weekday().num_days_from_monday())
+                    .filter(|goal| goal.created_at.with_timezone(&Local).date_naive() <= date)
+                    .map(|goal| GoalSnapshot {
+                        goal_type: goal.goal_type,
+                        target: goal.target,
+                        reached_at: None,
+                    })
+            });
+            days.push(build_progress(date, snapshot.as_ref(), focus_seconds, completed_cycles));
+            date += Duration::days(1);
+        }
+
+        let days_with_goal = days.iter().filter(|day| day.target.is_some()).count() as i64;
+        let days_reached = days.iter().filter(|day| day.reached).count() as i64;
+
+        Ok(RangeStats {
+            start_date,
+            end_date,
+            total_focus_seconds: days.iter().map(|day| day.focus_seconds).sum(),
+            total_completed_cycles: days.iter().map(|day| day.completed_cycles).sum(),
+            days_with_goal,
+            days_reached,
+            goal_attainment_rate: (days_with_goal > 0).then(|| days_reached as f64 / days_with_goal as f64),
+            days,
+        })
+    }
+
+    /// 行数据转换为每日目标
+    fn row_to_goal(row: &SqliteRow) -> Result<DailyGoal, StorageError> {
+        let goal_type: String = row.try_get("goal_type")?;
+
+        Ok(DailyGoal {
+            weekday: row.try_get("weekday")?,
+            goal_type: GoalType::parse(&goal_type)
+                .ok_or_else(|| StorageError::InvalidInput(format!("未知的目标类型: {}", goal_type)))?,
+            target: row.try_get("target")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+/// 本地日期对应的UTC时间范围 [当天0点, 次日0点)
+pub fn local_day_bounds(date: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    day_bounds_in(date, &Local)
+}
+
+/// 查找夏令时空档之后有效本地时刻的步长
+const DST_GAP_STEP: Duration = Duration::minutes(15);
+
+/// 最多向后查找的步数（一整天）
+const MAX_DST_GAP_STEPS: i32 = 24 * 4;
+
+/// 指定时区中日期对应的UTC时间范围 [当天0点, 次日0点)
+fn day_bounds_in<Tz: TimeZone>(date: NaiveDate, tz: &Tz) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start_of = |date: NaiveDate| {
+        let midnight = date.and_hms_opt(0, 0, 0).unwrap_or_default();
+        // 0点恰好处于夏令时跳变的空档时，当天从空档之后第一个有效的本地时刻开始
+        (0..=MAX_DST_GAP_STEPS)
+            .find_map(|step| tz.from_local_datetime(&(midnight + DST_GAP_STEP * step)).earliest())
+            .map(|time| time.with_timezone(&Utc))
+            .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+    };
+    (start_of(date), start_of(date + Duration::days(1)))
+}
+
+/// 目标完成比例
+fn goal_progress(goal_type: GoalType, target: i32, focus_seconds: i64, completed_cycles: i64) -> f64 {
+    let achieved = match goal_type {
+        GoalType::Cycles => completed_cycles as f64,
+        GoalType::FocusMinutes => focus_seconds as f64 / 60.0,
+    };
+    achieved / target.max(1) as f64
+}
+
+/// 组装某天的目标进度
+fn build_progress(
+    date: NaiveDate,
+    goal: Option<&GoalSnapshot>,
+    focus_seconds: i64,
+    completed_cycles: i64,
+) -> DailyGoalProgress {
+    let progress = goal.map(|goal| goal_progress(goal.goal_type, goal.target, focus_seconds, completed_cycles));
+    let reached = progress.is_some_and(|progress| progress >= 1.0);
+
+    DailyGoalProgress {
+        date,
+        goal_type: goal.map(|goal| goal.goal_type),
+        target: goal.map(|goal| goal.target),
+        focus_seconds,
+        completed_cycles,
+        progress,
+        reached,
+        reached_at: goal.and_then(|goal| goal.reached_at).filter(|_| reached),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{migrated_pool, DstZone, MidnightDstZone};
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    fn day_hours(date: NaiveDate) -> i64 {
+        let (start, end) = day_bounds_in(date, &DstZone);
+        (end - start).num_hours()
+    }
+
+    #[test]
+    fn day_bounds_follow_local_midnight() {
+        let (start, end) = day_bounds_in(date(1, 15), &DstZone);
+
+        assert_eq!(start.to_rfc3339(), "2024-01-14T23:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2024-01-15T23:00:00+00:00");
+        // 相邻两天首尾相接
+        assert_eq!(day_bounds_in(date(1, 16), &DstZone).0, end);
+    }
+
+    #[test]
+    fn dst_days_are_shorter_or_longer() {
+        assert_eq!(day_hours(date(3, 30)), 24);
+        assert_eq!(day_hours(date(3, 31)), 23);
+        assert_eq!(day_hours(date(10, 27)), 25);
+        assert_eq!(day_hours(date(10, 28)), 24);
+    }
+
+    #[test]
+    fn day_starting_in_dst_gap_begins_after_the_gap() {
+        let (start, end) = day_bounds_in(date(9, 8), &MidnightDstZone);
+
+        // 本地00:00不存在，当天从01:00（UTC-3）开始
+        assert_eq!(start.to_rfc3339(), "2024-09-08T04:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2024-09-09T03:00:00+00:00");
+        assert_eq!(day_bounds_in(date(9, 7), &MidnightDstZone).1, start);
+        assert_eq!((end - start).num_hours(), 23);
+    }
+
+    #[test]
+    fn goal_progress_by_goal_type() {
+        assert_eq!(goal_progress(GoalType::Cycles, 4, 0, 2), 0.5);
+        assert_eq!(goal_progress(GoalType::FocusMinutes, 300, 9000, 0), 0.5);
+        // 可以超额完成
+        assert_eq!(goal_progress(GoalType::Cycles, 2, 0, 3), 1.5);
+        // 目标为0时按1计算，避免除以0
+        assert_eq!(goal_progress(GoalType::Cycles, 0, 0, 0), 0.0);
+    }
+
+    #[test]
+    fn day_without_goal_is_never_reached() {
+        let progress = build_progress(date(5, 1), None, 7200, 3);
+
+        assert_eq!(progress.target, None);
+        assert_eq!(progress.progress, None);
+        assert!(!progress.reached);
+        assert_eq!(progress.focus_seconds, 7200);
+    }
+
+    #[test]
+    fn reached_at_is_kept_only_while_reached() {
+        let snapshot = GoalSnapshot {
+            goal_type: GoalType::Cycles,
+            target: 2,
+            reached_at: Some(Utc::now()),
+        };
+
+        let reached = build_progress(date(5, 1), Some(&snapshot), 0, 2);
+        assert!(reached.reached);
+        assert_eq!(reached.reached_at, snapshot.reached_at);
+
+        // 目标提高后当天不再达成
+        let not_reached = build_progress(date(5, 1), Some(&GoalSnapshot { target: 3, ..snapshot }), 0, 2);
+        assert!(!not_reached.reached);
+        assert_eq!(not_reached.reached_at, None);
+    }
+
+    #[test]
+    fn empty_day_has_zero_progress() {
+        let snapshot = GoalSnapshot {
+            goal_type: GoalType::FocusMinutes,
+            target: 240,
+            reached_at: None,
+        };
+        let progress = build_progress(date(5, 1), Some(&snapshot), 0, 0);
+
+        assert_eq!(progress.progress, Some(0.0));
+        assert!(!progress.reached);
+    }
+
+    #[tokio::test]
+    async fn days_before_goal_was_created_have_no_goal() {
+        let (_dir, pool) = migrated_pool().await;
+        let store = GoalStore::new(pool.clone());
+        for weekday in 0..7 {
+            store.set_daily_goal(weekday, GoalType::Cycles, 1).await.unwrap();
+        }
+        // 目标在三天前创建
+        sqlx::query("UPDATE daily_goals SET created_at = ?")
+            .bind(format_timestamp(&(Utc::now() - Duration::days(3))))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let today = Local::now().date_naive();
+        let stats = store.range_stats(today - Duration::days(6), today).await.unwrap();
+
+        let with_goal: Vec<NaiveDate> = stats.days.iter()
+            .filter(|day| day.target.is_some())
+            .map(|day| day.date)
+            .collect();
+        let since_created: Vec<NaiveDate> = (0..4).rev().map(|days| today - Duration::days(days)).collect();
+        assert_eq!(with_goal, since_created);
+        assert_eq!(stats.days_with_goal, 4);
+        assert_eq!(stats.goal_attainment_rate, Some(0.0));
+    }
+}
@@ -0,0 +1,406 @@
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::{Row, Sqlite, Transaction};
+use uuid::Uuid;
+
+use crate::database::{format_timestamp, now_timestamp};
+use crate::models::{LabelFocusStats, Project, SessionLabels, Tag};
+use super::StorageError;
+
+/// 项目层级的最大深度（防止异常数据导致无限循环）
+const MAX_PROJECT_DEPTH: usize = 16;
+
+/// 标签与项目存储（tags、session_tags、projects表）
+#[derive(Clone)]
+pub struct LabelStore {
+    pool: SqlitePool,
+}
+
+impl LabelStore {
+    /// 创建新的标签存储
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// 创建项目（可指定父项目）
+    pub async fn create_project(&self, name: &str, parent_id: Option<&str>) -> Result<Project, StorageError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(StorageError::InvalidInput("项目名称不能为空".to_string()));
+        }
+        if let Some(parent_id) = parent_id {
+            self.get_project(parent_id).await?;
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let now = now_timestamp();
+
+        sqlx::query(
+            "INSERT INTO projects (id, name, parent_id, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(name)
+        .bind(parent_id)
+        .bind(&now)
+        .bind(&now)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_project(&id).await
+    }
+
+    /// 获取单个项目
+    pub async fn get_project(&self, project_id: &str) -> Result<Project, StorageError> {
+        let row = sqlx::query(
+            "SELECT id, name, parent_id, created_at, updated_at FROM projects WHERE id = ?",
+        )
+        .bind(project_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| StorageError::NotFound(format!("项目 {}", project_id)))?;
+
+        Self::row_to_project(&row)
+    }
+
+    /// 列出全部项目
+    pub async fn list_projects(&self) -> Result<Vec<Project>, StorageError> {
+        let rows = sqlx::query(
+            "SELECT id, name, parent_id, created_at, updated_at FROM projects ORDER BY name",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::row_to_project).collect()
+    }
+
+    /// 列出全部标签
+    pub async fn list_tags(&self) -> Result<Vec<Tag>, StorageError> {
+        let rows = sqlx::query("SELECT id, name, created_at FROM tags ORDER BY name")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(Self::row_to_tag).collect()
+    }
+
+    /// 设置会话的项目和标签（覆盖原有标签）
+    pub async fn set_session_labels(
+        &self,
+        session_id: &str,
+        project_id: Option<&str>,
+        tags: &[String],
+    ) -> Result<SessionLabels, StorageError> {
+        if let Some(project_id) = project_id {
+            self.get_project(project_id).await?;
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query("UPDATE focus_sessions SET project_id = ?, updated_at = ? WHERE id = ?")
+            .bind(project_id)
+            .bind(now_timestamp())
+            .bind(session_id)
+            .execute(&mut *tx)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound(format!("会话 {}", session_id)));
+        }
+
+        sqlx::query("DELETE FROM session_tags WHERE session_id = ?")
+            .bind(session_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for name in Self::normalize_tag_names(tags) {
+            let tag_id = Self::upsert_tag(&mut tx, &name).await?;
+            sqlx::query("INSERT INTO session_tags (session_id, tag_id, created_at) VALUES (?, ?, ?)")
+                .bind(session_id)
+                .bind(&tag_id)
+                .bind(now_timestamp())
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+
+        self.get_session_labels(session_id).await
+    }
+
+    /// 获取会话的项目和标签
+    pub async fn get_session_labels(&self, session_id: &str) -> Result<SessionLabels, StorageError> {
+        let project_id: Option<String> = sqlx::query_scalar("SELECT project_id FROM focus_sessions WHERE id = ?")
+            .bind(session_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| StorageError::NotFound(format!("会话 {}", session_id)))?;
+
+        let rows = sqlx::query(
+            "SELECT t.id, t.name, t.created_at FROM tags t
+             JOIN session_tags st ON st.tag_id = t.id
+             WHERE st.session_id = ?
+             ORDER BY t.name",
+        )
+        .bind(session_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(SessionLabels {
+            session_id: session_id.to_string(),
+            project_id,
+            tags: rows.iter().map(Self::row_to_tag).collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// 按标签汇总时间范围内的专注统计
+    pub async fn tag_stats(&self, start: &DateTime<Utc>, end: &DateTime<Utc>) -> Result<Vec<LabelFocusStats>, StorageError> {
+        let rows = sqlx::query(
+            "SELECT t.id, t.name,
+                    COALESCE(SUM(fs.duration_seconds + COALESCE(fs.overtime_seconds, 0)), 0) AS focus_seconds,
+                    COUNT(fs.id) AS completed_cycles
+             FROM tags t
+             JOIN session_tags st ON st.tag_id = t.id
+             JOIN focus_sessions fs ON fs.id = st.session_id
+             WHERE fs.session_type IN ('Focus', 'OpenFocus') AND fs.completed = 1
+               AND fs.start_time >= ? AND fs.start_time < ?
+             GROUP BY t.id
+             ORDER BY focus_seconds DESC",
+        )
+        .bind(format_timestamp(start))
+        .bind(format_timestamp(end))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                let name: String = row.try_get("name")?;
+                Ok(LabelFocusStats {
+                    id: row.try_get("id")?,
+                    path: name.clone(),
+                    name,
+                    focus_seconds: row.try_get("focus_seconds")?,
+                    completed_cycles: row.try_get("completed_cycles")?,
+                })
+            })
+            .collect()
+    }
+
+    /// 按项目汇总时间范围内的专注统计（父项目包含子项目的数据）
+    pub async fn project_stats(&self, start: &DateTime<Utc>, end: &DateTime<Utc>) -> Result<Vec<LabelFocusStats>, StorageError> {
+        let rows = sqlx::query(
+            "SELECT project_id,
+                    COALESCE(SUM(duration_seconds + COALESCE(overtime_seconds, 0)), 0) AS focus_seconds,
+                    COUNT(id) AS completed_cycles
+             FROM focus_sessions
+             WHERE session_type IN ('Focus', 'OpenFocus') AND completed = 1 AND project_id IS NOT NULL
+               AND start_time >= ? AND start_time < ?
+             GROUP BY project_id",
+        )
+        .bind(format_timestamp(start))
+        .bind(format_timestamp(end))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let projects: HashMap<String, Project> = self.list_projects().await?
+            .into_iter()
+            .map(|p| (p.id.clone(), p))
+            .collect();
+
+        // 将每个项目的数据累加到自身及全部祖先项目
+        let mut totals: HashMap<String, (i64, i64)> = HashMap::new();
+        for row in &rows {
+            let project_id: String = row.try_get("project_id")?;
+            let focus_seconds: i64 = row.try_get("focus_seconds")?;
+            let completed_cycles: i64 = row.try_get("completed_cycles")?;
+
+            for ancestor_id in Self::ancestor_chain(&projects, &project_id) {
+                let entry = totals.entry(ancestor_id).or_insert((0, 0));
+                entry.0 += focus_seconds;
+                entry.1 += completed_cycles;
+            }
+        }
+
+        let mut stats: Vec<LabelFocusStats> = totals
+            .into_iter()
+            .filter_map(|(id, (focus_seconds, completed_cycles))| {
+                let project = projects.get(&id)?;
+                Some(LabelFocusStats {
+                    path: Self::project_path(&projects, &id),
+                    id,
+                    name: project.name.clone(),
+                    focus_seconds,
+                    completed_cycles,
+                })
+            })
+            .collect();
+        stats.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Ok(stats)
+    }
+
+    /// 获取项目自身及全部祖先ID
+    fn ancestor_chain(projects: &HashMap<String, Project>, project_id: &str) -> Vec<String> {
+        let mut chain = Vec::new();
+        let mut current = Some(project_id.to_string());
+
+        while let Some(id) = current {
+            if chain.len() >= MAX_PROJECT_DEPTH || chain.contains(&id) {
+                break;
+            }
+            current = projects.get(&id).and_then(|p| p.parent_id.clone());
+            chain.push(id);
+        }
+
+        chain
+    }
+
+    /// 构建项目完整路径
+    fn project_path(projects: &HashMap<String, Project>, project_id: &str) -> String {
+        let mut names: Vec<&str> = Self::ancestor_chain(projects, project_id)
+            .iter()
+            .filter_map(|id| projects.get(id).map(|p| p.name.as_str()))
+            .collect();
+        names.reverse();
+        names.join("/")
+    }
+
+    /// 规范化标签名：去除空白、跳过空值、忽略大小写去重
+    fn normalize_tag_names(tags: &[String]) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for tag in tags {
+            let name = tag.trim();
+            if !name.is_empty() && !names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                names.push(name.to_string());
+            }
+        }
+        names
+    }
+
+    /// 获取或创建标签，返回标签ID
+    async fn upsert_tag(tx: &mut Transaction<'_, Sqlite>, name: &str) -> Result<String, StorageError> {
+        sqlx::query("INSERT OR IGNORE INTO tags (id, name, created_at) VALUES (?, ?, ?)")
+            .bind(Uuid::new_v4().to_string())
+            .bind(name)
+            .bind(now_timestamp())
+            .execute(&mut **tx)
+            .await?;
+
+        let tag_id = sqlx::query_scalar("SELECT id FROM tags WHERE name = ?")
+            .bind(name)
+            .fetch_one(&mut **tx)
+            .await?;
+
+        Ok(tag_id)
+    }
+
+    /// 行数据转换为项目
+    fn row_to_project(row: &SqliteRow) -> Result<Project, StorageError> {
+        Ok(Project {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            parent_id: row.try_get("parent_id")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+
+    /// 行数据转换为标签
+    fn row_to_tag(row: &SqliteRow) -> Result<Tag, StorageError> {
+        Ok(Tag {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::models::SessionType;
+    use crate::services::storage::SessionStore;
+    use crate::test_support::migrated_pool;
+
+    /// 写入一个已完成的专注会话并设置项目和标签
+    async fn completed_focus(
+        sessions: &SessionStore,
+        labels: &LabelStore,
+        id: &str,
+        project_id: Option<&str>,
+        tags: &[&str],
+    ) {
+        sessions.insert_session(id, &SessionType::Focus, 1500, None).await.unwrap();
+        sessions.complete_session(id).await.unwrap();
+        let tags: Vec<String> = tags.iter().map(|tag| tag.to_string()).collect();
+        labels.set_session_labels(id, project_id, &tags).await.unwrap();
+    }
+
+    fn today() -> (DateTime<Utc>, DateTime<Utc>) {
+        let now = Utc::now();
+        (now - Duration::hours(1), now + Duration::hours(1))
+    }
+
+    #[tokio::test]
+    async fn project_stats_roll_up_children_with_overtime() {
+        let (_dir, pool) = migrated_pool().await;
+        let labels = LabelStore::new(pool.clone());
+        let sessions = SessionStore::new(pool);
+        let work = labels.create_project("工作", None).await.unwrap();
+        let report = labels.create_project("报告", Some(&work.id)).await.unwrap();
+        let home = labels.create_project("家务", None).await.unwrap();
+
+        completed_focus(&sessions, &labels, "s1", Some(&work.id), &[]).await;
+        completed_focus(&sessions, &labels, "s2", Some(&report.id), &[]).await;
+        completed_focus(&sessions, &labels, "s3", Some(&home.id), &[]).await;
+        sessions.save_overtime("s2", 600).await.unwrap();
+        // 未完成的会话不计入
+        sessions.insert_session("s4", &SessionType::Focus, 1500, None).await.unwrap();
+        labels.set_session_labels("s4", Some(&report.id), &[]).await.unwrap();
+
+        let (start, end) = today();
+        let stats = labels.project_stats(&start, &end).await.unwrap();
+        let summary: Vec<(&str, i64, i64)> = stats.iter()
+            .map(|s| (s.path.as_str(), s.focus_seconds, s.completed_cycles))
+            .collect();
+
+        assert_eq!(summary, [
+            ("家务", 1500, 1),
+            ("工作", 1500 + 1500 + 600, 2),
+            ("工作/报告", 1500 + 600, 1),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn session_tags_are_normalized_and_counted() {
+        let (_dir, pool) = migrated_pool().await;
+        let labels = LabelStore::new(pool.clone());
+        let sessions = SessionStore::new(pool);
+
+        completed_focus(&sessions, &labels, "s1", None, &["Rust", " rust ", "", "写作"]).await;
+        completed_focus(&sessions, &labels, "s2", None, &["RUST"]).await;
+        sessions.save_overtime("s2", 300).await.unwrap();
+
+        assert_eq!(labels.get_session_labels("s1").await.unwrap().tags.len(), 2);
+        assert_eq!(labels.list_tags().await.unwrap().len(), 2);
+
+        let (start, end) = today();
+        let stats = labels.tag_stats(&start, &end).await.unwrap();
+        assert_eq!(stats[0].name, "Rust");
+        assert_eq!((stats[0].focus_seconds, stats[0].completed_cycles), (1500 + 1500 + 300, 2));
+        assert_eq!((stats[1].focus_seconds, stats[1].completed_cycles), (1500, 1));
+    }
+
+    #[tokio::test]
+    async fn labels_require_existing_project_and_session() {
+        let (_dir, pool) = migrated_pool().await;
+        let labels = LabelStore::new(pool);
+
+        assert!(matches!(labels.create_project(" ", None).await, Err(StorageError::InvalidInput(_))));
+        assert!(matches!(labels.create_project("报告", Some("missing")).await, Err(StorageError::NotFound(_))));
+        assert!(matches!(
+            labels.set_session_labels("missing", None, &["Rust".to_string()]).await,
+            Err(StorageError::NotFound(_))
+        ));
+    }
+}
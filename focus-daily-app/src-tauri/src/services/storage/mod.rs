@@ -0,0 +1,41 @@
+pub mod session_store;
+pub mod task_store;
+pub mod label_store;
+pub mod rating_store;
+pub mod goal_store;
+pub mod streak_store;
+pub mod report_store;
+pub mod sequence_store;
+pub mod audio_config_store;
+pub mod sound_store;
+pub mod ambient_store;
+pub mod quiet_hours_store;
+pub mod event_log_store;
+pub mod settings_store;
+
+// 重新导出主要接口
+pub use session_store::{FocusMetrics, FocusSpan, SessionStore};
+pub use task_store::TaskStore;
+pub use label_store::LabelStore;
+pub use rating_store::{RatedSessionSample, RatingStore};
+pub use goal_store::GoalStore;
+pub use streak_store::StreakStore;
+pub use report_store::ReportStore;
+pub use sequence_store::{classic_sequence, SequenceStore, CLASSIC_SEQUENCE_ID};
+pub use audio_config_store::AudioConfigStore;
+pub use sound_store::{NewCustomSound, SoundStore};
+pub use ambient_store::{AmbientStore, DEFAULT_PROFILE_ID};
+pub use quiet_hours_store::QuietHoursStore;
+pub use event_log_store::{EventLogRetention, EventLogStore};
+pub use settings_store::SettingsStore;
+
+/// 存储层错误类型
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("数据库错误: {0}")]
+    Database(#[from] sqlx::Error),
+    #[error("记录不存在: {0}")]
+    NotFound(String),
+    #[error("无效输入: {0}")]
+    InvalidInput(String),
+}
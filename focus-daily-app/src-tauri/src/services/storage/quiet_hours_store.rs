@@ -0,0 +1,119 @@
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+
+use crate::database::now_timestamp;
+use crate::models::{parse_clock_minutes, QuietHourRange, QuietHours, QuietHoursMode, UpdateQuietHours};
+use super::StorageError;
+
+/// 免打扰设置存储（quiet_hours_settings与quiet_hour_ranges表）
+#[derive(Clone)]
+pub struct QuietHoursStore {
+    pool: SqlitePool,
+}
+
+impl QuietHoursStore {
+    /// 创建新的免打扰设置存储
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// 获取免打扰设置（未保存过时返回默认值）
+    pub async fn get_quiet_hours(&self) -> Result<QuietHours, StorageError> {
+        let row = sqlx::query("SELECT enabled, mode, updated_at FROM quiet_hours_settings WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await?;
+        let Some(row) = row else {
+            return Ok(QuietHours::default());
+        };
+
+        let mode: String = row.try_get("mode")?;
+        let ranges = sqlx::query(
+            "SELECT weekday, start_time, end_time FROM quiet_hour_ranges ORDER BY weekday, start_time",
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(|row| {
+            Ok(QuietHourRange {
+                weekday: row.try_get::<i64, _>("weekday")? as u32,
+                start_time: row.try_get("start_time")?,
+                end_time: row.try_get("end_time")?,
+            })
+        })
+        .collect::<Result<Vec<_>, StorageError>>()?;
+
+        Ok(QuietHours {
+            enabled: row.try_get("enabled")?,
+            mode: QuietHoursMode::parse(&mode)
+                .ok_or_else(|| StorageError::InvalidInput(format!("未知的免打扰方式: {}", mode)))?,
+            ranges,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+
+    /// 更新免打扰设置；提供ranges时整体替换全部时间段
+    pub async fn update_quiet_hours(&self, input: UpdateQuietHours) -> Result<QuietHours, StorageError> {
+        let mut quiet_hours = self.get_quiet_hours().await?;
+
+        if let Some(enabled) = input.enabled {
+            quiet_hours.enabled = enabled;
+        }
+        if let Some(mode) = input.mode {
+            quiet_hours.mode = mode;
+        }
+        if let Some(ranges) = input.ranges {
+            quiet_hours.ranges = ranges.into_iter().map(Self::normalize_range).collect::<Result<_, _>>()?;
+        }
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO quiet_hours_settings (id, enabled, mode, updated_at)
+             VALUES (1, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET
+                enabled = excluded.enabled,
+                mode = excluded.mode,
+                updated_at = excluded.updated_at",
+        )
+        .bind(quiet_hours.enabled)
+        .bind(quiet_hours.mode.as_str())
+        .bind(now_timestamp())
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM quiet_hour_ranges").execute(&mut *tx).await?;
+        for range in &quiet_hours.ranges {
+            sqlx::query("INSERT INTO quiet_hour_ranges (weekday, start_time, end_time) VALUES (?, ?, ?)")
+                .bind(range.weekday)
+                .bind(&range.start_time)
+                .bind(&range.end_time)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+
+        self.get_quiet_hours().await
+    }
+
+    /// 校验时间段并把时间统一为HH:MM
+    fn normalize_range(range: QuietHourRange) -> Result<QuietHourRange, StorageError> {
+        if range.weekday > 6 {
+            return Err(StorageError::InvalidInput("星期必须在0（周一）到6（周日）之间".to_string()));
+        }
+        let parse = |value: &str| {
+            parse_clock_minutes(value)
+                .ok_or_else(|| StorageError::InvalidInput(format!("无效的时间: {}（应为HH:MM）", value)))
+        };
+        let start = parse(&range.start_time)?;
+        let end = parse(&range.end_time)?;
+        if start == end {
+            return Err(StorageError::InvalidInput("免打扰时段的开始和结束时间不能相同".to_string()));
+        }
+
+        let format = |minutes: u32| format!("{:02}:{:02}", minutes / 60, minutes % 60);
+        Ok(QuietHourRange {
+            weekday: range.weekday,
+            start_time: format(start),
+            end_time: format(end),
+        })
+    }
+}
@@ -0,0 +1,131 @@
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+
+use crate::database::{format_timestamp, now_timestamp};
+use crate::models::SessionRating;
+use super::StorageError;
+
+/// 评分会话的行为样本（用于相关性分析）
+#[derive(Debug, Clone)]
+pub struct RatedSessionSample {
+    pub score: i32,
+    pub distractions: i32,
+    pub pause_count: i32,
+    pub micro_breaks_triggered: i32,
+    pub micro_breaks_completed: i32,
+}
+
+/// 会话评价存储（session_ratings表）
+#[derive(Clone)]
+pub struct RatingStore {
+    pool: SqlitePool,
+}
+
+impl RatingStore {
+    /// 创建新的评价存储
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// 保存专注会话的自我评价（重复评价会覆盖）
+    pub async fn rate_session(
+        &self,
+        session_id: &str,
+        score: i32,
+        distractions: i32,
+        note: Option<String>,
+    ) -> Result<SessionRating, StorageError> {
+        if !(1..=5).contains(&score) {
+            return Err(StorageError::InvalidInput("评分必须在1-5之间".to_string()));
+        }
+        if distractions < 0 {
+            return Err(StorageError::InvalidInput("分心次数不能为负数".to_string()));
+        }
+
+        let session_type: String = sqlx::query_scalar("SELECT session_type FROM focus_sessions WHERE id = ?")
+            .bind(session_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| StorageError::NotFound(format!("会话 {}", session_id)))?;
+        if !matches!(session_type.as_str(), "Focus" | "OpenFocus") {
+            return Err(StorageError::InvalidInput("只能评价专注会话".to_string()));
+        }
+
+        let note = note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        let now = now_timestamp();
+
+        sqlx::query(
+            "INSERT INTO session_ratings (session_id, score, distractions, note, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(session_id) DO UPDATE SET
+                 score = excluded.score,
+                 distractions = excluded.distractions,
+                 note = excluded.note,
+                 updated_at = excluded.updated_at",
+        )
+        .bind(session_id)
+        .bind(score)
+        .bind(distractions)
+        .bind(&note)
+        .bind(&now)
+        .bind(&now)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_rating(session_id).await
+    }
+
+    /// 获取会话评价
+    pub async fn get_rating(&self, session_id: &str) -> Result<SessionRating, StorageError> {
+        let row = sqlx::query(
+            "SELECT session_id, score, distractions, note, created_at, updated_at
+             FROM session_ratings WHERE session_id = ?",
+        )
+        .bind(session_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| StorageError::NotFound(format!("会话评价 {}", session_id)))?;
+
+        Self::row_to_rating(&row)
+    }
+
+    /// 获取时间范围内已评价会话的行为样本
+    pub async fn rated_samples(&self, start: &DateTime<Utc>, end: &DateTime<Utc>) -> Result<Vec<RatedSessionSample>, StorageError> {
+        let rows = sqlx::query(
+            "SELECT r.score, r.distractions, fs.pause_count,
+                    fs.micro_breaks_triggered, fs.micro_breaks_completed
+             FROM session_ratings r
+             JOIN focus_sessions fs ON fs.id = r.session_id
+             WHERE fs.start_time >= ? AND fs.start_time < ?",
+        )
+        .bind(format_timestamp(start))
+        .bind(format_timestamp(end))
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(RatedSessionSample {
+                    score: row.try_get("score")?,
+                    distractions: row.try_get("distractions")?,
+                    pause_count: row.try_get("pause_count")?,
+                    micro_breaks_triggered: row.try_get("micro_breaks_triggered")?,
+                    micro_breaks_completed: row.try_get("micro_breaks_completed")?,
+                })
+            })
+            .collect()
+    }
+
+    /// 行数据转换为会话评价
+    fn row_to_rating(row: &SqliteRow) -> Result<SessionRating, StorageError> {
+        Ok(SessionRating {
+            session_id: row.try_get("session_id")?,
+            score: row.try_get("score")?,
+            distractions: row.try_get("distractions")?,
+            note: row.try_get("note")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
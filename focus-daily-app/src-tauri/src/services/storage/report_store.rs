@@ -0,0 +1,127 @@
+use chrono::{Datelike, Duration, Local, NaiveDate, Utc};
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+
+use crate::database::format_timestamp;
+use crate::models::{InterruptionReasonCount, TaskFocusSummary, WeeklyReport};
+use crate::services::analytics::best_and_worst_days;
+use super::goal_store::local_day_bounds;
+use super::{GoalStore, StorageError};
+
+/// 报告中列出的中断原因数量
+const TOP_REASONS: i64 = 5;
+/// 报告中列出的任务数量
+const TOP_TASKS: i64 = 5;
+
+/// 每周回顾报告的数据查询
+#[derive(Clone)]
+pub struct ReportStore {
+    pool: SqlitePool,
+    goals: GoalStore,
+}
+
+impl ReportStore {
+    /// 创建新的报告存储
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            goals: GoalStore::new(pool.clone()),
+            pool,
+        }
+    }
+
+    /// 生成包含指定日期的那一周（周一至周日）的回顾报告
+    pub async fn weekly_report(&self, date: NaiveDate) -> Result<WeeklyReport, StorageError> {
+        let week_start = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+        let week_end = week_start + Duration::days(6);
+        let today = Local::now().date_naive();
+
+        let range = self.goals.range_stats(week_start, week_end).await?;
+        let (start, _) = local_day_bounds(week_start);
+        let (_, end) = local_day_bounds(week_end);
+        let start = format_timestamp(&start);
+        let end = format_timestamp(&end);
+
+        let (best_day, worst_day) = best_and_worst_days(&range.days, today);
+
+        let metrics = sqlx::query(
+            "SELECT
+                 AVG(CASE WHEN micro_breaks_triggered > 0
+                     THEN CAST(micro_breaks_completed AS REAL) / micro_breaks_triggered END) AS micro_break_completion,
+                 COALESCE(SUM(pause_count), 0) AS interruptions
+             FROM focus_sessions
+             WHERE session_type IN ('Focus', 'OpenFocus') AND start_time >= ? AND start_time < ?",
+        )
+        .bind(&start)
+        .bind(&end)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let reason_rows = sqlx::query(
+            "SELECT i.reason, COUNT(*) AS count
+             FROM session_interruptions i
+             JOIN focus_sessions fs ON fs.id = i.session_id
+             WHERE i.reason IS NOT NULL AND fs.start_time >= ? AND fs.start_time < ?
+             GROUP BY i.reason
+             ORDER BY count DESC, i.reason
+             LIMIT ?",
+        )
+        .bind(&start)
+        .bind(&end)
+        .bind(TOP_REASONS)
+        .fetch_all(&self.pool)
+        .await?;
+        let interruption_reasons = reason_rows.iter()
+            .map(|row| {
+                Ok(InterruptionReasonCount {
+                    reason: row.try_get("reason")?,
+                    count: row.try_get("count")?,
+                })
+            })
+            .collect::<Result<Vec<_>, StorageError>>()?;
+
+        let task_rows = sqlx::query(
+            "SELECT t.id, t.title,
+                    COALESCE(SUM(fs.duration_seconds + COALESCE(fs.overtime_seconds, 0)), 0) AS focus_seconds,
+                    COUNT(*) AS completed_cycles
+             FROM focus_sessions fs
+             JOIN tasks t ON t.id = fs.task_id
+             WHERE fs.session_type IN ('Focus', 'OpenFocus') AND fs.completed = 1
+               AND fs.start_time >= ? AND fs.start_time < ?
+             GROUP BY t.id
+             ORDER BY focus_seconds DESC, t.title
+             LIMIT ?",
+        )
+        .bind(&start)
+        .bind(&end)
+        .bind(TOP_TASKS)
+        .fetch_all(&self.pool)
+        .await?;
+        let top_tasks = task_rows.iter()
+            .map(|row| {
+                Ok(TaskFocusSummary {
+                    task_id: row.try_get("id")?,
+                    title: row.try_get("title")?,
+                    focus_seconds: row.try_get("focus_seconds")?,
+                    completed_cycles: row.try_get("completed_cycles")?,
+                })
+            })
+            .collect::<Result<Vec<_>, StorageError>>()?;
+
+        Ok(WeeklyReport {
+            week_start,
+            week_end,
+            total_focus_seconds: range.total_focus_seconds,
+            total_completed_cycles: range.total_completed_cycles,
+            days_reached: range.days_reached,
+            goal_attainment_rate: range.goal_attainment_rate,
+            best_day,
+            worst_day,
+            average_micro_break_completion: metrics.try_get("micro_break_completion")?,
+            total_interruptions: metrics.try_get("interruptions")?,
+            interruption_reasons,
+            top_tasks,
+            days: range.days,
+            generated_at: Utc::now(),
+        })
+    }
+}
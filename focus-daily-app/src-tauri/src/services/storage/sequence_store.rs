@@ -0,0 +1,187 @@
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::database::now_timestamp;
+use crate::models::{AudioType, PhaseSequence, SequenceStep, StepKind, UserSettings};
+use super::StorageError;
+
+/// 内置90/20序列的ID
+pub const CLASSIC_SEQUENCE_ID: &str = "classic-90-20";
+
+/// 序列允许的最大步骤数
+const MAX_STEPS: usize = 32;
+/// 单个步骤的最大时长（分钟）
+const MAX_STEP_MINUTES: u32 = 600;
+
+/// 内置的90/20序列（按当前设置的专注与长休息时长）
+pub fn classic_sequence(settings: &UserSettings) -> PhaseSequence {
+    PhaseSequence {
+        id: CLASSIC_SEQUENCE_ID.to_string(),
+        name: "90/20".to_string(),
+        steps: vec![
+            SequenceStep {
+                name: "专注".to_string(),
+                kind: StepKind::Focus,
+                duration_minutes: settings.focus_duration_minutes as u32,
+                micro_breaks: true,
+                audio_cue: Some(AudioType::FocusStart),
+            },
+            SequenceStep {
+                name: "长休息".to_string(),
+                kind: StepKind::LongBreak,
+                duration_minutes: settings.long_break_duration_minutes as u32,
+                micro_breaks: false,
+                audio_cue: Some(AudioType::LongBreakStart),
+            },
+        ],
+        built_in: true,
+        created_at: None,
+        updated_at: None,
+    }
+}
+
+/// 用户定义的阶段序列存储（phase_sequences表）
+#[derive(Clone)]
+pub struct SequenceStore {
+    pool: SqlitePool,
+}
+
+impl SequenceStore {
+    /// 创建新的序列存储
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// 创建阶段序列
+    pub async fn create_sequence(&self, name: &str, steps: Vec<SequenceStep>) -> Result<PhaseSequence, StorageError> {
+        let (name, steps) = Self::validate(name, steps)?;
+        let id = Uuid::new_v4().to_string();
+        let now = now_timestamp();
+
+        sqlx::query(
+            "INSERT INTO phase_sequences (id, name, steps, created_at, updated_at) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(&name)
+        .bind(Self::encode_steps(&steps)?)
+        .bind(&now)
+        .bind(&now)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_sequence(&id).await
+    }
+
+    /// 更新阶段序列的名称和步骤
+    pub async fn update_sequence(&self, sequence_id: &str, name: &str, steps: Vec<SequenceStep>) -> Result<PhaseSequence, StorageError> {
+        Self::ensure_editable(sequence_id)?;
+        let (name, steps) = Self::validate(name, steps)?;
+
+        let result = sqlx::query("UPDATE phase_sequences SET name = ?, steps = ?, updated_at = ? WHERE id = ?")
+            .bind(&name)
+            .bind(Self::encode_steps(&steps)?)
+            .bind(now_timestamp())
+            .bind(sequence_id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound(format!("阶段序列 {}", sequence_id)));
+        }
+
+        self.get_sequence(sequence_id).await
+    }
+
+    /// 删除阶段序列
+    pub async fn delete_sequence(&self, sequence_id: &str) -> Result<(), StorageError> {
+        Self::ensure_editable(sequence_id)?;
+
+        let result = sqlx::query("DELETE FROM phase_sequences WHERE id = ?")
+            .bind(sequence_id)
+            .execute(&self.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound(format!("阶段序列 {}", sequence_id)));
+        }
+        Ok(())
+    }
+
+    /// 获取单个阶段序列
+    pub async fn get_sequence(&self, sequence_id: &str) -> Result<PhaseSequence, StorageError> {
+        let row = sqlx::query("SELECT id, name, steps, created_at, updated_at FROM phase_sequences WHERE id = ?")
+            .bind(sequence_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| StorageError::NotFound(format!("阶段序列 {}", sequence_id)))?;
+
+        Self::row_to_sequence(&row)
+    }
+
+    /// 列出用户定义的阶段序列（不含内置序列）
+    pub async fn list_sequences(&self) -> Result<Vec<PhaseSequence>, StorageError> {
+        let rows = sqlx::query("SELECT id, name, steps, created_at, updated_at FROM phase_sequences ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(Self::row_to_sequence).collect()
+    }
+
+    /// 内置序列不能修改或删除
+    fn ensure_editable(sequence_id: &str) -> Result<(), StorageError> {
+        if sequence_id == CLASSIC_SEQUENCE_ID {
+            return Err(StorageError::InvalidInput("内置序列不能修改或删除".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 校验并规范化序列名称和步骤
+    fn validate(name: &str, steps: Vec<SequenceStep>) -> Result<(String, Vec<SequenceStep>), StorageError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(StorageError::InvalidInput("序列名称不能为空".to_string()));
+        }
+        if steps.is_empty() || steps.len() > MAX_STEPS {
+            return Err(StorageError::InvalidInput(format!("序列步骤数必须在1到{}之间", MAX_STEPS)));
+        }
+
+        let steps = steps
+            .into_iter()
+            .map(|mut step| {
+                step.name = step.name.trim().to_string();
+                if step.name.is_empty() {
+                    return Err(StorageError::InvalidInput("步骤名称不能为空".to_string()));
+                }
+                if step.duration_minutes == 0 || step.duration_minutes > MAX_STEP_MINUTES {
+                    return Err(StorageError::InvalidInput(format!(
+                        "步骤「{}」的时长必须在1到{}分钟之间",
+                        step.name, MAX_STEP_MINUTES
+                    )));
+                }
+                // 微休息只在专注步骤中安排
+                step.micro_breaks &= step.kind == StepKind::Focus;
+                Ok(step)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((name.to_string(), steps))
+    }
+
+    fn encode_steps(steps: &[SequenceStep]) -> Result<String, StorageError> {
+        serde_json::to_string(steps).map_err(|e| StorageError::InvalidInput(format!("序列步骤无法保存: {}", e)))
+    }
+
+    fn row_to_sequence(row: &SqliteRow) -> Result<PhaseSequence, StorageError> {
+        let steps: String = row.try_get("steps")?;
+        let steps = serde_json::from_str(&steps)
+            .map_err(|e| StorageError::InvalidInput(format!("序列步骤数据无效: {}", e)))?;
+
+        Ok(PhaseSequence {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            steps,
+            built_in: false,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
@@ -0,0 +1,396 @@
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+
+use crate::database::{format_timestamp, now_timestamp};
+use crate::models::{LongBreakAdherence, LongBreakStatus, SessionType};
+use super::StorageError;
+
+/// 专注会话的行为数据
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FocusMetrics {
+    /// 暂停次数
+    pub pause_count: u32,
+    /// 触发的微休息次数
+    pub micro_breaks_triggered: u32,
+    /// 完成的微休息次数
+    pub micro_breaks_completed: u32,
+    /// 跳过的微休息次数
+    pub micro_breaks_skipped: u32,
+}
+
+/// 专注会话的起止时间与质量评分（用于按时段统计）
+#[derive(Debug, Clone)]
+pub struct FocusSpan {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub quality_score: Option<f64>,
+}
+
+/// 统计连续未完成长休息时最多回看的专注会话数
+const MAX_SKIP_LOOKBACK: i64 = 100;
+
+/// 会话存储（focus_sessions表）
+#[derive(Clone)]
+pub struct SessionStore {
+    pool: SqlitePool,
+}
+
+impl SessionStore {
+    /// 创建新的会话存储
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// 插入新开始的会话
+    pub async fn insert_session(
+        &self,
+        session_id: &str,
+        session_type: &SessionType,
+        duration_seconds: u32,
+        task_id: Option<&str>,
+    ) -> Result<(), StorageError> {
+        let now = now_timestamp();
+
+        sqlx::query(
+            "INSERT INTO focus_sessions (id, start_time, duration_seconds, session_type, completed, task_id, created_at, updated_at)
+             VALUES (?, ?, ?, ?, 0, ?, ?, ?)",
+        )
+        .bind(session_id)
+        .bind(&now)
+        .bind(duration_seconds as i64)
+        .bind(session_type.as_str())
+        .bind(task_id)
+        .bind(&now)
+        .bind(&now)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 标记会话完成
+    pub async fn complete_session(&self, session_id: &str) -> Result<(), StorageError> {
+        let now = now_timestamp();
+
+        sqlx::query(
+            "UPDATE focus_sessions SET end_time = ?, completed = 1, updated_at = ? WHERE id = ?",
+        )
+        .bind(&now)
+        .bind(&now)
+        .bind(session_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 记录提前结束（未完成）的会话的结束时间
+    pub async fn end_session(&self, session_id: &str) -> Result<(), StorageError> {
+        let now = now_timestamp();
+
+        sqlx::query(
+            "UPDATE focus_sessions SET end_time = ?, updated_at = ? WHERE id = ? AND end_time IS NULL",
+        )
+        .bind(&now)
+        .bind(&now)
+        .bind(session_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 删除会话及其标签（用于撤销未能成功开始的会话）
+    pub async fn delete_session(&self, session_id: &str) -> Result<(), StorageError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM session_tags WHERE session_id = ?")
+            .bind(session_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM focus_sessions WHERE id = ?")
+            .bind(session_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 更新专注会话的行为数据
+    pub async fn update_focus_metrics(&self, session_id: &str, metrics: FocusMetrics) -> Result<(), StorageError> {
+        sqlx::query(
+            "UPDATE focus_sessions
+             SET pause_count = ?, micro_breaks_triggered = ?, micro_breaks_completed = ?,
+                 micro_breaks_skipped = ?, updated_at = ?
+             WHERE id = ?",
+        )
+        .bind(metrics.pause_count as i64)
+        .bind(metrics.micro_breaks_triggered as i64)
+        .bind(metrics.micro_breaks_completed as i64)
+        .bind(metrics.micro_breaks_skipped as i64)
+        .bind(now_timestamp())
+        .bind(session_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 保存专注质量评分
+    pub async fn save_focus_quality(
+        &self,
+        session_id: &str,
+        focus_quality_score: f32,
+        micro_break_effectiveness: f32,
+    ) -> Result<(), StorageError> {
+        sqlx::query(
+            "UPDATE focus_sessions
+             SET focus_quality_score = ?, micro_break_effectiveness = ?, updated_at = ?
+             WHERE id = ?",
+        )
+        .bind(focus_quality_score as f64)
+        .bind(micro_break_effectiveness as f64)
+        .bind(now_timestamp())
+        .bind(session_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 记录一次专注中断（暂停）及其原因
+    pub async fn record_interruption(
+        &self,
+        session_id: &str,
+        reason: Option<&str>,
+        elapsed_seconds: u64,
+    ) -> Result<(), StorageError> {
+        sqlx::query(
+            "INSERT INTO session_interruptions (session_id, reason, elapsed_seconds, created_at)
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(session_id)
+        .bind(reason)
+        .bind(elapsed_seconds as i64)
+        .bind(now_timestamp())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 与时间范围重叠的专注会话时段（裁剪到范围内，未结束的会话不计入）
+    pub async fn focus_spans(&self, start: &DateTime<Utc>, end: &DateTime<Utc>) -> Result<Vec<FocusSpan>, StorageError> {
+        let rows = sqlx::query(
+            "SELECT start_time, end_time, focus_quality_score FROM focus_sessions
+             WHERE session_type IN ('Focus', 'OpenFocus') AND end_time IS NOT NULL
+               AND start_time < ? AND end_time > ?",
+        )
+        .bind(format_timestamp(end))
+        .bind(format_timestamp(start))
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut spans = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let span_start: DateTime<Utc> = row.try_get("start_time")?;
+            let span_end: DateTime<Utc> = row.try_get("end_time")?;
+            let span = FocusSpan {
+                start: span_start.max(*start),
+                end: span_end.min(*end),
+                quality_score: row.try_get("focus_quality_score")?,
+            };
+            if span.start < span.end {
+                spans.push(span);
+            }
+        }
+        Ok(spans)
+    }
+
+    /// 更新会话的计划时长（会话进行中延长或缩短后）
+    pub async fn update_planned_duration(&self, session_id: &str, duration_seconds: u64) -> Result<(), StorageError> {
+        sqlx::query("UPDATE focus_sessions SET duration_seconds = ?, updated_at = ? WHERE id = ?")
+            .bind(duration_seconds as i64)
+            .bind(now_timestamp())
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 保存心流模式下超出计划时长的加时
+    pub async fn save_overtime(&self, session_id: &str, overtime_seconds: u64) -> Result<(), StorageError> {
+        sqlx::query("UPDATE focus_sessions SET overtime_seconds = ?, updated_at = ? WHERE id = ?")
+            .bind(overtime_seconds as i64)
+            .bind(now_timestamp())
+            .bind(session_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 记录专注会话之后开始的长休息及间隔
+    pub async fn start_long_break_for(
+        &self,
+        focus_session_id: &str,
+        long_break_session_id: &str,
+        gap_seconds: i64,
+    ) -> Result<(), StorageError> {
+        sqlx::query(
+            "UPDATE focus_sessions SET long_break_session_id = ?, long_break_gap_seconds = ?, updated_at = ?
+             WHERE id = ?",
+        )
+        .bind(long_break_session_id)
+        .bind(gap_seconds)
+        .bind(now_timestamp())
+        .bind(focus_session_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 记录专注会话之后长休息的最终执行情况（只记录一次）
+    pub async fn resolve_long_break(&self, focus_session_id: &str, status: LongBreakStatus) -> Result<(), StorageError> {
+        sqlx::query(
+            "UPDATE focus_sessions SET long_break_status = ?, updated_at = ?
+             WHERE id = ? AND long_break_status IS NULL",
+        )
+        .bind(status.as_str())
+        .bind(now_timestamp())
+        .bind(focus_session_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 最近连续未完成长休息（跳过或错过）的次数
+    pub async fn consecutive_skipped_long_breaks(&self) -> Result<i64, StorageError> {
+        let statuses: Vec<String> = sqlx::query_scalar(
+            "SELECT long_break_status FROM focus_sessions
+             WHERE session_type IN ('Focus', 'OpenFocus') AND long_break_status IS NOT NULL
+             ORDER BY start_time DESC
+             LIMIT ?",
+        )
+        .bind(MAX_SKIP_LOOKBACK)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(statuses.iter().take_while(|status| *status != LongBreakStatus::Taken.as_str()).count() as i64)
+    }
+
+    /// 时间范围内专注会话之后的长休息执行情况
+    pub async fn long_break_adherence(&self, start: &DateTime<Utc>, end: &DateTime<Utc>) -> Result<LongBreakAdherence, StorageError> {
+        let row = sqlx::query(
+            "SELECT
+                 COUNT(CASE WHEN long_break_status = 'Taken' THEN 1 END) AS taken,
+                 COUNT(CASE WHEN long_break_status = 'Skipped' THEN 1 END) AS skipped,
+                 COUNT(CASE WHEN long_break_status = 'Missed' THEN 1 END) AS missed,
+                 AVG(long_break_gap_seconds) AS average_gap_seconds
+             FROM focus_sessions
+             WHERE session_type IN ('Focus', 'OpenFocus') AND start_time >= ? AND start_time < ?",
+        )
+        .bind(format_timestamp(start))
+        .bind(format_timestamp(end))
+        .fetch_one(&self.pool)
+        .await?;
+
+        let taken: i64 = row.try_get("taken")?;
+        let skipped: i64 = row.try_get("skipped")?;
+        let missed: i64 = row.try_get("missed")?;
+        let resolved = taken + skipped + missed;
+
+        Ok(LongBreakAdherence {
+            taken,
+            skipped,
+            missed,
+            adherence_rate: (resolved > 0).then(|| taken as f64 / resolved as f64),
+            average_gap_seconds: row.try_get("average_gap_seconds")?,
+            consecutive_skipped: self.consecutive_skipped_long_breaks().await?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::test_support::migrated_pool;
+
+    /// 按时间顺序写入专注会话及其之后的长休息情况（None表示尚未确定）
+    async fn focus_history(store: &SessionStore, pool: &SqlitePool, statuses: &[Option<LongBreakStatus>]) -> DateTime<Utc> {
+        let base = Utc::now() - Duration::hours(statuses.len() as i64 + 1);
+        for (index, status) in statuses.iter().enumerate() {
+            let id = format!("focus-{}", index);
+            store.insert_session(&id, &SessionType::Focus, 1500, None).await.unwrap();
+            sqlx::query("UPDATE focus_sessions SET start_time = ? WHERE id = ?")
+                .bind(format_timestamp(&(base + Duration::hours(index as i64))))
+                .bind(&id)
+                .execute(pool)
+                .await
+                .unwrap();
+            if let Some(status) = status {
+                store.resolve_long_break(&id, *status).await.unwrap();
+            }
+        }
+        base
+    }
+
+    #[tokio::test]
+    async fn consecutive_skips_stop_at_the_last_taken_break() {
+        let (_dir, pool) = migrated_pool().await;
+        let store = SessionStore::new(pool.clone());
+        use LongBreakStatus::*;
+
+        focus_history(&store, &pool, &[Some(Skipped), Some(Taken), Some(Skipped), Some(Missed), None]).await;
+        // 尚未确定的会话不计入也不中断
+        assert_eq!(store.consecutive_skipped_long_breaks().await.unwrap(), 2);
+
+        store.resolve_long_break("focus-4", Taken).await.unwrap();
+        assert_eq!(store.consecutive_skipped_long_breaks().await.unwrap(), 0);
+
+        // 只记录第一次结果
+        store.resolve_long_break("focus-4", Skipped).await.unwrap();
+        assert_eq!(store.consecutive_skipped_long_breaks().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn consecutive_skips_without_any_taken_break() {
+        let (_dir, pool) = migrated_pool().await;
+        let store = SessionStore::new(pool.clone());
+        use LongBreakStatus::*;
+
+        assert_eq!(store.consecutive_skipped_long_breaks().await.unwrap(), 0);
+        focus_history(&store, &pool, &[Some(Missed), Some(Skipped), Some(Missed)]).await;
+        assert_eq!(store.consecutive_skipped_long_breaks().await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn adherence_aggregates_statuses_and_gaps() {
+        let (_dir, pool) = migrated_pool().await;
+        let store = SessionStore::new(pool.clone());
+        use LongBreakStatus::*;
+
+        let base = focus_history(&store, &pool, &[Some(Taken), Some(Taken), Some(Skipped), Some(Missed), None]).await;
+        store.start_long_break_for("focus-0", "break-0", 60).await.unwrap();
+        store.start_long_break_for("focus-1", "break-1", 180).await.unwrap();
+
+        let adherence = store.long_break_adherence(&base, &Utc::now()).await.unwrap();
+        assert_eq!((adherence.taken, adherence.skipped, adherence.missed), (2, 1, 1));
+        assert_eq!(adherence.adherence_rate, Some(0.5));
+        assert_eq!(adherence.average_gap_seconds, Some(120.0));
+        assert_eq!(adherence.consecutive_skipped, 2);
+
+        // 范围内没有已确定结果的会话时没有完成比例
+        let later = Utc::now() + Duration::hours(1);
+        let empty = store.long_break_adherence(&later, &(later + Duration::hours(1))).await.unwrap();
+        assert_eq!(empty.adherence_rate, None);
+        assert_eq!(empty.average_gap_seconds, None);
+    }
+}
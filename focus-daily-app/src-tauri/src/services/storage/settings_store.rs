@@ -0,0 +1,120 @@
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+
+use crate::database::now_timestamp;
+use crate::models::{Theme, UserSettings};
+use super::{StorageError, DEFAULT_PROFILE_ID};
+
+/// 用户设置存储（user_settings表中的默认设置）
+#[derive(Clone)]
+pub struct SettingsStore {
+    pool: SqlitePool,
+}
+
+impl SettingsStore {
+    /// 创建新的用户设置存储
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// 获取用户设置（设置记录不存在时返回默认值）
+    pub async fn get_settings(&self) -> Result<UserSettings, StorageError> {
+        let row = sqlx::query("SELECT * FROM user_settings WHERE id = ?")
+            .bind(DEFAULT_PROFILE_ID)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        match row {
+            Some(row) => Self::row_to_settings(&row),
+            None => Ok(UserSettings {
+                id: DEFAULT_PROFILE_ID.to_string(),
+                ..UserSettings::default()
+            }),
+        }
+    }
+
+    /// 保存用户设置
+    pub async fn save_settings(&self, settings: &UserSettings) -> Result<UserSettings, StorageError> {
+        Self::validate(settings)?;
+
+        let now = now_timestamp();
+        sqlx::query(
+            "INSERT INTO user_settings (
+                 id, theme, language, auto_start, focus_duration_minutes, long_break_duration_minutes,
+                 micro_break_min_interval_minutes, micro_break_max_interval_minutes, micro_break_duration_seconds,
+                 notifications_enabled, long_break_skip_warning_threshold, flow_mode_enabled, created_at, updated_at
+             )
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET
+                theme = excluded.theme,
+                language = excluded.language,
+                auto_start = excluded.auto_start,
+                focus_duration_minutes = excluded.focus_duration_minutes,
+                long_break_duration_minutes = excluded.long_break_duration_minutes,
+                micro_break_min_interval_minutes = excluded.micro_break_min_interval_minutes,
+                micro_break_max_interval_minutes = excluded.micro_break_max_interval_minutes,
+                micro_break_duration_seconds = excluded.micro_break_duration_seconds,
+                notifications_enabled = excluded.notifications_enabled,
+                long_break_skip_warning_threshold = excluded.long_break_skip_warning_threshold,
+                flow_mode_enabled = excluded.flow_mode_enabled,
+                updated_at = excluded.updated_at",
+        )
+        .bind(DEFAULT_PROFILE_ID)
+        .bind(settings.theme.as_str())
+        .bind(&settings.language)
+        .bind(settings.auto_start)
+        .bind(settings.focus_duration_minutes)
+        .bind(settings.long_break_duration_minutes)
+        .bind(settings.micro_break_min_interval_minutes)
+        .bind(settings.micro_break_max_interval_minutes)
+        .bind(settings.micro_break_duration_seconds)
+        .bind(settings.notifications_enabled)
+        .bind(settings.long_break_skip_warning_threshold)
+        .bind(settings.flow_mode_enabled)
+        .bind(&now)
+        .bind(&now)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_settings().await
+    }
+
+    /// 校验时长设置
+    fn validate(settings: &UserSettings) -> Result<(), StorageError> {
+        if settings.focus_duration_minutes <= 0 || settings.long_break_duration_minutes <= 0 {
+            return Err(StorageError::InvalidInput("专注和长休息时长必须大于0".to_string()));
+        }
+        if settings.micro_break_duration_seconds <= 0 {
+            return Err(StorageError::InvalidInput("微休息时长必须大于0".to_string()));
+        }
+        if settings.micro_break_min_interval_minutes <= 0
+            || settings.micro_break_min_interval_minutes > settings.micro_break_max_interval_minutes
+        {
+            return Err(StorageError::InvalidInput("微休息间隔的最小值必须大于0且不超过最大值".to_string()));
+        }
+        Ok(())
+    }
+
+    fn row_to_settings(row: &SqliteRow) -> Result<UserSettings, StorageError> {
+        let theme: String = row.try_get("theme")?;
+
+        Ok(UserSettings {
+            id: row.try_get("id")?,
+            theme: Theme::parse(&theme)
+                .ok_or_else(|| StorageError::InvalidInput(format!("未知的主题: {}", theme)))?,
+            language: row.try_get("language")?,
+            auto_start: row.try_get("auto_start")?,
+            focus_duration_minutes: row.try_get("focus_duration_minutes")?,
+            long_break_duration_minutes: row.try_get("long_break_duration_minutes")?,
+            micro_break_min_interval_minutes: row.try_get("micro_break_min_interval_minutes")?,
+            micro_break_max_interval_minutes: row.try_get("micro_break_max_interval_minutes")?,
+            micro_break_duration_seconds: row.try_get("micro_break_duration_seconds")?,
+            notifications_enabled: row.try_get("notifications_enabled")?,
+            long_break_skip_warning_threshold: row.try_get::<Option<i64>, _>("long_break_skip_warning_threshold")?
+                .map(|threshold| threshold.max(0) as u32),
+            flow_mode_enabled: row.try_get("flow_mode_enabled")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
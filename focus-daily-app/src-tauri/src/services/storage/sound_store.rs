@@ -0,0 +1,198 @@
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+
+use crate::database::now_timestamp;
+use crate::models::CustomSound;
+use super::StorageError;
+
+/// 查询音效及其引用计数（引用即指向该文件的提示音配置和背景音设置）
+const SELECT_SOUNDS: &str =
+    "SELECT s.id, s.name, s.file_path, s.format, s.size_bytes, s.duration_ms, s.loudness_db, s.peak_db, s.created_at,
+            (SELECT COUNT(*) FROM audio_configs a WHERE a.file_path = s.file_path)
+            + (SELECT COUNT(*) FROM ambient_settings m WHERE m.source = 'File' AND m.file_path = s.file_path)
+            AS reference_count
+     FROM custom_sounds s";
+
+/// 待保存的音效信息
+#[derive(Debug, Clone)]
+pub struct NewCustomSound {
+    pub id: String,
+    pub name: String,
+    pub file_path: String,
+    pub format: String,
+    pub size_bytes: u64,
+    pub duration_ms: u64,
+    pub loudness_db: f32,
+    pub peak_db: f32,
+}
+
+/// 自定义音效存储（custom_sounds表）
+#[derive(Clone)]
+pub struct SoundStore {
+    pool: SqlitePool,
+}
+
+impl SoundStore {
+    /// 创建新的音效存储
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// 保存导入的音效
+    pub async fn insert_sound(&self, sound: &NewCustomSound) -> Result<CustomSound, StorageError> {
+        sqlx::query(
+            "INSERT INTO custom_sounds (id, name, file_path, format, size_bytes, duration_ms, loudness_db, peak_db, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&sound.id)
+        .bind(&sound.name)
+        .bind(&sound.file_path)
+        .bind(&sound.format)
+        .bind(sound.size_bytes as i64)
+        .bind(sound.duration_ms as i64)
+        .bind(sound.loudness_db as f64)
+        .bind(sound.peak_db as f64)
+        .bind(now_timestamp())
+        .execute(&self.pool)
+        .await?;
+
+        self.get_sound(&sound.id).await
+    }
+
+    /// 获取单个音效
+    pub async fn get_sound(&self, sound_id: &str) -> Result<CustomSound, StorageError> {
+        let row = sqlx::query(&format!("{} WHERE s.id = ?", SELECT_SOUNDS))
+            .bind(sound_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| StorageError::NotFound(format!("音效 {}", sound_id)))?;
+
+        Self::row_to_sound(&row)
+    }
+
+    /// 列出全部音效（按导入时间）
+    pub async fn list_sounds(&self) -> Result<Vec<CustomSound>, StorageError> {
+        let rows = sqlx::query(&format!("{} ORDER BY s.created_at", SELECT_SOUNDS))
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.iter().map(Self::row_to_sound).collect()
+    }
+
+    /// 删除音效记录；仍被提示音或背景音引用时拒绝删除
+    pub async fn delete_sound(&self, sound_id: &str) -> Result<CustomSound, StorageError> {
+        let sound = self.get_sound(sound_id).await?;
+
+        // 引用检查与删除在同一条语句中完成，避免删除期间被重新引用
+        let result = sqlx::query(
+            "DELETE FROM custom_sounds
+             WHERE id = ?
+               AND NOT EXISTS (SELECT 1 FROM audio_configs WHERE file_path = custom_sounds.file_path)
+               AND NOT EXISTS (
+                   SELECT 1 FROM ambient_settings WHERE source = 'File' AND file_path = custom_sounds.file_path
+               )",
+        )
+        .bind(sound_id)
+        .execute(&self.pool)
+        .await?;
+        if result.rows_affected() == 0 {
+            let sound = self.get_sound(sound_id).await?;
+            return Err(StorageError::InvalidInput(format!(
+                "音效「{}」正被使用（{}处），不能删除",
+                sound.name, sound.reference_count
+            )));
+        }
+
+        Ok(sound)
+    }
+
+    fn row_to_sound(row: &SqliteRow) -> Result<CustomSound, StorageError> {
+        Ok(CustomSound {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            file_path: row.try_get("file_path")?,
+            format: row.try_get("format")?,
+            size_bytes: row.try_get::<i64, _>("size_bytes")?.max(0) as u64,
+            duration_ms: row.try_get::<i64, _>("duration_ms")?.max(0) as u64,
+            loudness_db: row.try_get::<f64, _>("loudness_db")? as f32,
+            peak_db: row.try_get::<f64, _>("peak_db")? as f32,
+            reference_count: row.try_get::<i64, _>("reference_count")?.max(0) as u32,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::migrated_pool;
+
+    fn new_sound(id: &str) -> NewCustomSound {
+        NewCustomSound {
+            id: id.to_string(),
+            name: "雨声".to_string(),
+            file_path: format!("/sounds/{}.wav", id),
+            format: "wav".to_string(),
+            size_bytes: 1024,
+            duration_ms: 2000,
+            loudness_db: -18.0,
+            peak_db: -6.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn referenced_sound_cannot_be_deleted() {
+        let (_dir, pool) = migrated_pool().await;
+        let store = SoundStore::new(pool.clone());
+        store.insert_sound(&new_sound("rain")).await.unwrap();
+
+        sqlx::query(
+            "INSERT INTO audio_configs (id, config_type, file_path, is_default, created_at, updated_at)
+             VALUES ('cue', 'FocusEnd', '/sounds/rain.wav', 0, ?, ?)",
+        )
+        .bind(now_timestamp())
+        .bind(now_timestamp())
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO ambient_settings (profile_id, source, file_path, updated_at)
+             VALUES ('default', 'File', '/sounds/rain.wav', ?)",
+        )
+        .bind(now_timestamp())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert_eq!(store.get_sound("rain").await.unwrap().reference_count, 2);
+        assert!(matches!(store.delete_sound("rain").await, Err(StorageError::InvalidInput(_))));
+
+        // 背景音不再使用文件时仍被提示音引用
+        sqlx::query("UPDATE ambient_settings SET source = 'BrownNoise'").execute(&pool).await.unwrap();
+        assert!(matches!(store.delete_sound("rain").await, Err(StorageError::InvalidInput(_))));
+
+        sqlx::query("DELETE FROM audio_configs").execute(&pool).await.unwrap();
+        let deleted = store.delete_sound("rain").await.unwrap();
+        assert_eq!(deleted.file_path, "/sounds/rain.wav");
+        assert!(store.list_sounds().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn deleting_missing_sound_is_not_found() {
+        let (_dir, pool) = migrated_pool().await;
+        let store = SoundStore::new(pool);
+
+        assert!(matches!(store.delete_sound("missing").await, Err(StorageError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn sound_paths_are_unique() {
+        let (_dir, pool) = migrated_pool().await;
+        let store = SoundStore::new(pool);
+        store.insert_sound(&new_sound("rain")).await.unwrap();
+
+        let duplicate = NewCustomSound { id: "other".to_string(), ..new_sound("rain") };
+        assert!(matches!(store.insert_sound(&duplicate).await, Err(StorageError::Database(_))));
+        assert_eq!(store.list_sounds().await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,199 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+
+use crate::database::now_timestamp;
+use crate::models::{DailyGoalProgress, StreakStats};
+use crate::services::analytics::{consistency_score, StreakCounter};
+use super::{GoalStore, StorageError};
+
+/// 每次范围统计的天数（不超过GoalStore允许的范围）
+const CHUNK_DAYS: i64 = 365;
+
+/// 目标连续达成存储（goal_streaks表）
+///
+/// 保存到最近一次达成日为止的连续天数，之后的日期在读取或达成时增量推进。
+#[derive(Clone)]
+pub struct StreakStore {
+    pool: SqlitePool,
+    goals: GoalStore,
+}
+
+impl StreakStore {
+    /// 创建新的连续达成存储
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            goals: GoalStore::new(pool.clone()),
+            pool,
+        }
+    }
+
+    /// 获取连续达成天数及最近7/30天的一致性得分
+    pub async fn get_streak_stats(&self, today: NaiveDate) -> Result<StreakStats, StorageError> {
+        let counter = self.advance(today).await?;
+        let recent = self.goals.range_stats(today - Duration::days(29), today).await?.days;
+        let last_week = &recent[recent.len().saturating_sub(7)..];
+
+        Ok(StreakStats {
+            current_streak: counter.current_streak,
+            longest_streak: counter.longest_streak,
+            last_reached_date: counter.last_reached_date,
+            consistency_7d: consistency_score(last_week, today),
+            consistency_30d: consistency_score(&recent, today),
+        })
+    }
+
+    /// 从上次达成日之后逐天推进到今天并保存
+    pub async fn advance(&self, today: NaiveDate) -> Result<StreakCounter, StorageError> {
+        let Some(mut counter) = self.load().await? else {
+            return self.recompute(today).await;
+        };
+
+        let from = match counter.last_reached_date {
+            Some(date) if date >= today => return Ok(counter),
+            Some(date) => date + Duration::days(1),
+            None => return self.recompute(today).await,
+        };
+        if (today - from).num_days() >= CHUNK_DAYS {
+            // 间隔过长时必然已中断，直接全量重算
+            return self.recompute(today).await;
+        }
+
+        for day in self.goals.range_stats(from, today).await?.days {
+            counter.push(&day, today);
+        }
+        self.save(&counter).await?;
+        Ok(counter)
+    }
+
+    /// 根据目标历史全量重算连续天数并保存（目标配置变化后调用）
+    pub async fn recompute(&self, today: NaiveDate) -> Result<StreakCounter, StorageError> {
+        let mut counter = StreakCounter::default();
+
+        if let Some(mut from) = self.earliest_goal_date().await? {
+            while from <= today {
+                let to = (from + Duration::days(CHUNK_DAYS - 1)).min(today);
+                let days: Vec<DailyGoalProgress> = self.goals.range_stats(from, to).await?.days;
+                for day in &days {
+                    counter.push(day, today);
+                }
+                from = to + Duration::days(1);
+            }
+        }
+
+        self.save(&counter).await?;
+        Ok(counter)
+    }
+
+    /// 最早可能存在目标的本地日期
+    async fn earliest_goal_date(&self) -> Result<Option<NaiveDate>, StorageError> {
+        let history: Option<NaiveDate> = sqlx::query_scalar("SELECT MIN(date) FROM goal_history")
+            .fetch_one(&self.pool)
+            .await?;
+        let configured: Option<DateTime<Utc>> = sqlx::query_scalar("SELECT MIN(created_at) FROM daily_goals")
+            .fetch_one(&self.pool)
+            .await?;
+        let configured = configured.map(|time| time.with_timezone(&Local).date_naive());
+
+        Ok(match (history, configured) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        })
+    }
+
+    /// 读取保存的连续天数
+    async fn load(&self) -> Result<Option<StreakCounter>, StorageError> {
+        let row = sqlx::query("SELECT current_streak, longest_streak, last_reached_date FROM goal_streaks WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await?;
+
+        row.map(|row| {
+            Ok(StreakCounter {
+                current_streak: row.try_get("current_streak")?,
+                longest_streak: row.try_get("longest_streak")?,
+                last_reached_date: row.try_get("last_reached_date")?,
+            })
+        })
+        .transpose()
+    }
+
+    /// 保存连续天数
+    async fn save(&self, counter: &StreakCounter) -> Result<(), StorageError> {
+        sqlx::query(
+            "INSERT INTO goal_streaks (id, current_streak, longest_streak, last_reached_date, updated_at)
+             VALUES (1, ?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET
+                 current_streak = excluded.current_streak,
+                 longest_streak = excluded.longest_streak,
+                 last_reached_date = excluded.last_reached_date,
+                 updated_at = excluded.updated_at",
+        )
+        .bind(counter.current_streak)
+        .bind(counter.longest_streak)
+        .bind(counter.last_reached_date)
+        .bind(now_timestamp())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::format_timestamp;
+    use crate::models::GoalType;
+    use crate::services::storage::goal_store::local_day_bounds;
+    use crate::test_support::migrated_pool;
+
+    async fn goals_created_days_ago(pool: &SqlitePool, days: i64) {
+        let goals = GoalStore::new(pool.clone());
+        for weekday in 0..7 {
+            goals.set_daily_goal(weekday, GoalType::Cycles, 1).await.unwrap();
+        }
+        sqlx::query("UPDATE daily_goals SET created_at = ?")
+            .bind(format_timestamp(&(Utc::now() - Duration::days(days))))
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn goal_set_today_has_no_consistency_yet() {
+        let (_dir, pool) = migrated_pool().await;
+        goals_created_days_ago(&pool, 0).await;
+
+        let stats = StreakStore::new(pool).get_streak_stats(Local::now().date_naive()).await.unwrap();
+
+        assert_eq!(stats.consistency_7d, None);
+        assert_eq!(stats.consistency_30d, None);
+        assert_eq!(stats.current_streak, 0);
+    }
+
+    #[tokio::test]
+    async fn consistency_counts_only_days_since_goal_was_created() {
+        let (_dir, pool) = migrated_pool().await;
+        goals_created_days_ago(&pool, 2).await;
+        let today = Local::now().date_naive();
+        let goals = GoalStore::new(pool.clone());
+
+        // 昨天完成一个专注会话，前天没有
+        let (start, _) = local_day_bounds(today - Duration::days(1));
+        sqlx::query(
+            "INSERT INTO focus_sessions (id, start_time, duration_seconds, session_type, completed, created_at, updated_at)
+             VALUES ('s1', ?, 1500, 'Focus', 1, ?, ?)",
+        )
+        .bind(format_timestamp(&(start + Duration::hours(12))))
+        .bind(now_timestamp())
+        .bind(now_timestamp())
+        .execute(&pool)
+        .await
+        .unwrap();
+        goals.record_day_progress(today - Duration::days(1)).await.unwrap();
+
+        let stats = StreakStore::new(pool).get_streak_stats(today).await.unwrap();
+
+        assert_eq!(stats.consistency_7d, Some(0.5));
+        assert_eq!(stats.consistency_30d, Some(0.5));
+    }
+}
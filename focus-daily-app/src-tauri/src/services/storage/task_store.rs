@@ -0,0 +1,239 @@
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::database::now_timestamp;
+use crate::models::{CreateTask, Task, TaskStatus, TaskWithStats};
+use super::StorageError;
+
+/// 查询任务及累计专注数据的公共SQL
+const TASK_WITH_STATS_SQL: &str = "
+    SELECT
+        t.id, t.title, t.project, t.estimated_cycles, t.status,
+        t.completed_at, t.created_at, t.updated_at,
+        COALESCE(SUM(CASE WHEN fs.completed = 1
+            THEN fs.duration_seconds + COALESCE(fs.overtime_seconds, 0) ELSE 0 END), 0) AS focus_seconds,
+        COUNT(CASE WHEN fs.completed = 1 THEN 1 END) AS completed_cycles
+    FROM tasks t
+    LEFT JOIN focus_sessions fs ON fs.task_id = t.id AND fs.session_type IN ('Focus', 'OpenFocus')
+";
+
+/// 任务存储（tasks表）
+#[derive(Clone)]
+pub struct TaskStore {
+    pool: SqlitePool,
+}
+
+impl TaskStore {
+    /// 创建新的任务存储
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// 创建任务
+    pub async fn create_task(&self, input: CreateTask) -> Result<Task, StorageError> {
+        let title = input.title.trim();
+        if title.is_empty() {
+            return Err(StorageError::InvalidInput("任务标题不能为空".to_string()));
+        }
+        if input.estimated_cycles < 1 {
+            return Err(StorageError::InvalidInput("预估周期数至少为1".to_string()));
+        }
+
+        let id = Uuid::new_v4().to_string();
+        let project = input.project
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty());
+        let now = now_timestamp();
+
+        sqlx::query(
+            "INSERT INTO tasks (id, title, project, estimated_cycles, status, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(title)
+        .bind(&project)
+        .bind(input.estimated_cycles)
+        .bind(TaskStatus::Todo.as_str())
+        .bind(&now)
+        .bind(&now)
+        .execute(&self.pool)
+        .await?;
+
+        self.get_task(&id).await
+    }
+
+    /// 获取单个任务
+    pub async fn get_task(&self, task_id: &str) -> Result<Task, StorageError> {
+        let row = sqlx::query(
+            "SELECT id, title, project, estimated_cycles, status, completed_at, created_at, updated_at
+             FROM tasks WHERE id = ?",
+        )
+        .bind(task_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| StorageError::NotFound(format!("任务 {}", task_id)))?;
+
+        Self::row_to_task(&row)
+    }
+
+    /// 获取任务及其累计专注数据
+    pub async fn get_task_with_stats(&self, task_id: &str) -> Result<TaskWithStats, StorageError> {
+        let sql = format!("{} WHERE t.id = ? GROUP BY t.id", TASK_WITH_STATS_SQL);
+        let row = sqlx::query(&sql)
+            .bind(task_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| StorageError::NotFound(format!("任务 {}", task_id)))?;
+
+        Self::row_to_task_with_stats(&row)
+    }
+
+    /// 列出任务（按创建时间倒序）
+    pub async fn list_tasks(&self, include_completed: bool) -> Result<Vec<TaskWithStats>, StorageError> {
+        let filter = if include_completed { "" } else { "WHERE t.status != 'Completed'" };
+        let sql = format!(
+            "{} {} GROUP BY t.id ORDER BY t.created_at DESC",
+            TASK_WITH_STATS_SQL, filter
+        );
+
+        let rows = sqlx::query(&sql).fetch_all(&self.pool).await?;
+        rows.iter().map(Self::row_to_task_with_stats).collect()
+    }
+
+    /// 标记任务进行中（开始关联的专注会话时调用）
+    pub async fn mark_in_progress(&self, task_id: &str) -> Result<(), StorageError> {
+        sqlx::query("UPDATE tasks SET status = ?, updated_at = ? WHERE id = ? AND status = ?")
+            .bind(TaskStatus::InProgress.as_str())
+            .bind(now_timestamp())
+            .bind(task_id)
+            .bind(TaskStatus::Todo.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 完成任务
+    pub async fn complete_task(&self, task_id: &str) -> Result<TaskWithStats, StorageError> {
+        let now = now_timestamp();
+        let result = sqlx::query(
+            "UPDATE tasks SET status = ?, completed_at = COALESCE(completed_at, ?), updated_at = ? WHERE id = ?",
+        )
+        .bind(TaskStatus::Completed.as_str())
+        .bind(&now)
+        .bind(&now)
+        .bind(task_id)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(StorageError::NotFound(format!("任务 {}", task_id)));
+        }
+
+        self.get_task_with_stats(task_id).await
+    }
+
+    /// 行数据转换为任务
+    fn row_to_task(row: &SqliteRow) -> Result<Task, StorageError> {
+        let status: String = row.try_get("status")?;
+
+        Ok(Task {
+            id: row.try_get("id")?,
+            title: row.try_get("title")?,
+            project: row.try_get("project")?,
+            estimated_cycles: row.try_get("estimated_cycles")?,
+            status: TaskStatus::parse(&status)
+                .ok_or_else(|| StorageError::InvalidInput(format!("未知的任务状态: {}", status)))?,
+            completed_at: row.try_get("completed_at")?,
+            created_at: row.try_get("created_at")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+
+    /// 行数据转换为带统计的任务
+    fn row_to_task_with_stats(row: &SqliteRow) -> Result<TaskWithStats, StorageError> {
+        Ok(TaskWithStats {
+            task: Self::row_to_task(row)?,
+            focus_seconds: row.try_get("focus_seconds")?,
+            completed_cycles: row.try_get("completed_cycles")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SessionType;
+    use crate::services::storage::SessionStore;
+    use crate::test_support::migrated_pool;
+
+    fn new_task(title: &str) -> CreateTask {
+        CreateTask { title: title.to_string(), project: Some("  ".to_string()), estimated_cycles: 2 }
+    }
+
+    #[tokio::test]
+    async fn task_moves_from_todo_to_in_progress_to_completed() {
+        let (_dir, pool) = migrated_pool().await;
+        let store = TaskStore::new(pool);
+
+        let task = store.create_task(new_task(" 写报告 ")).await.unwrap();
+        assert_eq!(task.title, "写报告");
+        assert_eq!(task.project, None);
+        assert_eq!(task.status, TaskStatus::Todo);
+
+        store.mark_in_progress(&task.id).await.unwrap();
+        assert_eq!(store.get_task(&task.id).await.unwrap().status, TaskStatus::InProgress);
+
+        let completed = store.complete_task(&task.id).await.unwrap();
+        assert_eq!(completed.task.status, TaskStatus::Completed);
+        let completed_at = completed.task.completed_at.expect("完成时间");
+
+        // 已完成的任务不会回到进行中，再次完成时保留最初的完成时间
+        store.mark_in_progress(&task.id).await.unwrap();
+        assert_eq!(store.get_task(&task.id).await.unwrap().status, TaskStatus::Completed);
+        let again = store.complete_task(&task.id).await.unwrap();
+        assert_eq!(again.task.completed_at, Some(completed_at));
+    }
+
+    #[tokio::test]
+    async fn invalid_tasks_are_rejected() {
+        let (_dir, pool) = migrated_pool().await;
+        let store = TaskStore::new(pool);
+
+        assert!(matches!(store.create_task(new_task(" ")).await, Err(StorageError::InvalidInput(_))));
+        let no_cycles = CreateTask { estimated_cycles: 0, ..new_task("写报告") };
+        assert!(matches!(store.create_task(no_cycles).await, Err(StorageError::InvalidInput(_))));
+        assert!(matches!(store.complete_task("missing").await, Err(StorageError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn stats_count_completed_focus_with_overtime() {
+        let (_dir, pool) = migrated_pool().await;
+        let store = TaskStore::new(pool.clone());
+        let sessions = SessionStore::new(pool);
+        let task = store.create_task(new_task("写报告")).await.unwrap();
+
+        for (id, session_type, completed) in [
+            ("focus", SessionType::Focus, true),
+            ("open", SessionType::OpenFocus, true),
+            ("unfinished", SessionType::Focus, false),
+            ("break", SessionType::LongBreak, true),
+        ] {
+            sessions.insert_session(id, &session_type, 1500, Some(&task.id)).await.unwrap();
+            if completed {
+                sessions.complete_session(id).await.unwrap();
+            }
+        }
+        sessions.save_overtime("focus", 300).await.unwrap();
+
+        let stats = store.get_task_with_stats(&task.id).await.unwrap();
+        assert_eq!(stats.focus_seconds, 1500 + 300 + 1500);
+        assert_eq!(stats.completed_cycles, 2);
+
+        // 已完成的任务默认不列出
+        store.complete_task(&task.id).await.unwrap();
+        assert!(store.list_tasks(false).await.unwrap().is_empty());
+        assert_eq!(store.list_tasks(true).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,58 @@
+use tauri::State;
+
+use crate::models::{CreateTask, TaskWithStats};
+use crate::services::storage::TaskStore;
+use crate::services::timer_commands::DatabaseState;
+
+/// 创建任务
+#[tauri::command]
+pub async fn create_task(
+    database: State<'_, DatabaseState>,
+    input: CreateTask,
+) -> Result<TaskWithStats, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        let store = TaskStore::new(pool.clone());
+        let task = store.create_task(input).await.map_err(|e| e.to_string())?;
+        store.get_task_with_stats(&task.id).await.map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 列出任务及累计专注时间
+#[tauri::command]
+pub async fn list_tasks(
+    database: State<'_, DatabaseState>,
+    include_completed: Option<bool>,
+) -> Result<Vec<TaskWithStats>, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        TaskStore::new(pool.clone())
+            .list_tasks(include_completed.unwrap_or(false))
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
+
+/// 完成任务
+#[tauri::command]
+pub async fn complete_task(
+    database: State<'_, DatabaseState>,
+    task_id: String,
+) -> Result<TaskWithStats, String> {
+    let database_guard = database.read().await;
+
+    if let Some(pool) = database_guard.as_ref() {
+        TaskStore::new(pool.clone())
+            .complete_task(&task_id)
+            .await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("数据库未连接".to_string())
+    }
+}
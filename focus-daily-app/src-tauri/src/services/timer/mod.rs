@@ -5,7 +5,7 @@ pub mod micro_break_timer;
 pub mod timer_state;
 
 // 重新导出主要的公共接口
-pub use timer_manager::{TimerManager, CycleState};
+pub use timer_manager::{TimerManager, CycleState, FocusSessionOptions};
 pub use timer_state::{TimerState, SessionPhase, TimerEvent};
 pub use focus_timer::FocusTimer;
 pub use long_break_timer::LongBreakTimer;
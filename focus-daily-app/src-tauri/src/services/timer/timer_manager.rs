@@ -4,8 +4,20 @@ use tokio::time::{interval, Duration};
 use uuid::Uuid;
 use tauri::{AppHandle, Emitter};
 use rand::Rng;
-
-use crate::models::{SessionType, UserSettings};
+use sqlx::sqlite::SqlitePool;
+
+use crate::models::{
+    AmbientSettings, AudioType, CyclePlan, CyclePlanProgress, DailyGoalProgress, LongBreakStatus, PhaseSequence, SequenceStep,
+    QuietHoursMode, SessionType, StepKind, TaskStatus, UserSettings,
+};
+use crate::services::analytics::{compute_focus_quality, FocusQualityInput};
+use crate::services::audio::{AmbientPlayer, AudioService, AMBIENT_STOP_FADE};
+use crate::services::events::{AudioEvent, EnhancedEventManager};
+use crate::services::storage::{
+    classic_sequence, AmbientStore, AudioConfigStore, FocusMetrics, GoalStore, LabelStore, SequenceStore, SessionStore,
+    StorageError, StreakStore, TaskStore, CLASSIC_SEQUENCE_ID, DEFAULT_PROFILE_ID,
+};
+use super::micro_break_timer::EnhancedMicroBreakScheduler;
 use super::timer_state::{TimerState, SessionPhase, TimerEvent};
 
 /// 定时器管理器错误类型
@@ -23,6 +35,15 @@ pub enum TimerError {
     InvalidState(String),
 }
 
+impl From<StorageError> for TimerError {
+    fn from(error: StorageError) -> Self {
+        match error {
+            StorageError::Database(e) => TimerError::Database(e.to_string()),
+            other => TimerError::InvalidState(other.to_string()),
+        }
+    }
+}
+
 /// 会话循环状态
 #[derive(Debug, Clone, PartialEq)]
 pub enum CycleState {
@@ -30,10 +51,128 @@ pub enum CycleState {
     InFocusSession,     // 专注会话中
     InLongBreak,        // 长休息中
     InMicroBreak,       // 微休息中
+    InSequenceStep,     // 阶段序列的热身、复盘或休息步骤中
     Completed,          // 已完成
 }
 
+/// 开始专注会话的可选参数
+#[derive(Debug, Clone, Default)]
+pub struct FocusSessionOptions {
+    /// 关联任务ID
+    pub task_id: Option<String>,
+    /// 所属项目ID
+    pub project_id: Option<String>,
+    /// 标签名称
+    pub tags: Vec<String>,
+    /// 是否启用心流模式（为空时使用用户设置）
+    pub flow_mode: Option<bool>,
+}
+
+/// 新会话开始时写入数据库的内容
+struct NewSession<'a> {
+    id: &'a str,
+    session_type: SessionType,
+    duration_seconds: u32,
+    task_id: Option<&'a str>,
+    project_id: Option<&'a str>,
+    tags: &'a [String],
+}
+
+/// 当前专注会话的行为跟踪（微休息期间定时器状态会被替换，这里保持不变）
+struct FocusTracker {
+    /// 专注会话ID
+    session_id: Option<String>,
+    /// 暂停次数
+    pause_count: u32,
+    /// 微休息行为统计
+    micro_breaks: EnhancedMicroBreakScheduler,
+    /// 最近一次微休息触发时的专注已用时长（秒）
+    focus_elapsed_at_break: u64,
+    /// 计划专注时长（秒）
+    planned_seconds: u64,
+    /// 是否已计算过专注质量评分
+    quality_recorded: bool,
+    /// 微休息期间挂起的专注状态，微休息结束后恢复
+    suspended_focus: Option<TimerState>,
+    /// 本专注会话是否安排微休息
+    micro_breaks_enabled: bool,
+}
+
+impl FocusTracker {
+    /// 为新的专注会话创建跟踪器
+    fn new(session_id: Option<String>, settings: &UserSettings) -> Self {
+        Self {
+            session_id,
+            pause_count: 0,
+            micro_breaks: EnhancedMicroBreakScheduler::new(
+                settings.micro_break_min_interval_minutes as u32,
+                settings.micro_break_max_interval_minutes as u32,
+                settings.micro_break_duration_seconds as u32,
+            ),
+            focus_elapsed_at_break: 0,
+            planned_seconds: settings.focus_duration_minutes as u64 * 60,
+            quality_recorded: false,
+            suspended_focus: None,
+            micro_breaks_enabled: true,
+        }
+    }
+
+    /// 当前的行为数据
+    fn metrics(&self) -> FocusMetrics {
+        FocusMetrics {
+            pause_count: self.pause_count,
+            micro_breaks_triggered: self.micro_breaks.break_count(),
+            micro_breaks_completed: self.micro_breaks.completed_count(),
+            micro_breaks_skipped: self.micro_breaks.skip_count(),
+        }
+    }
+}
+
+/// 专注会话结束后是否进行了长休息的跟踪
+#[derive(Default)]
+struct LongBreakTracker {
+    /// 已完成、尚未开始长休息的专注会话ID及其结束时间
+    pending_focus: Option<(String, chrono::DateTime<chrono::Utc>)>,
+    /// 正在进行的长休息所跟随的专注会话ID
+    active_for_focus: Option<String>,
+}
+
+/// 进行中的计划（多周期计划或阶段序列）
+struct ActivePlan {
+    name: String,
+    steps: Vec<SequenceStep>,
+    /// 当前步骤的索引
+    current: usize,
+    /// 每个专注步骤使用的参数
+    options: FocusSessionOptions,
+}
+
+impl ActivePlan {
+    /// 计划中的专注步骤数
+    fn focus_target(&self) -> u32 {
+        self.steps.iter().filter(|step| step.kind.counts_as_focus()).count() as u32
+    }
+}
+
+/// 计划步骤运行时所处的阶段
+fn step_phase(kind: StepKind) -> SessionPhase {
+    match kind {
+        StepKind::Focus => SessionPhase::Focus,
+        StepKind::LongBreak => SessionPhase::LongBreak,
+        StepKind::WarmUp | StepKind::Review | StepKind::Break => SessionPhase::SequenceStep,
+    }
+}
+
+/// 连续未完成长休息的次数是否达到提醒阈值（阈值为0时不提醒）
+fn skip_warning_due(consecutive: i64, threshold: u32) -> bool {
+    threshold > 0 && consecutive >= threshold as i64
+}
+
+/// 计划允许的最大周期数
+const MAX_PLAN_CYCLES: u32 = 12;
+
 /// 定时器管理器
+#[derive(Clone)]
 pub struct TimerManager {
     /// 当前定时器状态
     state: Arc<RwLock<TimerState>>,
@@ -49,12 +188,45 @@ pub struct TimerManager {
     cycle_state: Arc<RwLock<CycleState>>,
     /// 完成的专注会话数量
     completed_focus_sessions: Arc<Mutex<u32>>,
+    /// 当前专注会话的行为跟踪
+    focus_tracker: Arc<Mutex<FocusTracker>>,
+    /// 长休息执行情况跟踪
+    long_break_tracker: Arc<Mutex<LongBreakTracker>>,
+    /// 进行中的多周期计划
+    plan: Arc<Mutex<Option<ActivePlan>>>,
+    /// 会话存储（会话记录的唯一写入方；未连接数据库时为None，不保存会话）
+    session_store: Option<SessionStore>,
+    /// 任务存储
+    task_store: Option<TaskStore>,
+    /// 标签与项目存储
+    label_store: Option<LabelStore>,
+    /// 每日目标存储
+    goal_store: Option<GoalStore>,
+    /// 目标连续达成存储
+    streak_store: Option<StreakStore>,
+    /// 阶段序列存储
+    sequence_store: Option<SequenceStore>,
+    /// 提示音配置存储
+    audio_config_store: Option<AudioConfigStore>,
+    /// 音频服务（未设置时只通知前端，不播放提示音）
+    audio_service: Option<Arc<AudioService>>,
+    /// 专注背景音设置存储
+    ambient_store: Option<AmbientStore>,
+    /// 专注背景音播放器（随音频服务创建）
+    ambient: Option<Arc<AmbientPlayer>>,
+    /// 当前使用的背景音设置档案
+    ambient_profile: Arc<RwLock<String>>,
+    /// 事件管理器（负责去重、重试和写入事件日志；未设置时直接通知前端）
+    event_manager: Option<EnhancedEventManager>,
 }
 
 impl TimerManager {
     /// 创建新的定时器管理器
-    pub fn new(app_handle: AppHandle, settings: UserSettings) -> Self {
+    pub fn new(app_handle: AppHandle, settings: UserSettings, database: Option<SqlitePool>) -> Self {
         Self {
+            focus_tracker: Arc::new(Mutex::new(FocusTracker::new(None, &settings))),
+            long_break_tracker: Arc::new(Mutex::new(LongBreakTracker::default())),
+            plan: Arc::new(Mutex::new(None)),
             state: Arc::new(RwLock::new(TimerState::default())),
             settings: Arc::new(RwLock::new(settings)),
             app_handle,
@@ -62,6 +234,59 @@ impl TimerManager {
             micro_break_handle: Arc::new(Mutex::new(None)),
             cycle_state: Arc::new(RwLock::new(CycleState::WaitingToStart)),
             completed_focus_sessions: Arc::new(Mutex::new(0)),
+            session_store: database.clone().map(SessionStore::new),
+            task_store: database.clone().map(TaskStore::new),
+            label_store: database.clone().map(LabelStore::new),
+            goal_store: database.clone().map(GoalStore::new),
+            streak_store: database.clone().map(StreakStore::new),
+            sequence_store: database.clone().map(SequenceStore::new),
+            audio_config_store: database.clone().map(AudioConfigStore::new),
+            audio_service: None,
+            ambient_store: database.map(AmbientStore::new),
+            ambient: None,
+            ambient_profile: Arc::new(RwLock::new(DEFAULT_PROFILE_ID.to_string())),
+            event_manager: None,
+        }
+    }
+
+    /// 设置发送定时器事件的事件管理器
+    pub fn with_event_manager(mut self, event_manager: EnhancedEventManager) -> Self {
+        self.event_manager = Some(event_manager);
+        self
+    }
+
+    /// 设置播放阶段切换提示音的音频服务
+    pub fn with_audio_service(mut self, audio_service: Arc<AudioService>) -> Self {
+        self.ambient = Some(Arc::new(AmbientPlayer::new(audio_service.clone())));
+        self.audio_service = Some(audio_service);
+        self
+    }
+
+    /// 获取当前使用的背景音设置档案
+    pub async fn get_ambient_profile(&self) -> String {
+        self.ambient_profile.read().await.clone()
+    }
+
+    /// 切换背景音设置档案；专注进行中时立即按新档案播放
+    pub async fn set_ambient_profile(&self, profile_id: String) {
+        *self.ambient_profile.write().await = profile_id;
+        self.refresh_ambient().await;
+    }
+
+    /// 背景音设置变化后，专注进行中时按最新设置重新播放
+    pub async fn refresh_ambient(&self) {
+        let state = self.state.read().await;
+        let in_focus = state.phase.is_focus() && (state.is_running() || state.is_paused());
+        let paused = state.is_paused();
+        drop(state);
+
+        if in_focus && *self.cycle_state.read().await == CycleState::InFocusSession {
+            self.start_ambient().await;
+            if paused {
+                if let Some(ambient) = self.ambient.as_ref() {
+                    ambient.pause();
+                }
+            }
         }
     }
 
@@ -81,8 +306,45 @@ impl TimerManager {
         *settings = new_settings;
     }
 
-    /// 开始专注会话
-    pub async fn start_focus_session(&self) -> Result<String, TimerError> {
+    /// 开始专注会话（可选关联任务、项目和标签）
+    pub async fn start_focus_session(&self, options: FocusSessionOptions) -> Result<String, TimerError> {
+        self.start_focus_phase(options, SessionPhase::Focus, None).await
+    }
+
+    /// 开始正计时专注会话（没有固定时长，由用户结束）
+    pub async fn start_open_focus_session(&self, options: FocusSessionOptions) -> Result<String, TimerError> {
+        self.start_focus_phase(options, SessionPhase::OpenFocus, None).await
+    }
+
+    /// 开始专注阶段（倒计时或正计时）；计划中的步骤使用步骤的时长和微休息设置
+    async fn start_focus_phase(
+        &self,
+        options: FocusSessionOptions,
+        phase: SessionPhase,
+        step: Option<&SequenceStep>,
+    ) -> Result<String, TimerError> {
+        let FocusSessionOptions { task_id, project_id, tags, flow_mode } = options;
+
+        // 校验关联任务
+        if let Some(task_id) = task_id.as_deref() {
+            let task_store = self.task_store.as_ref()
+                .ok_or_else(|| TimerError::Database("数据库未连接，无法关联任务".to_string()))?;
+            let task = task_store.get_task(task_id).await?;
+            if task.status == TaskStatus::Completed {
+                return Err(TimerError::InvalidState(format!("任务「{}」已完成", task.title)));
+            }
+        }
+
+        // 校验项目和标签
+        let has_labels = project_id.is_some() || !tags.is_empty();
+        if has_labels {
+            let label_store = self.label_store.as_ref()
+                .ok_or_else(|| TimerError::Database("数据库未连接，无法设置项目或标签".to_string()))?;
+            if let Some(project_id) = project_id.as_deref() {
+                label_store.get_project(project_id).await?;
+            }
+        }
+
         let mut state = self.state.write().await;
         let mut cycle_state = self.cycle_state.write().await;
         
@@ -98,45 +360,91 @@ impl TimerManager {
 
         // 获取用户设置
         let settings = self.settings.read().await;
-        let duration_minutes = settings.focus_duration_minutes as u32;
-        
-        // 创建新的专注会话状态
+        let (session_type, duration_seconds) = if phase == SessionPhase::OpenFocus {
+            (SessionType::OpenFocus, 0)
+        } else {
+            let minutes = step.map_or(settings.focus_duration_minutes as u32, |step| step.duration_minutes);
+            (SessionType::Focus, minutes * 60)
+        };
+        let micro_breaks = step.is_none_or(|step| step.micro_breaks);
+
+        // 先写入会话、项目标签和任务状态，全部成功后再切换状态（失败时定时器保持原状态）
         let session_id = Uuid::new_v4().to_string();
-        *state = TimerState::new_focus_session(duration_minutes);
+        self.save_session_to_db(&NewSession {
+            id: &session_id,
+            session_type,
+            duration_seconds,
+            task_id: task_id.as_deref(),
+            project_id: project_id.as_deref(),
+            tags: &tags,
+        }).await?;
+
+        // 创建新的专注会话状态
+        if phase == SessionPhase::OpenFocus {
+            *state = TimerState::new_open_focus_session();
+        } else {
+            *state = TimerState::new_focus_session(duration_seconds / 60);
+            state.flow_mode = flow_mode.unwrap_or(settings.flow_mode_enabled);
+        }
         state.session_id = Some(session_id.clone());
+        state.task_id = task_id.clone();
+        state.step_name = step.map(|step| step.name.clone());
         state.start();
 
         // 更新循环状态
         *cycle_state = CycleState::InFocusSession;
 
         // 计算下次微休息时间
-        self.schedule_next_micro_break(&mut state, &settings).await;
+        if micro_breaks {
+            self.schedule_next_micro_break(&mut state, &settings).await;
+        }
+
+        // 重置专注行为跟踪
+        let mut tracker = self.focus_tracker.lock().await;
+        *tracker = FocusTracker::new(Some(session_id.clone()), &settings);
+        tracker.planned_seconds = duration_seconds as u64;
+        tracker.micro_breaks_enabled = micro_breaks;
+        tracker.micro_breaks.start_scheduling();
+        drop(tracker);
 
         drop(state);
         drop(cycle_state);
         drop(settings);
 
-        // 保存会话到数据库
-        self.save_session_to_db(&session_id, SessionType::Focus, duration_minutes * 60).await?;
+        // 上一个专注会话之后没有进行长休息
+        let pending_focus = self.long_break_tracker.lock().await.pending_focus.take();
+        if let Some((focus_id, _)) = pending_focus {
+            self.resolve_long_break(&focus_id, LongBreakStatus::Missed).await;
+        }
 
         // 启动定时器
         self.start_timer_task().await;
 
         // 启动微休息调度器
-        self.start_micro_break_scheduler().await;
+        if micro_breaks {
+            self.start_micro_break_scheduler().await;
+        }
 
         // 发送开始事件
         self.emit_event(TimerEvent::Started {
-            phase: SessionPhase::Focus,
-            duration: (duration_minutes * 60) as u64,
+            phase,
+            duration: duration_seconds as u64,
             session_id: session_id.clone(),
         }).await;
+        self.play_cue(step.and_then(|step| step.audio_cue).unwrap_or(AudioType::FocusStart));
+        self.start_ambient().await;
 
         Ok(session_id)
     }
 
     /// 开始长休息会话
     pub async fn start_long_break_session(&self) -> Result<String, TimerError> {
+        let duration_minutes = self.settings.read().await.long_break_duration_minutes as u32;
+        self.start_break(duration_minutes, None).await
+    }
+
+    /// 开始指定时长的长休息（计划中的休息步骤带有步骤名称和提示音）
+    async fn start_break(&self, duration_minutes: u32, step: Option<&SequenceStep>) -> Result<String, TimerError> {
         let mut state = self.state.write().await;
         let mut cycle_state = self.cycle_state.write().await;
         
@@ -145,13 +453,21 @@ impl TimerManager {
             return Err(TimerError::AlreadyRunning);
         }
 
-        let settings = self.settings.read().await;
-        let duration_minutes = settings.long_break_duration_minutes as u32;
-        
-        // 创建新的长休息状态
+        // 先保存会话，成功后再切换状态
         let session_id = Uuid::new_v4().to_string();
+        self.save_session_to_db(&NewSession {
+            id: &session_id,
+            session_type: SessionType::LongBreak,
+            duration_seconds: duration_minutes * 60,
+            task_id: None,
+            project_id: None,
+            tags: &[],
+        }).await?;
+
+        // 创建新的长休息状态
         *state = TimerState::new_long_break_session(duration_minutes);
         state.session_id = Some(session_id.clone());
+        state.step_name = step.map(|step| step.name.clone());
         state.start();
 
         // 更新循环状态
@@ -159,10 +475,24 @@ impl TimerManager {
 
         drop(state);
         drop(cycle_state);
-        drop(settings);
 
-        // 保存会话到数据库
-        self.save_session_to_db(&session_id, SessionType::LongBreak, duration_minutes * 60).await?;
+        // 暂停中的专注被休息替换时结束背景音
+        if let Some(ambient) = self.ambient.as_ref() {
+            ambient.fade_out(AMBIENT_STOP_FADE);
+        }
+
+        // 记录长休息所跟随的专注会话及间隔
+        let mut long_break_tracker = self.long_break_tracker.lock().await;
+        if let Some((focus_id, focus_ended_at)) = long_break_tracker.pending_focus.take() {
+            let gap_seconds = (chrono::Utc::now() - focus_ended_at).num_seconds().max(0);
+            if let Some(store) = self.session_store.as_ref() {
+                if let Err(e) = store.start_long_break_for(&focus_id, &session_id, gap_seconds).await {
+                    eprintln!("Failed to persist long break of session {}: {}", focus_id, e);
+                }
+            }
+            long_break_tracker.active_for_focus = Some(focus_id);
+        }
+        drop(long_break_tracker);
         
         // 启动定时器（长休息不需要微休息调度器）
         self.start_timer_task().await;
@@ -173,6 +503,57 @@ impl TimerManager {
             duration: (duration_minutes * 60) as u64,
             session_id: session_id.clone(),
         }).await;
+        self.play_cue(step.and_then(|step| step.audio_cue).unwrap_or(AudioType::LongBreakStart));
+
+        Ok(session_id)
+    }
+
+    /// 开始阶段序列中的热身、复盘或休息步骤（不计入专注统计，也不影响长休息跟踪）
+    async fn start_sequence_step(&self, step: &SequenceStep) -> Result<String, TimerError> {
+        let mut state = self.state.write().await;
+        let mut cycle_state = self.cycle_state.write().await;
+
+        // 检查是否已有定时器在运行
+        if state.is_running() {
+            return Err(TimerError::AlreadyRunning);
+        }
+
+        // 先保存会话，成功后再切换状态
+        let session_id = Uuid::new_v4().to_string();
+        self.save_session_to_db(&NewSession {
+            id: &session_id,
+            session_type: step.kind.session_type(),
+            duration_seconds: step.duration_minutes * 60,
+            task_id: None,
+            project_id: None,
+            tags: &[],
+        }).await?;
+
+        *state = TimerState::new_sequence_step_session(step.duration_minutes);
+        state.session_id = Some(session_id.clone());
+        state.step_name = Some(step.name.clone());
+        state.start();
+
+        *cycle_state = CycleState::InSequenceStep;
+
+        drop(state);
+        drop(cycle_state);
+
+        // 背景音只在专注步骤中播放
+        if let Some(ambient) = self.ambient.as_ref() {
+            ambient.fade_out(AMBIENT_STOP_FADE);
+        }
+
+        self.start_timer_task().await;
+
+        self.emit_event(TimerEvent::Started {
+            phase: SessionPhase::SequenceStep,
+            duration: (step.duration_minutes * 60) as u64,
+            session_id: session_id.clone(),
+        }).await;
+        if let Some(cue) = step.audio_cue {
+            self.play_cue(cue);
+        }
 
         Ok(session_id)
     }
@@ -185,6 +566,14 @@ impl TimerManager {
         let settings = self.settings.read().await;
         let duration_seconds = settings.micro_break_duration_seconds as u32;
         
+        // 专注中开始的微休息：挂起专注状态，微休息结束后恢复
+        let suspends_focus = state.phase.is_focus() && (state.is_running() || state.is_paused());
+        if suspends_focus {
+            let mut focus_state = state.clone();
+            focus_state.micro_break_count += 1;
+            self.focus_tracker.lock().await.suspended_focus = Some(focus_state);
+        }
+
         // 创建新的微休息状态
         let session_id = Uuid::new_v4().to_string();
         *state = TimerState::new_micro_break_session(duration_seconds);
@@ -204,6 +593,12 @@ impl TimerManager {
         // 启动微休息定时器
         self.start_timer_task().await;
 
+        // 背景音按设置降低音量或暂停，微休息结束后恢复
+        if let (true, Some(ambient)) = (suspends_focus, self.ambient.as_ref()) {
+            ambient.resume();
+            ambient.duck();
+        }
+
         // 发送微休息开始事件
         self.emit_event(TimerEvent::Started {
             phase: SessionPhase::MicroBreak,
@@ -233,35 +628,430 @@ impl TimerManager {
         // 返回专注会话
         *cycle_state = CycleState::InFocusSession;
         
-        // 重新创建专注会话状态
-        let settings = self.settings.read().await;
-        let focus_duration = settings.focus_duration_minutes as u32;
-        let remaining_focus_time = (focus_duration * 60) as u64 - state.elapsed_duration;
-        
-        *state = TimerState::new_focus_session(focus_duration);
-        state.remaining_duration = remaining_focus_time;
-        state.elapsed_duration = (focus_duration * 60) as u64 - remaining_focus_time;
-        state.start();
+        // 恢复挂起的专注状态；没有挂起状态时重新创建专注会话状态
+        let suspended_focus = self.focus_tracker.lock().await.suspended_focus.take();
+        let focus_phase = suspended_focus.as_ref().map_or(SessionPhase::Focus, |focus| focus.phase.clone());
+        if let Some(focus_state) = suspended_focus {
+            *state = focus_state;
+            state.resume_from_suspension();
+        } else {
+            let settings = self.settings.read().await;
+            let focus_duration = settings.focus_duration_minutes as u32;
+            let remaining_focus_time = ((focus_duration * 60) as u64).saturating_sub(state.elapsed_duration);
+
+            *state = TimerState::new_focus_session(focus_duration);
+            state.remaining_duration = remaining_focus_time;
+            state.elapsed_duration = (focus_duration * 60) as u64 - remaining_focus_time;
+            state.start();
+        }
 
         drop(state);
         drop(cycle_state);
-        drop(settings);
+
+        // 记录微休息跳过
+        let mut tracker = self.focus_tracker.lock().await;
+        let focus_elapsed = tracker.focus_elapsed_at_break;
+        tracker.micro_breaks.on_break_skipped(focus_elapsed);
+        drop(tracker);
+        Self::persist_focus_metrics(&self.focus_tracker, &self.session_store).await;
 
         // 重新启动专注定时器和微休息调度器
         self.start_timer_task().await;
         self.start_micro_break_scheduler().await;
+        if let Some(ambient) = self.ambient.as_ref() {
+            ambient.restore();
+        }
 
         // 发送跳过事件
         self.emit_event(TimerEvent::PhaseChanged {
             from: SessionPhase::MicroBreak,
-            to: SessionPhase::Focus,
+            to: focus_phase,
         }).await;
 
         Ok(())
     }
 
+    /// 结束正计时专注会话，按实际时长保存
+    pub async fn stop_open_focus_session(&self) -> Result<String, TimerError> {
+        let mut state = self.state.write().await;
+
+        if state.phase != SessionPhase::OpenFocus || !(state.is_running() || state.is_paused()) {
+            return Err(TimerError::InvalidState("当前没有进行中的正计时会话".to_string()));
+        }
+
+        let session_id = state.session_id.clone().unwrap_or_default();
+        let elapsed = state.elapsed_duration;
+        state.complete();
+        drop(state);
+
+        self.stop_timer_task().await;
+        self.stop_micro_break_scheduler().await;
+        *self.cycle_state.write().await = CycleState::WaitingToStart;
+
+        if let Some(store) = self.session_store.as_ref() {
+            store.update_planned_duration(&session_id, elapsed).await?;
+            store.complete_session(&session_id).await?;
+        }
+
+        // 以实际时长作为计划时长计算专注质量
+        self.focus_tracker.lock().await.planned_seconds = elapsed;
+        self.finalize_focus_quality(elapsed, true).await;
+        Self::update_goal_progress(&self.app_handle, &self.goal_store, &self.streak_store).await;
+
+        let _ = self.app_handle.emit("open-focus-completed", serde_json::json!({
+            "session_id": session_id,
+            "duration_seconds": elapsed,
+        }));
+        self.emit_event(TimerEvent::Completed {
+            phase: SessionPhase::OpenFocus,
+            session_id: session_id.clone(),
+        }).await;
+        self.play_cue(AudioType::FocusEnd);
+        if let Some(ambient) = self.ambient.as_ref() {
+            ambient.fade_out(AMBIENT_STOP_FADE);
+        }
+
+        Ok(session_id)
+    }
+
+    /// 延长当前会话（任意阶段）
+    pub async fn extend_current_session(&self, seconds: u64) -> Result<TimerState, TimerError> {
+        self.adjust_current_session(seconds as i64).await
+    }
+
+    /// 缩短当前会话（任意阶段），最多缩短到立即结束
+    pub async fn shorten_current_session(&self, seconds: u64) -> Result<TimerState, TimerError> {
+        self.adjust_current_session(-(seconds as i64)).await
+    }
+
+    /// 调整当前会话的计划时长，重新评估下次微休息并保存
+    async fn adjust_current_session(&self, delta_seconds: i64) -> Result<TimerState, TimerError> {
+        if delta_seconds == 0 {
+            return Err(TimerError::InvalidState("调整时长必须大于0秒".to_string()));
+        }
+
+        let mut state = self.state.write().await;
+
+        if !(state.is_running() || state.is_paused()) {
+            return Err(TimerError::NotRunning);
+        }
+        if state.is_overtime() {
+            return Err(TimerError::InvalidState("专注会话已进入加时，无需调整时长".to_string()));
+        }
+        if state.phase == SessionPhase::OpenFocus {
+            return Err(TimerError::InvalidState("正计时会话没有计划时长".to_string()));
+        }
+
+        let applied = state.adjust_duration(delta_seconds);
+
+        // 专注阶段：计划的微休息落在新的结束时间之后时取消，之前已取消且仍有时间时重新安排
+        if state.phase == SessionPhase::Focus && self.focus_tracker.lock().await.micro_breaks_enabled {
+            let settings = self.settings.read().await;
+            let next = state.next_micro_break_at
+                .filter(|at| *at > state.elapsed_duration)
+                .unwrap_or_else(|| state.elapsed_duration + Self::random_micro_break_interval(&settings));
+            state.next_micro_break_at = (state.flow_mode || next < state.total_duration).then_some(next);
+        }
+
+        let snapshot = state.clone();
+        drop(state);
+
+        if snapshot.phase == SessionPhase::Focus {
+            self.focus_tracker.lock().await.planned_seconds = snapshot.total_duration;
+        }
+
+        if let (Some(store), Some(session_id)) = (self.session_store.as_ref(), snapshot.session_id.as_deref()) {
+            store.update_planned_duration(session_id, snapshot.total_duration).await?;
+        }
+
+        self.emit_event(TimerEvent::DurationAdjusted {
+            phase: snapshot.phase.clone(),
+            session_id: snapshot.session_id.clone().unwrap_or_default(),
+            delta_seconds: applied,
+            total: snapshot.total_duration,
+            remaining: snapshot.remaining_duration,
+            next_micro_break_at: snapshot.next_micro_break_at,
+        }).await;
+
+        Ok(snapshot)
+    }
+
+    /// 结束心流模式下正在加时的专注会话
+    pub async fn finish_focus_session(&self) -> Result<(), TimerError> {
+        let mut state = self.state.write().await;
+
+        if !state.is_overtime() || !(state.is_running() || state.is_paused()) {
+            return Err(TimerError::InvalidState("当前没有处于加时阶段的专注会话".to_string()));
+        }
+
+        // 关闭心流模式后，定时器任务会在下一秒按正常流程完成会话
+        state.flow_mode = false;
+        let was_paused = state.is_paused();
+        state.resume();
+        drop(state);
+
+        if was_paused {
+            self.start_timer_task().await;
+        }
+
+        Ok(())
+    }
+
+    /// 跳过长休息（专注结束后放弃开始，或提前结束进行中的长休息）
+    pub async fn skip_long_break(&self) -> Result<(), TimerError> {
+        let mut state = self.state.write().await;
+        let in_long_break = state.phase == SessionPhase::LongBreak && (state.is_running() || state.is_paused());
+
+        let mut long_break_tracker = self.long_break_tracker.lock().await;
+        let focus_id = if in_long_break {
+            long_break_tracker.active_for_focus.take()
+        } else {
+            long_break_tracker.pending_focus.take().map(|(focus_id, _)| focus_id)
+        };
+        drop(long_break_tracker);
+
+        let long_break_session_id = if in_long_break {
+            // 与LongBreakTimer::skip一致，直接结束长休息
+            state.complete();
+            *self.cycle_state.write().await = CycleState::WaitingToStart;
+            state.session_id.clone()
+        } else {
+            None
+        };
+        drop(state);
+
+        if in_long_break {
+            self.stop_timer_task().await;
+            self.play_cue(AudioType::LongBreakEnd);
+        } else if focus_id.is_none() {
+            return Err(TimerError::InvalidState("当前没有可跳过的长休息".to_string()));
+        }
+
+        if let (Some(store), Some(session_id)) = (self.session_store.as_ref(), long_break_session_id.as_deref()) {
+            if let Err(e) = store.end_session(session_id).await {
+                eprintln!("Failed to persist end of session {}: {}", session_id, e);
+            }
+        }
+        if let Some(focus_id) = focus_id.as_deref() {
+            self.resolve_long_break(focus_id, LongBreakStatus::Skipped).await;
+        }
+
+        let _ = self.app_handle.emit("long-break-skipped", serde_json::json!({
+            "focus_session_id": focus_id,
+            "long_break_session_id": long_break_session_id,
+        }));
+
+        // 计划中跳过长休息直接进入下一个专注
+        self.advance_plan(SessionPhase::LongBreak).await;
+
+        Ok(())
+    }
+
+    /// 开始多周期计划：依次进行专注与长休息，直到完成目标专注数
+    pub async fn start_cycle_plan(&self, plan: CyclePlan, options: FocusSessionOptions) -> Result<String, TimerError> {
+        if plan.cycles == 0 || plan.cycles > MAX_PLAN_CYCLES {
+            return Err(TimerError::Settings(format!("计划周期数必须在1到{}之间", MAX_PLAN_CYCLES)));
+        }
+        if plan.final_break_minutes == Some(0) {
+            return Err(TimerError::Settings("最终休息时长必须大于0分钟".to_string()));
+        }
+
+        // 多周期计划即重复内置90/20序列，周期之间为长休息
+        let classic = classic_sequence(&*self.settings.read().await);
+        let (focus, long_break) = (classic.steps[0].clone(), classic.steps[1].clone());
+        let mut steps = Vec::new();
+        for cycle in 0..plan.cycles {
+            if cycle > 0 {
+                steps.push(long_break.clone());
+            }
+            steps.push(focus.clone());
+        }
+        if let Some(minutes) = plan.final_break_minutes {
+            steps.push(SequenceStep {
+                name: "最终休息".to_string(),
+                duration_minutes: minutes,
+                ..long_break
+            });
+        }
+
+        self.start_plan(format!("{} × {}", classic.name, plan.cycles), steps, options).await
+    }
+
+    /// 按阶段序列开始计划（内置90/20序列或用户定义的序列）
+    pub async fn start_phase_sequence(&self, sequence_id: &str, options: FocusSessionOptions) -> Result<String, TimerError> {
+        let sequence = self.get_phase_sequence(sequence_id).await?;
+        self.start_plan(sequence.name, sequence.steps, options).await
+    }
+
+    /// 列出全部阶段序列（内置序列在前）
+    pub async fn list_phase_sequences(&self) -> Result<Vec<PhaseSequence>, TimerError> {
+        let mut sequences = vec![classic_sequence(&*self.settings.read().await)];
+        if let Some(store) = self.sequence_store.as_ref() {
+            sequences.extend(store.list_sequences().await?);
+        }
+        Ok(sequences)
+    }
+
+    /// 获取阶段序列（内置序列按当前设置生成）
+    async fn get_phase_sequence(&self, sequence_id: &str) -> Result<PhaseSequence, TimerError> {
+        if sequence_id == CLASSIC_SEQUENCE_ID {
+            return Ok(classic_sequence(&*self.settings.read().await));
+        }
+        let store = self.sequence_store.as_ref()
+            .ok_or_else(|| TimerError::Database("数据库未连接，无法读取阶段序列".to_string()))?;
+        Ok(store.get_sequence(sequence_id).await?)
+    }
+
+    /// 取消进行中的多周期计划（当前阶段继续进行）
+    pub async fn cancel_cycle_plan(&self) -> Result<(), TimerError> {
+        match self.plan.lock().await.take() {
+            Some(_) => Ok(()),
+            None => Err(TimerError::InvalidState("当前没有进行中的计划".to_string())),
+        }
+    }
+
+    /// 获取进行中的多周期计划进度
+    pub async fn get_cycle_plan(&self) -> Option<CyclePlanProgress> {
+        let plan = self.plan.lock().await;
+        let active = plan.as_ref()?;
+        Some(CyclePlanProgress {
+            name: active.name.clone(),
+            steps: active.steps.clone(),
+            current_step: active.current,
+            completed_focus_sessions: *self.completed_focus_sessions.lock().await,
+            target_focus_sessions: active.focus_target(),
+        })
+    }
+
+    /// 开始计划并进入第一个步骤
+    async fn start_plan(&self, name: String, steps: Vec<SequenceStep>, options: FocusSessionOptions) -> Result<String, TimerError> {
+        let mut active_plan = self.plan.lock().await;
+        if active_plan.is_some() {
+            return Err(TimerError::InvalidState("已有进行中的计划".to_string()));
+        }
+        *active_plan = Some(ActivePlan {
+            name,
+            steps,
+            current: 0,
+            options,
+        });
+        drop(active_plan);
+        *self.completed_focus_sessions.lock().await = 0;
+
+        let result = self.start_plan_step(0).await;
+        if result.is_err() {
+            *self.plan.lock().await = None;
+        }
+        result
+    }
+
+    /// 开始计划中的指定步骤并发送进度事件
+    async fn start_plan_step(&self, index: usize) -> Result<String, TimerError> {
+        let plan = self.plan.lock().await;
+        let (step, options, target) = match plan.as_ref().and_then(|active| Some((active, active.steps.get(index)?))) {
+            Some((active, step)) => (step.clone(), active.options.clone(), active.focus_target()),
+            None => return Err(TimerError::InvalidState("计划步骤不存在".to_string())),
+        };
+        drop(plan);
+
+        let phase = step_phase(step.kind);
+        let session_id = match phase {
+            SessionPhase::Focus => self.start_focus_phase(options, phase.clone(), Some(&step)).await?,
+            SessionPhase::LongBreak => self.start_break(step.duration_minutes, Some(&step)).await?,
+            _ => self.start_sequence_step(&step).await?,
+        };
+
+        let completed = *self.completed_focus_sessions.lock().await;
+        self.emit_event(TimerEvent::PlanProgress {
+            phase,
+            step: index,
+            step_name: step.name,
+            audio_cue: step.audio_cue,
+            completed,
+            target,
+        }).await;
+
+        Ok(session_id)
+    }
+
+    /// 计划中当前步骤结束后进入下一步骤；全部完成时将循环状态标记为已完成
+    async fn advance_plan(&self, finished: SessionPhase) {
+        let mut plan = self.plan.lock().await;
+        let Some(active) = plan.as_mut() else {
+            return;
+        };
+
+        // 只在计划的当前步骤结束时推进（期间手动开始的其他阶段不影响计划）
+        if step_phase(active.steps[active.current].kind) != finished {
+            return;
+        }
+
+        let next = active.current + 1;
+        let target = active.focus_target();
+        if next >= active.steps.len() {
+            // 计划完成
+            *plan = None;
+            drop(plan);
+            let completed = *self.completed_focus_sessions.lock().await;
+            *self.cycle_state.write().await = CycleState::Completed;
+            self.emit_event(TimerEvent::PlanCompleted { completed, target }).await;
+            return;
+        }
+        active.current = next;
+        let no_long_break = finished == SessionPhase::Focus && active.steps[next].kind != StepKind::LongBreak;
+        drop(plan);
+
+        // 专注步骤之后没有安排长休息（例如紧接着复盘或下一个专注），不计入长休息执行情况
+        if no_long_break {
+            self.long_break_tracker.lock().await.pending_focus = None;
+        }
+
+        if let Err(e) = self.start_plan_step(next).await {
+            // 无法进入下一步骤（例如关联任务已完成）时终止计划
+            eprintln!("Failed to advance cycle plan: {}", e);
+            *self.plan.lock().await = None;
+            let _ = self.app_handle.emit("plan-aborted", serde_json::json!({
+                "step": next,
+                "target": target,
+                "error": e.to_string(),
+            }));
+        }
+    }
+
+    /// 在新任务中推进计划（定时器任务内不能直接重启自身）
+    fn advance_plan_later(manager: TimerManager, finished: SessionPhase) {
+        tokio::spawn(async move {
+            manager.advance_plan(finished).await;
+        });
+    }
+
+    /// 记录长休息执行情况；连续未完成次数达到设置阈值时发出提醒
+    async fn resolve_long_break(&self, focus_id: &str, status: LongBreakStatus) {
+        let Some(store) = self.session_store.as_ref() else {
+            return;
+        };
+        if let Err(e) = store.resolve_long_break(focus_id, status).await {
+            eprintln!("Failed to persist long break of session {}: {}", focus_id, e);
+            return;
+        }
+
+        let Some(threshold) = self.settings.read().await.long_break_skip_warning_threshold else {
+            return;
+        };
+        match store.consecutive_skipped_long_breaks().await {
+            Ok(consecutive) if skip_warning_due(consecutive, threshold) => {
+                let _ = self.app_handle.emit("long-break-skip-warning", serde_json::json!({
+                    "consecutive_skipped": consecutive,
+                    "threshold": threshold,
+                }));
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("Failed to count skipped long breaks: {}", e),
+        }
+    }
+
     /// 暂停定时器
-    pub async fn pause_timer(&self) -> Result<(), TimerError> {
+    pub async fn pause_timer(&self, reason: Option<String>) -> Result<(), TimerError> {
         let mut state = self.state.write().await;
         
         if !state.is_running() {
@@ -271,11 +1061,31 @@ impl TimerManager {
         state.pause();
         let phase = state.phase.clone();
         let remaining = state.remaining_duration;
+        let elapsed = state.elapsed_duration;
 
         drop(state);
 
         // 停止定时器任务
         self.stop_timer_task().await;
+        if let Some(ambient) = self.ambient.as_ref() {
+            ambient.pause();
+        }
+
+        // 专注阶段的暂停计为一次中断
+        if phase.is_focus() {
+            let mut tracker = self.focus_tracker.lock().await;
+            tracker.pause_count += 1;
+            let session_id = tracker.session_id.clone();
+            drop(tracker);
+            Self::persist_focus_metrics(&self.focus_tracker, &self.session_store).await;
+
+            if let (Some(store), Some(session_id)) = (self.session_store.as_ref(), session_id) {
+                let reason = reason.as_deref().map(str::trim).filter(|r| !r.is_empty());
+                if let Err(e) = store.record_interruption(&session_id, reason, elapsed).await {
+                    eprintln!("Failed to persist interruption of session {}: {}", session_id, e);
+                }
+            }
+        }
 
         self.emit_event(TimerEvent::Paused {
             phase,
@@ -301,9 +1111,12 @@ impl TimerManager {
 
         // 重新启动定时器任务
         self.start_timer_task().await;
+        if let Some(ambient) = self.ambient.as_ref() {
+            ambient.resume();
+        }
 
         // 如果是专注阶段，重新启动微休息调度器
-        if phase == SessionPhase::Focus {
+        if phase.is_focus() {
             self.start_micro_break_scheduler().await;
         }
 
@@ -319,6 +1132,9 @@ impl TimerManager {
     pub async fn reset_timer(&self) -> Result<(), TimerError> {
         let mut state = self.state.write().await;
         let phase = state.phase.clone();
+        let was_active = state.is_running() || state.is_paused();
+        let elapsed = state.elapsed_duration;
+        let overtime = state.overtime_duration;
         
         state.reset();
         drop(state);
@@ -326,6 +1142,56 @@ impl TimerManager {
         // 停止所有定时器任务
         self.stop_timer_task().await;
         self.stop_micro_break_scheduler().await;
+        if let Some(ambient) = self.ambient.as_ref() {
+            ambient.fade_out(AMBIENT_STOP_FADE);
+        }
+
+        // 专注会话被提前结束时记录结束时间，并同样计算专注质量
+        if was_active {
+            let focus_elapsed = match phase {
+                SessionPhase::Focus | SessionPhase::OpenFocus => Some(elapsed),
+                SessionPhase::MicroBreak => Some(self.focus_tracker.lock().await.focus_elapsed_at_break),
+                SessionPhase::LongBreak | SessionPhase::SequenceStep => None,
+            };
+            if let Some(focus_elapsed) = focus_elapsed {
+                let mut tracker = self.focus_tracker.lock().await;
+                let session_id = tracker.session_id.clone();
+                let suspended_focus = tracker.suspended_focus.take();
+                drop(tracker);
+
+                // 微休息期间重置时，加时与正计时时长以挂起的专注状态为准
+                let (focus_phase, focus_duration, overtime) = match suspended_focus {
+                    Some(focus) => (focus.phase, focus.elapsed_duration, focus.overtime_duration),
+                    None => (phase.clone(), elapsed, overtime),
+                };
+                if let (Some(store), Some(session_id)) = (self.session_store.as_ref(), session_id) {
+                    if focus_phase == SessionPhase::OpenFocus {
+                        if let Err(e) = store.update_planned_duration(&session_id, focus_duration).await {
+                            eprintln!("Failed to persist duration of session {}: {}", session_id, e);
+                        }
+                    }
+                    if let Err(e) = store.end_session(&session_id).await {
+                        eprintln!("Failed to persist end of session {}: {}", session_id, e);
+                    }
+                    if overtime > 0 {
+                        if let Err(e) = store.save_overtime(&session_id, overtime).await {
+                            eprintln!("Failed to persist overtime of session {}: {}", session_id, e);
+                        }
+                    }
+                }
+                self.finalize_focus_quality(focus_elapsed, false).await;
+            }
+        }
+
+        // 重置进行中的长休息视为跳过
+        if was_active && phase == SessionPhase::LongBreak {
+            let focus_id = self.long_break_tracker.lock().await.active_for_focus.take();
+            if let Some(focus_id) = focus_id {
+                self.resolve_long_break(&focus_id, LongBreakStatus::Skipped).await;
+            }
+        }
+        *self.cycle_state.write().await = CycleState::WaitingToStart;
+        *self.plan.lock().await = None;
 
         self.emit_event(TimerEvent::Reset { phase }).await;
 
@@ -342,8 +1208,18 @@ impl TimerManager {
         let settings = Arc::clone(&self.settings);
         let app_handle = self.app_handle.clone();
         let completed_sessions = Arc::clone(&self.completed_focus_sessions);
+        let focus_tracker = Arc::clone(&self.focus_tracker);
+        let session_store = self.session_store.clone();
+        let goal_store = self.goal_store.clone();
+        let streak_store = self.streak_store.clone();
+        let long_break_tracker = Arc::clone(&self.long_break_tracker);
+        let manager = self.clone();
 
         let handle = tokio::spawn(async move {
+            // 阶段结束前的预警时间（剩余秒数）
+            let focus_warning_at = manager.warning_lead(AudioType::FocusWarning).await;
+            let long_break_warning_at = manager.warning_lead(AudioType::LongBreakWarning).await;
+
             let mut interval = interval(Duration::from_secs(1));
             
             loop {
@@ -363,9 +1239,31 @@ impl TimerManager {
                     let remaining = state_guard.remaining_duration;
                     let elapsed = state_guard.elapsed_duration;
                     let progress = state_guard.progress();
-                    
+                    let flow_mode = state_guard.flow_mode;
+
+                    // 剩余时间首次不超过预警时间时播放阶段即将结束提示音（会话开始时已不足或被缩短时同样提示）
+                    let warning = match phase {
+                        SessionPhase::Focus => focus_warning_at.map(|lead| (lead, AudioType::FocusWarning)),
+                        SessionPhase::LongBreak => long_break_warning_at.map(|lead| (lead, AudioType::LongBreakWarning)),
+                        _ => None,
+                    };
+                    let warning = warning
+                        .filter(|(lead, _)| state_guard.take_warning(*lead))
+                        .map(|(_, cue)| cue);
+
                     drop(state_guard);
 
+                    if let Some(cue) = warning {
+                        manager.play_cue(cue);
+                    }
+
+                    // 专注结束前背景音开始淡出（心流模式会继续加时，不淡出）
+                    if let (SessionPhase::Focus, false, Some(ambient)) = (&phase, flow_mode, manager.ambient.as_ref()) {
+                        if ambient.fade_out_lead() == Some(remaining) {
+                            ambient.fade_out(Duration::from_secs(remaining));
+                        }
+                    }
+
                     // 发送tick事件
                     let _ = app_handle.emit("timer-tick", TimerEvent::Tick {
                         phase,
@@ -373,17 +1271,118 @@ impl TimerManager {
                         elapsed,
                         progress,
                     });
+                } else if state_guard.phase == SessionPhase::OpenFocus {
+                    // 正计时专注：没有总时长，持续计时直到用户结束
+                    state_guard.elapsed_duration += 1;
+                    let elapsed = state_guard.elapsed_duration;
+
+                    drop(state_guard);
+
+                    let _ = app_handle.emit("timer-tick", TimerEvent::Tick {
+                        phase: SessionPhase::OpenFocus,
+                        remaining: 0,
+                        elapsed,
+                        progress: 0.0,
+                    });
+                } else if state_guard.flow_mode && state_guard.phase == SessionPhase::Focus {
+                    // 心流模式：到点后继续正计时，直到用户结束
+                    state_guard.elapsed_duration += 1;
+                    state_guard.overtime_duration += 1;
+
+                    let elapsed = state_guard.elapsed_duration;
+                    let overtime = state_guard.overtime_duration;
+                    let progress = state_guard.progress();
+                    let session_id = state_guard.session_id.clone().unwrap_or_default();
+
+                    drop(state_guard);
+
+                    if overtime == 1 {
+                        let _ = app_handle.emit("focus-overtime-started", serde_json::json!({
+                            "session_id": session_id,
+                            "planned_duration": elapsed - overtime,
+                        }));
+                    }
+
+                    let _ = app_handle.emit("timer-tick", TimerEvent::Tick {
+                        phase: SessionPhase::Focus,
+                        remaining: 0,
+                        elapsed,
+                        progress,
+                    });
                 } else {
                     // 定时器完成 - 处理自动状态转换
                     let phase = state_guard.phase.clone();
                     let session_id = state_guard.session_id.clone().unwrap_or_default();
+                    let overtime = state_guard.overtime_duration;
                     state_guard.complete();
                     
                     let mut cycle_guard = cycle_state.write().await;
-                    
+
+                    // 微休息结束后在释放状态锁之前恢复挂起的专注状态，
+                    // 避免微休息调度器在数据库写入期间看到已完成的微休息状态而退出
+                    let mut resumed_focus = None;
+                    if phase == SessionPhase::MicroBreak {
+                        if let Some(mut focus_state) = focus_tracker.lock().await.suspended_focus.take() {
+                            focus_state.resume_from_suspension();
+                            resumed_focus = Some(focus_state.phase.clone());
+                            *state_guard = focus_state;
+                        }
+                    }
+
                     drop(state_guard);
+
+                    // 微休息之后定时器状态中的会话ID已被替换，专注会话以跟踪器中的ID为准
+                    let persisted_session_id = if phase == SessionPhase::Focus {
+                        focus_tracker.lock().await.session_id.clone().unwrap_or_else(|| session_id.clone())
+                    } else {
+                        session_id.clone()
+                    };
+
+                    // 先在持有循环状态锁时完成内存中的状态转换，数据库读写在释放锁之后进行，
+                    // 避免暂停、跳过、查询状态等命令等待SQLite
+                    let mut focus_quality = None;
+                    let mut long_break_for = None;
+                    match phase {
+                        SessionPhase::Focus => {
+                            // 专注会话完成，增加计数，等待用户开始或跳过长休息
+                            *completed_sessions.lock().await += 1;
+
+                            // 计算专注质量的输入在新会话开始前取出
+                            let mut tracker = focus_tracker.lock().await;
+                            let planned_seconds = tracker.planned_seconds;
+                            focus_quality = Self::take_focus_quality_input(&mut tracker, planned_seconds, true);
+                            drop(tracker);
+
+                            long_break_tracker.lock().await.pending_focus =
+                                Some((persisted_session_id.clone(), chrono::Utc::now()));
+                            
+                            *cycle_guard = CycleState::WaitingToStart; // 等待用户决定是否开始长休息
+                        },
+                        SessionPhase::LongBreak => {
+                            // 长休息完成，回到等待状态
+                            *cycle_guard = CycleState::WaitingToStart;
+                            long_break_for = long_break_tracker.lock().await.active_for_focus.take();
+                        },
+                        SessionPhase::OpenFocus => {
+                            // 正计时会话不会自动完成，只能由用户结束
+                        },
+                        SessionPhase::SequenceStep => {
+                            // 序列步骤完成，由计划进入下一步骤
+                            *cycle_guard = CycleState::WaitingToStart;
+                        },
+                        SessionPhase::MicroBreak => {
+                            // 微休息完成，自动返回专注会话
+                            *cycle_guard = CycleState::InFocusSession;
+
+                            let mut tracker = focus_tracker.lock().await;
+                            let focus_elapsed = tracker.focus_elapsed_at_break;
+                            tracker.micro_breaks.on_break_completed(focus_elapsed);
+                        }
+                    }
                     
-                    // 发送事件到前端更新数据库中的会话完成状态
+                    drop(cycle_guard);
+                    
+                    // 通知前端会话已完成（完成状态由下面的会话存储写入）
                     let update_data = serde_json::json!({
                         "session_id": session_id.clone(),
                         "completed": true,
@@ -391,61 +1390,106 @@ impl TimerManager {
                         "updated_at": chrono::Utc::now().to_rfc3339()
                     });
                     let _ = app_handle.emit("update-session-completion", update_data);
+
+                    if let Some(store) = session_store.as_ref() {
+                        if let Err(e) = store.complete_session(&persisted_session_id).await {
+                            eprintln!("Failed to persist completion of session {}: {}", persisted_session_id, e);
+                        }
+                    }
                     
-                    // 根据当前阶段处理状态转换
+                    // 保存结果并发送事件
                     match phase {
                         SessionPhase::Focus => {
-                            // 专注会话完成，增加计数并切换到长休息
-                            let mut sessions = completed_sessions.lock().await;
-                            *sessions += 1;
-                            drop(sessions);
-                            
-                            *cycle_guard = CycleState::WaitingToStart; // 等待用户决定是否开始长休息
+                            // 保存心流模式加时
+                            if let (Some(store), true) = (session_store.as_ref(), overtime > 0) {
+                                if let Err(e) = store.save_overtime(&persisted_session_id, overtime).await {
+                                    eprintln!("Failed to persist overtime of session {}: {}", persisted_session_id, e);
+                                }
+                            }
+
+                            // 计算专注质量并发送效率反馈
+                            if let Some((quality_session_id, input)) = focus_quality {
+                                manager.report_focus_quality(quality_session_id, &input).await;
+                            }
+
+                            // 更新今日目标进度与连续达成天数
+                            Self::update_goal_progress(&app_handle, &goal_store, &streak_store).await;
                             
                             // 发送完成事件和阶段变更事件
-                            let _ = app_handle.emit("timer-completed", TimerEvent::Completed {
+                            manager.emit_event(TimerEvent::Completed {
                                 phase: SessionPhase::Focus,
                                 session_id: session_id.clone(),
-                            });
+                            }).await;
+                            manager.play_cue(AudioType::FocusEnd);
+                            if let Some(ambient) = manager.ambient.as_ref() {
+                                ambient.fade_out(AMBIENT_STOP_FADE);
+                            }
                             
                             let _ = app_handle.emit("focus-session-completed", serde_json::json!({
                                 "session_id": session_id,
                                 "completed_sessions": *completed_sessions.lock().await,
+                                "overtime_seconds": overtime,
                                 "next_phase": "long_break"
                             }));
                         },
                         SessionPhase::LongBreak => {
-                            // 长休息完成，回到等待状态
-                            *cycle_guard = CycleState::WaitingToStart;
+                            if let (Some(store), Some(focus_id)) = (session_store.as_ref(), long_break_for) {
+                                if let Err(e) = store.resolve_long_break(&focus_id, LongBreakStatus::Taken).await {
+                                    eprintln!("Failed to persist long break of session {}: {}", focus_id, e);
+                                }
+                            }
                             
-                            let _ = app_handle.emit("timer-completed", TimerEvent::Completed {
+                            manager.emit_event(TimerEvent::Completed {
                                 phase: SessionPhase::LongBreak,
                                 session_id: session_id.clone(),
-                            });
+                            }).await;
+                            manager.play_cue(AudioType::LongBreakEnd);
                             
                             let _ = app_handle.emit("long-break-completed", serde_json::json!({
                                 "session_id": session_id,
                                 "next_phase": "focus"
                             }));
                         },
+                        SessionPhase::OpenFocus => {},
+                        SessionPhase::SequenceStep => {
+                            manager.emit_event(TimerEvent::Completed {
+                                phase: SessionPhase::SequenceStep,
+                                session_id: session_id.clone(),
+                            }).await;
+                        },
                         SessionPhase::MicroBreak => {
-                            // 微休息完成，自动返回专注会话
-                            *cycle_guard = CycleState::InFocusSession;
+                            Self::persist_focus_metrics(&focus_tracker, &session_store).await;
                             
-                            let _ = app_handle.emit("timer-completed", TimerEvent::Completed {
+                            manager.emit_event(TimerEvent::Completed {
                                 phase: SessionPhase::MicroBreak,
                                 session_id: session_id.clone(),
-                            });
+                            }).await;
+                            manager.play_cue(AudioType::MicroBreakEnd);
                             
-                            // 自动恢复专注会话的逻辑需要在这里实现
                             let _ = app_handle.emit("micro-break-completed", serde_json::json!({
                                 "session_id": session_id,
                                 "returning_to_focus": true
                             }));
                         }
                     }
-                    
-                    drop(cycle_guard);
+
+                    // 已恢复挂起的专注状态时，本任务继续为专注计时
+                    if let Some(focus_phase) = resumed_focus {
+                        if let Some(ambient) = manager.ambient.as_ref() {
+                            ambient.restore();
+                        }
+
+                        manager.emit_event(TimerEvent::PhaseChanged {
+                            from: SessionPhase::MicroBreak,
+                            to: focus_phase,
+                        }).await;
+                        continue;
+                    }
+
+                    // 多周期计划自动进入下一阶段
+                    if matches!(phase, SessionPhase::Focus | SessionPhase::LongBreak | SessionPhase::SequenceStep) {
+                        Self::advance_plan_later(manager, phase);
+                    }
                     break;
                 }
             }
@@ -472,6 +1516,9 @@ impl TimerManager {
         let cycle_state = Arc::clone(&self.cycle_state);
         let settings = Arc::clone(&self.settings);
         let app_handle = self.app_handle.clone();
+        let focus_tracker = Arc::clone(&self.focus_tracker);
+        let session_store = self.session_store.clone();
+        let manager = self.clone();
 
         let handle = tokio::spawn(async move {
             let mut interval = interval(Duration::from_secs(1));
@@ -481,6 +1528,11 @@ impl TimerManager {
                 
                 let state_guard = state.read().await;
                 let cycle_guard = cycle_state.read().await;
+
+                // 微休息期间专注状态被挂起，等待恢复后继续调度
+                if *cycle_guard == CycleState::InMicroBreak {
+                    continue;
+                }
                 
                 // 只在专注阶段运行
                 if *cycle_guard != CycleState::InFocusSession || !state_guard.is_running() {
@@ -498,16 +1550,26 @@ impl TimerManager {
                         drop(state_guard);
                         drop(cycle_guard);
                         
-                        // 获取微休息设置
+                        // 获取微休息设置，并按计划安排下一次微休息（加时阶段同样继续）
                         let settings_guard = settings.read().await;
                         let duration = settings_guard.micro_break_duration_seconds as u64;
+                        let next_interval = Self::random_micro_break_interval(&settings_guard);
                         drop(settings_guard);
+                        state.write().await.next_micro_break_at = Some(elapsed + next_interval);
+
+                        // 记录微休息触发
+                        let mut tracker = focus_tracker.lock().await;
+                        tracker.micro_breaks.on_break_triggered(elapsed);
+                        tracker.focus_elapsed_at_break = elapsed;
+                        drop(tracker);
+                        Self::persist_focus_metrics(&focus_tracker, &session_store).await;
 
                         // 触发微休息事件，前端可以选择是否开始微休息
-                        let _ = app_handle.emit("micro-break-triggered", TimerEvent::MicroBreakTriggered {
+                        manager.emit_event(TimerEvent::MicroBreakTriggered {
                             count: micro_break_count + 1,
                             duration,
-                        });
+                        }).await;
+                        manager.play_cue(AudioType::MicroBreakStart);
                         
                         // 发送微休息准备事件，包含更多上下文信息
                         let _ = app_handle.emit("micro-break-ready", serde_json::json!({
@@ -516,8 +1578,8 @@ impl TimerManager {
                             "focus_elapsed": elapsed,
                             "auto_start": true // 可以配置是否自动开始
                         }));
-                        
-                        break;
+
+                        continue;
                     }
                 }
                 
@@ -540,27 +1602,94 @@ impl TimerManager {
 
     /// 计算下次微休息时间
     async fn schedule_next_micro_break(&self, state: &mut TimerState, settings: &UserSettings) {
+        state.next_micro_break_at = Some(state.elapsed_duration + Self::random_micro_break_interval(settings));
+    }
+
+    /// 在设置的最小和最大间隔之间随机选取微休息间隔（秒）
+    fn random_micro_break_interval(settings: &UserSettings) -> u64 {
         let min_interval = settings.micro_break_min_interval_minutes as u64 * 60;
         let max_interval = settings.micro_break_max_interval_minutes as u64 * 60;
         
         let mut rng = rand::thread_rng();
-        let random_interval = rng.gen_range(min_interval..=max_interval);
-        
-        state.next_micro_break_at = Some(state.elapsed_duration + random_interval);
+        rng.gen_range(min_interval..=max_interval.max(min_interval))
+    }
+
+    /// 专注会话结束时计算专注质量评分，发送效率反馈事件并保存
+    async fn finalize_focus_quality(&self, elapsed_seconds: u64, completed: bool) {
+        let pending = Self::take_focus_quality_input(&mut *self.focus_tracker.lock().await, elapsed_seconds, completed);
+        if let Some((session_id, input)) = pending {
+            self.report_focus_quality(session_id, &input).await;
+        }
+    }
+
+    /// 取出专注质量评分的输入（每个专注会话只评分一次，已评分时返回None）
+    fn take_focus_quality_input(
+        tracker: &mut FocusTracker,
+        elapsed_seconds: u64,
+        completed: bool,
+    ) -> Option<(String, FocusQualityInput)> {
+        let session_id = match tracker.session_id.clone() {
+            Some(session_id) if !tracker.quality_recorded => session_id,
+            _ => return None,
+        };
+        tracker.quality_recorded = true;
+
+        Some((session_id, FocusQualityInput {
+            elapsed_seconds,
+            planned_seconds: tracker.planned_seconds,
+            completed,
+            pause_count: tracker.pause_count,
+            micro_breaks: tracker.micro_breaks.get_stats_summary(),
+        }))
+    }
+
+    /// 计算专注质量评分，发送效率反馈事件并保存
+    async fn report_focus_quality(&self, session_id: String, input: &FocusQualityInput) {
+        let quality = compute_focus_quality(input);
+        let event = TimerEvent::EfficiencyFeedback {
+            session_id: session_id.clone(),
+            focus_quality_score: quality.score,
+            micro_break_effectiveness: quality.micro_break_effectiveness,
+        };
+        self.emit_event(event).await;
+
+        if let Some(store) = self.session_store.as_ref() {
+            if let Err(e) = store.save_focus_quality(&session_id, quality.score, quality.micro_break_effectiveness).await {
+                eprintln!("Failed to persist focus quality of session {}: {}", session_id, e);
+            }
+        }
+    }
+
+    /// 持久化当前专注会话的行为数据
+    async fn persist_focus_metrics(focus_tracker: &Arc<Mutex<FocusTracker>>, session_store: &Option<SessionStore>) {
+        let tracker = focus_tracker.lock().await;
+        let session_id = tracker.session_id.clone();
+        let metrics = tracker.metrics();
+        drop(tracker);
+
+        if let (Some(store), Some(session_id)) = (session_store.as_ref(), session_id) {
+            if let Err(e) = store.update_focus_metrics(&session_id, metrics).await {
+                eprintln!("Failed to persist focus metrics of session {}: {}", session_id, e);
+            }
+        }
     }
 
     /// 保存会话到数据库
-    async fn save_session_to_db(&self, session_id: &str, session_type: SessionType, duration_seconds: u32) -> Result<(), TimerError> {
-        // 在Tauri 2.0中，数据库操作应该通过前端JavaScript进行
-        // 这里我们发送事件到前端，让前端处理数据库保存
+    async fn save_session_to_db(&self, session: &NewSession<'_>) -> Result<(), TimerError> {
+        // 已连接数据库时由后端直接写入
+        Self::persist_new_session(
+            self.session_store.as_ref(),
+            self.task_store.as_ref(),
+            self.label_store.as_ref(),
+            session,
+        ).await?;
+
+        // 通知前端会话已创建（会话记录只由后端写入，前端不再插入）
         let session_data = serde_json::json!({
-            "id": session_id,
-            "session_type": match session_type {
-                SessionType::Focus => "Focus",
-                SessionType::LongBreak => "LongBreak", 
-                SessionType::MicroBreak => "MicroBreak",
-            },
-            "duration_seconds": duration_seconds,
+            "id": session.id,
+            "session_type": session.session_type.as_str(),
+            "duration_seconds": session.duration_seconds,
+            "task_id": session.task_id,
             "start_time": chrono::Utc::now().to_rfc3339(),
             "created_at": chrono::Utc::now().to_rfc3339(),
             "updated_at": chrono::Utc::now().to_rfc3339()
@@ -570,9 +1699,40 @@ impl TimerManager {
         Ok(())
     }
 
-    /// 更新会话完成状态
+    /// 写入新会话及其项目标签，并将关联任务标记为进行中；后续写入失败时删除已写入的会话
+    async fn persist_new_session(
+        session_store: Option<&SessionStore>,
+        task_store: Option<&TaskStore>,
+        label_store: Option<&LabelStore>,
+        session: &NewSession<'_>,
+    ) -> Result<(), TimerError> {
+        let Some(store) = session_store else {
+            return Ok(());
+        };
+        store.insert_session(session.id, &session.session_type, session.duration_seconds, session.task_id).await?;
+
+        let linked = async {
+            if let (true, Some(label_store)) = (session.project_id.is_some() || !session.tags.is_empty(), label_store) {
+                label_store.set_session_labels(session.id, session.project_id, session.tags).await?;
+            }
+            if let (Some(task_id), Some(task_store)) = (session.task_id, task_store) {
+                task_store.mark_in_progress(task_id).await?;
+            }
+            Ok::<(), StorageError>(())
+        }.await;
+
+        if let Err(e) = linked {
+            if let Err(cleanup) = store.delete_session(session.id).await {
+                eprintln!("Failed to remove session {} after failed start: {}", session.id, cleanup);
+            }
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// 通知前端会话完成状态
     async fn update_session_completion(&self, session_id: &str, completed: bool) -> Result<(), TimerError> {
-        // 发送事件到前端处理数据库更新
+        // 仅通知前端，数据库由会话存储更新
         let update_data = serde_json::json!({
             "session_id": session_id,
             "completed": completed,
@@ -584,21 +1744,250 @@ impl TimerManager {
         Ok(())
     }
 
-    /// 获取今日会话统计
-    pub async fn get_today_stats(&self) -> Result<serde_json::Value, TimerError> {
-        // 发送事件到前端请求统计数据
-        let _ = self.app_handle.emit("request-today-stats", serde_json::json!({}));
-        
-        // 这里应该通过某种机制等待前端返回结果
-        // 为了简化，先返回空的统计数据
-        Ok(serde_json::json!({
-            "date": chrono::Utc::now().format("%Y-%m-%d").to_string(),
-            "sessions": []
-        }))
+    /// 获取今日专注统计与目标达成情况
+    pub async fn get_today_stats(&self) -> Result<DailyGoalProgress, TimerError> {
+        let store = self.goal_store.as_ref()
+            .ok_or_else(|| TimerError::Database("数据库未连接".to_string()))?;
+
+        let today = chrono::Local::now().date_naive();
+        let stats = store.range_stats(today, today).await?;
+        stats.days.into_iter().next()
+            .ok_or_else(|| TimerError::InvalidState("无法统计今日数据".to_string()))
+    }
+
+    /// 专注会话完成后重新计算今日目标进度和连续达成天数，并发送相应事件
+    async fn update_goal_progress(
+        app_handle: &AppHandle,
+        goal_store: &Option<GoalStore>,
+        streak_store: &Option<StreakStore>,
+    ) {
+        let today = chrono::Local::now().date_naive();
+
+        if let Some(store) = goal_store.as_ref() {
+            match store.record_day_progress(today).await {
+                Ok((progress, newly_reached)) => {
+                    if progress.target.is_some() {
+                        let _ = app_handle.emit("goal-progress", &progress);
+                        if newly_reached {
+                            let _ = app_handle.emit("goal-reached", &progress);
+                        }
+                    }
+                }
+                Err(e) => eprintln!("Failed to update daily goal progress: {}", e),
+            }
+        }
+
+        if let Some(store) = streak_store.as_ref() {
+            match store.get_streak_stats(today).await {
+                Ok(stats) => {
+                    let _ = app_handle.emit("streak-updated", &stats);
+                }
+                Err(e) => eprintln!("Failed to update goal streak: {}", e),
+            }
+        }
+    }
+
+    /// 预警提示音在阶段结束前多少秒播放（提示音被禁用时为None）
+    async fn warning_lead(&self, cue: AudioType) -> Option<u64> {
+        let config = match self.audio_config_store.as_ref() {
+            Some(store) => store.get_config(cue).await.unwrap_or_else(|e| {
+                eprintln!("Failed to load {} audio config: {}", cue.as_str(), e);
+                None
+            }),
+            None => None,
+        };
+
+        let lead_seconds = match config {
+            Some(config) if !config.enabled => None,
+            Some(config) => config.lead_seconds.or(cue.default_lead_seconds()),
+            None => cue.default_lead_seconds(),
+        };
+        lead_seconds.map(u64::from)
+    }
+
+    /// 播放阶段切换提示音并通知前端
+    ///
+    /// 按audio_configs中对应类型的配置（是否启用、音量、自定义文件）播放；在后台执行，不阻塞状态切换。
+    /// 免打扰时段内按设置静音或只发送界面通知。
+    fn play_cue(&self, cue: AudioType) {
+        let manager = self.clone();
+        let audio_service = self.audio_service.clone();
+        let audio_config_store = self.audio_config_store.clone();
+
+        tokio::spawn(async move {
+            let config = match audio_config_store.as_ref() {
+                Some(store) => store.get_config(cue).await.unwrap_or_else(|e| {
+                    eprintln!("Failed to load {} audio config: {}", cue.as_str(), e);
+                    None
+                }),
+                None => None,
+            };
+            if config.as_ref().is_some_and(|config| !config.enabled) {
+                return;
+            }
+
+            // 免打扰时段内不播放提示音，按设置静音或改为界面通知
+            match audio_service.as_ref().and_then(|audio_service| audio_service.quiet_mode()) {
+                Some(QuietHoursMode::Mute) => return,
+                Some(QuietHoursMode::VisualOnly) => {
+                    manager.emit_event(TimerEvent::VisualCue { event: AudioEvent::from(cue) }).await;
+                    return;
+                }
+                None => {}
+            }
+
+            if let Some(audio_service) = audio_service {
+                // 解码可能读取磁盘，避免阻塞异步运行时
+                let result = tokio::task::spawn_blocking(move || audio_service.play_cue(cue, config.as_ref())).await;
+                match result {
+                    Ok(Err(e)) => eprintln!("Failed to play {} cue: {}", cue.as_str(), e),
+                    Err(e) => eprintln!("Audio playback task failed: {}", e),
+                    Ok(Ok(())) => {}
+                }
+            }
+
+            manager.emit_event(TimerEvent::Audio { event: AudioEvent::from(cue) }).await;
+        });
+    }
+
+    /// 按当前档案的设置开始播放专注背景音（设置未启用时停止）
+    async fn start_ambient(&self) {
+        let Some(ambient) = self.ambient.as_ref() else {
+            return;
+        };
+
+        let profile_id = self.ambient_profile.read().await.clone();
+        let settings = match self.ambient_store.as_ref() {
+            Some(store) => store.get_settings(&profile_id).await.unwrap_or_else(|e| {
+                eprintln!("Failed to load ambient settings of {}: {}", profile_id, e);
+                AmbientSettings::defaults_for(&profile_id)
+            }),
+            None => AmbientSettings::defaults_for(&profile_id),
+        };
+        ambient.start(settings);
     }
 
     /// 发送事件到前端
+    ///
+    /// 设置了事件管理器时交给它去重、重试并写入事件日志；否则直接以具体事件名和timer-event发送。
     async fn emit_event(&self, event: TimerEvent) {
-        let _ = self.app_handle.emit("timer-event", &event);
+        match self.event_manager.as_ref() {
+            Some(event_manager) => {
+                if let Err(e) = event_manager.emit_event(event, None).await {
+                    eprintln!("{}", e);
+                }
+            }
+            None => {
+                let _ = self.app_handle.emit(&EnhancedEventManager::get_event_name(&event), &event);
+                let _ = self.app_handle.emit("timer-event", &event);
+            }
+        }
+    }
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CreateTask;
+    use crate::test_support::migrated_pool;
+
+    async fn create_task(tasks: &TaskStore) -> String {
+        let input = CreateTask { title: "写报告".to_string(), project: None, estimated_cycles: 2 };
+        tasks.create_task(input).await.unwrap().id
+    }
+
+    async fn session_count(pool: &SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM focus_sessions").fetch_one(pool).await.unwrap()
+    }
+
+    #[test]
+    fn skip_warning_fires_from_threshold() {
+        assert!(!skip_warning_due(2, 3));
+        assert!(skip_warning_due(3, 3));
+        assert!(skip_warning_due(4, 3));
+        assert!(!skip_warning_due(5, 0));
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn warning_fires_once_when_remaining_reaches_lead() {
+        let mut state = TimerState::new_focus_session(25);
+        state.remaining_duration = 61;
+        assert!(!state.take_warning(60));
+
+        // 被缩短后跳过了预警时刻，仍在下一次检查时提示
+        state.remaining_duration = 30;
+        assert!(state.take_warning(60));
+        state.remaining_duration = 29;
+        assert!(!state.take_warning(60));
+    }
+
+    #[tokio::test]
+    async fn new_session_is_written_with_task_and_labels() {
+        let (_dir, pool) = migrated_pool().await;
+        let sessions = SessionStore::new(pool.clone());
+        let tasks = TaskStore::new(pool.clone());
+        let labels = LabelStore::new(pool.clone());
+        let task_id = create_task(&tasks).await;
+        let tags = vec!["写作".to_string()];
+
+        TimerManager::persist_new_session(Some(&sessions), Some(&tasks), Some(&labels), &NewSession {
+            id: "s1",
+            session_type: SessionType::Focus,
+            duration_seconds: 1500,
+            task_id: Some(&task_id),
+            project_id: None,
+            tags: &tags,
+        }).await.unwrap();
+
+        assert_eq!(session_count(&pool).await, 1);
+        assert_eq!(tasks.get_task(&task_id).await.unwrap().status, TaskStatus::InProgress);
+        assert_eq!(labels.get_session_labels("s1").await.unwrap().tags.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_label_write_removes_the_new_session() {
+        let (_dir, pool) = migrated_pool().await;
+        let sessions = SessionStore::new(pool.clone());
+        let tasks = TaskStore::new(pool.clone());
+        let labels = LabelStore::new(pool.clone());
+        let task_id = create_task(&tasks).await;
+        // 让标签写入失败
+        sqlx::query("DROP TABLE tags").execute(&pool).await.unwrap();
+        let tags = vec!["写作".to_string()];
+
+        let result = TimerManager::persist_new_session(Some(&sessions), Some(&tasks), Some(&labels), &NewSession {
+            id: "s1",
+            session_type: SessionType::Focus,
+            duration_seconds: 1500,
+            task_id: Some(&task_id),
+            project_id: None,
+            tags: &tags,
+        }).await;
+
+        assert!(matches!(result, Err(TimerError::Database(_))));
+        assert_eq!(session_count(&pool).await, 0);
+        assert_eq!(tasks.get_task(&task_id).await.unwrap().status, TaskStatus::Todo);
+    }
+
+    #[tokio::test]
+    async fn failed_session_insert_writes_nothing() {
+        let (_dir, pool) = migrated_pool().await;
+        let sessions = SessionStore::new(pool.clone());
+        let tasks = TaskStore::new(pool.clone());
+        let task_id = create_task(&tasks).await;
+        // 已存在的会话ID导致插入失败
+        sessions.insert_session("s1", &SessionType::Focus, 1500, None).await.unwrap();
+
+        let result = TimerManager::persist_new_session(Some(&sessions), Some(&tasks), None, &NewSession {
+            id: "s1",
+            session_type: SessionType::Focus,
+            duration_seconds: 1500,
+            task_id: Some(&task_id),
+            project_id: None,
+            tags: &[],
+        }).await;
+
+        assert!(result.is_err());
+        assert_eq!(session_count(&pool).await, 1);
+        assert_eq!(tasks.get_task(&task_id).await.unwrap().status, TaskStatus::Todo);
+    }
+}
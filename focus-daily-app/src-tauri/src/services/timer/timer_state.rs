@@ -1,6 +1,9 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::models::AudioType;
+use crate::services::events::AudioEvent;
+
 /// 定时器状态
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TimerStatus {
@@ -11,11 +14,20 @@ pub enum TimerStatus {
 }
 
 /// 会话阶段
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum SessionPhase {
     Focus,          // 专注阶段
     LongBreak,      // 长休息阶段
     MicroBreak,     // 微休息阶段
+    OpenFocus,      // 正计时专注阶段（无固定时长）
+    SequenceStep,   // 阶段序列中的热身、复盘或休息步骤
+}
+
+impl SessionPhase {
+    /// 是否为专注阶段（倒计时或正计时）
+    pub fn is_focus(&self) -> bool {
+        matches!(self, SessionPhase::Focus | SessionPhase::OpenFocus)
+    }
 }
 
 /// 定时器状态数据
@@ -37,10 +49,24 @@ pub struct TimerState {
     pub pause_time: Option<DateTime<Utc>>,
     /// 会话ID
     pub session_id: Option<String>,
+    /// 关联任务ID
+    pub task_id: Option<String>,
     /// 微休息计数器
     pub micro_break_count: u32,
     /// 下次微休息时间（秒）
     pub next_micro_break_at: Option<u64>,
+    /// 心流模式：专注到点后继续正计时，直到用户结束
+    #[serde(default)]
+    pub flow_mode: bool,
+    /// 超出计划时长的加时（秒）
+    #[serde(default)]
+    pub overtime_duration: u64,
+    /// 计划或阶段序列中当前步骤的名称
+    #[serde(default)]
+    pub step_name: Option<String>,
+    /// 本阶段是否已播放过即将结束的预警提示音
+    #[serde(default)]
+    pub warning_played: bool,
 }
 
 impl Default for TimerState {
@@ -54,8 +80,13 @@ impl Default for TimerState {
             start_time: None,
             pause_time: None,
             session_id: None,
+            task_id: None,
             micro_break_count: 0,
             next_micro_break_at: None,
+            flow_mode: false,
+            overtime_duration: 0,
+            step_name: None,
+            warning_played: false,
         }
     }
 }
@@ -73,8 +104,13 @@ impl TimerState {
             start_time: None,
             pause_time: None,
             session_id: None,
+            task_id: None,
             micro_break_count: 0,
             next_micro_break_at: None,
+            flow_mode: false,
+            overtime_duration: 0,
+            step_name: None,
+            warning_played: false,
         }
     }
 
@@ -90,8 +126,33 @@ impl TimerState {
             start_time: None,
             pause_time: None,
             session_id: None,
+            task_id: None,
             micro_break_count: 0,
             next_micro_break_at: None,
+            flow_mode: false,
+            overtime_duration: 0,
+            step_name: None,
+            warning_played: false,
+        }
+    }
+
+    /// 剩余时间不超过预警时间且本阶段尚未预警时记为已预警并返回true
+    pub fn take_warning(&mut self, lead_seconds: u64) -> bool {
+        if self.warning_played || self.remaining_duration > lead_seconds {
+            return false;
+        }
+        self.warning_played = true;
+        true
+    }
+
+    /// 创建阶段序列中热身、复盘或休息步骤的状态
+    pub fn new_sequence_step_session(duration_minutes: u32) -> Self {
+        let total_seconds = (duration_minutes * 60) as u64;
+        Self {
+            phase: SessionPhase::SequenceStep,
+            total_duration: total_seconds,
+            remaining_duration: total_seconds,
+            ..Self::default()
         }
     }
 
@@ -107,8 +168,21 @@ impl TimerState {
             start_time: None,
             pause_time: None,
             session_id: None,
+            task_id: None,
             micro_break_count: 0,
             next_micro_break_at: None,
+            flow_mode: false,
+            overtime_duration: 0,
+            step_name: None,
+            warning_played: false,
+        }
+    }
+
+    /// 创建新的正计时专注会话状态（没有总时长，由用户结束）
+    pub fn new_open_focus_session() -> Self {
+        Self {
+            phase: SessionPhase::OpenFocus,
+            ..Self::default()
         }
     }
 
@@ -135,6 +209,25 @@ impl TimerState {
         }
     }
 
+    /// 从挂起（如微休息期间）中恢复运行
+    pub fn resume_from_suspension(&mut self) {
+        self.status = TimerStatus::Running;
+        self.pause_time = None;
+    }
+
+    /// 是否处于心流模式的加时阶段
+    pub fn is_overtime(&self) -> bool {
+        self.flow_mode && self.phase == SessionPhase::Focus && self.remaining_duration == 0
+    }
+
+    /// 调整计划时长（正数延长，负数缩短，最多缩短到剩余为0），返回实际调整的秒数
+    pub fn adjust_duration(&mut self, delta_seconds: i64) -> i64 {
+        let applied = delta_seconds.max(-(self.remaining_duration as i64));
+        self.remaining_duration = (self.remaining_duration as i64 + applied) as u64;
+        self.total_duration = (self.total_duration as i64 + applied) as u64;
+        applied
+    }
+
     /// 完成定时器
     pub fn complete(&mut self) {
         self.status = TimerStatus::Completed;
@@ -157,12 +250,12 @@ impl TimerState {
         self.elapsed_duration = self.total_duration.saturating_sub(remaining);
     }
 
-    /// 获取进度百分比 (0.0 - 1.0)
+    /// 获取进度百分比 (0.0 - 1.0，加时阶段保持为1.0)
     pub fn progress(&self) -> f64 {
         if self.total_duration == 0 {
             return 0.0;
         }
-        self.elapsed_duration as f64 / self.total_duration as f64
+        (self.elapsed_duration as f64 / self.total_duration as f64).min(1.0)
     }
 
     /// 检查是否已完成
@@ -254,6 +347,37 @@ pub enum TimerEvent {
         skipped: u32,
         completion_rate: f32,
     },
+    /// 当前会话计划时长调整
+    DurationAdjusted {
+        phase: SessionPhase,
+        session_id: String,
+        delta_seconds: i64,
+        total: u64,
+        remaining: u64,
+        next_micro_break_at: Option<u64>,
+    },
+    /// 计划进度更新（进入下一步骤时触发）
+    PlanProgress {
+        phase: SessionPhase,
+        step: usize,
+        step_name: String,
+        audio_cue: Option<AudioType>,
+        completed: u32,
+        target: u32,
+    },
+    /// 多周期计划完成
+    PlanCompleted {
+        completed: u32,
+        target: u32,
+    },
+    /// 音频事件（由音频服务处理后通知前端）
+    Audio {
+        event: AudioEvent,
+    },
+    /// 免打扰时段内代替提示音的界面通知
+    VisualCue {
+        event: AudioEvent,
+    },
     /// 阶段切换
     PhaseChanged {
         from: SessionPhase,
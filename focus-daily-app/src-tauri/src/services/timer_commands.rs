@@ -1,14 +1,19 @@
 use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqlitePool;
 use tauri::{AppHandle, State};
 use tokio::sync::RwLock;
 
-use crate::models::UserSettings;
-use crate::services::timer::{TimerManager, TimerState};
+use crate::database::connect_database;
+use crate::models::{CyclePlan, CyclePlanProgress, DailyGoalProgress, UserSettings};
+use crate::services::timer::{FocusSessionOptions, TimerManager, TimerState};
 use crate::services::timer::timer_manager::CycleState;
+use crate::services::audio::{AudioBackendKind, AudioService};
+use crate::services::storage::{QuietHoursStore, SettingsStore};
 use crate::services::events::{
     EnhancedEventManager, EventManagerConfig, EventStats, EventPriority,
     PerformanceMonitor, PerformanceReport, SystemHealth, PerformanceAlert,
-    AudioEvent, AudioConfig,
+    AudioEvent, AudioConfig, DeadLetter, EventCategory, EventHistoryPage, EventHistoryQuery,
 };
 
 /// 全局定时器管理器状态
@@ -20,6 +25,12 @@ pub type EventManagerState = Arc<RwLock<Option<EnhancedEventManager>>>;
 /// 全局性能监控器状态
 pub type PerformanceMonitorState = Arc<RwLock<Option<PerformanceMonitor>>>;
 
+/// 全局数据库连接池状态
+pub type DatabaseState = Arc<RwLock<Option<SqlitePool>>>;
+
+/// 全局音频服务状态
+pub type AudioServiceState = Arc<RwLock<Option<Arc<AudioService>>>>;
+
 /// 初始化定时器管理器
 #[tauri::command]
 pub async fn init_timer_manager(
@@ -27,15 +38,44 @@ pub async fn init_timer_manager(
     timer_manager: State<'_, TimerManagerState>,
     event_manager: State<'_, EventManagerState>,
     performance_monitor: State<'_, PerformanceMonitorState>,
+    database: State<'_, DatabaseState>,
+    audio: State<'_, AudioServiceState>,
 ) -> Result<(), String> {
-    // 获取默认用户设置
-    let default_settings = UserSettings::default();
-    
-    // 创建定时器管理器
-    let manager = TimerManager::new(app_handle.clone(), default_settings);
-    
+    // 连接数据库（失败时定时器照常运行，但不保存会话记录）
+    let pool = match connect_database(&app_handle).await {
+        Ok(pool) => Some(pool),
+        Err(e) => {
+            eprintln!("{}", e);
+            None
+        }
+    };
+
+    // 读取保存的用户设置（未连接数据库或读取失败时使用默认设置）
+    let settings = match pool.as_ref() {
+        Some(pool) => SettingsStore::new(pool.clone()).get_settings().await.unwrap_or_else(|e| {
+            eprintln!("Failed to load user settings: {}", e);
+            UserSettings::default()
+        }),
+        None => UserSettings::default(),
+    };
+    
+    // 创建音频服务
+    let audio_service = Arc::new(AudioService::new(AudioBackendKind::from_env().create()));
+    if let Some(pool) = pool.as_ref() {
+        match QuietHoursStore::new(pool.clone()).get_quiet_hours().await {
+            Ok(quiet_hours) => audio_service.set_quiet_hours(quiet_hours),
+            Err(e) => eprintln!("Failed to load quiet hours: {}", e),
+        }
+    }
+
     // 创建增强事件管理器
-    let enhanced_event_manager = EnhancedEventManager::new(app_handle.clone(), None);
+    let enhanced_event_manager = EnhancedEventManager::new(app_handle.clone(), None, pool.clone())
+        .with_audio_handler(audio_service.clone());
+
+    // 创建定时器管理器（定时器事件经由增强事件管理器发送）
+    let manager = TimerManager::new(app_handle.clone(), settings, pool.clone())
+        .with_audio_service(audio_service.clone())
+        .with_event_manager(enhanced_event_manager.clone());
     
     // 创建性能监控器
     let perf_monitor = PerformanceMonitor::new(None);
@@ -49,6 +89,12 @@ pub async fn init_timer_manager(
     
     let mut perf_guard = performance_monitor.write().await;
     *perf_guard = Some(perf_monitor);
+
+    let mut database_guard = database.write().await;
+    *database_guard = pool;
+
+    let mut audio_guard = audio.write().await;
+    *audio_guard = Some(audio_service);
     
     Ok(())
 }
@@ -86,11 +132,21 @@ pub async fn get_cycle_state(
 #[tauri::command]
 pub async fn start_focus_session(
     timer_manager: State<'_, TimerManagerState>,
+    task_id: Option<String>,
+    project_id: Option<String>,
+    tags: Option<Vec<String>>,
+    flow_mode: Option<bool>,
 ) -> Result<String, String> {
     let manager_guard = timer_manager.read().await;
     
     if let Some(manager) = manager_guard.as_ref() {
-        manager.start_focus_session().await
+        let options = FocusSessionOptions {
+            task_id,
+            project_id,
+            tags: tags.unwrap_or_default(),
+            flow_mode,
+        };
+        manager.start_focus_session(options).await
             .map_err(|e| e.to_string())
     } else {
         Err("定时器管理器未初始化".to_string())
@@ -127,15 +183,16 @@ pub async fn start_micro_break_session(
     }
 }
 
-/// 暂停定时器
+/// 暂停定时器（专注阶段可附带中断原因）
 #[tauri::command]
 pub async fn pause_timer(
     timer_manager: State<'_, TimerManagerState>,
+    reason: Option<String>,
 ) -> Result<(), String> {
     let manager_guard = timer_manager.read().await;
     
     if let Some(manager) = manager_guard.as_ref() {
-        manager.pause_timer().await
+        manager.pause_timer(reason).await
             .map_err(|e| e.to_string())
     } else {
         Err("定时器管理器未初始化".to_string())
@@ -187,11 +244,168 @@ pub async fn skip_micro_break(
     }
 }
 
+/// 开始正计时专注会话（可选关联任务、项目和标签）
+#[tauri::command]
+pub async fn start_open_focus_session(
+    timer_manager: State<'_, TimerManagerState>,
+    task_id: Option<String>,
+    project_id: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<String, String> {
+    let manager_guard = timer_manager.read().await;
+    
+    if let Some(manager) = manager_guard.as_ref() {
+        let options = FocusSessionOptions {
+            task_id,
+            project_id,
+            tags: tags.unwrap_or_default(),
+            flow_mode: None,
+        };
+        manager.start_open_focus_session(options).await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("定时器管理器未初始化".to_string())
+    }
+}
+
+/// 结束正计时专注会话
+#[tauri::command]
+pub async fn stop_open_focus_session(
+    timer_manager: State<'_, TimerManagerState>,
+) -> Result<String, String> {
+    let manager_guard = timer_manager.read().await;
+    
+    if let Some(manager) = manager_guard.as_ref() {
+        manager.stop_open_focus_session().await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("定时器管理器未初始化".to_string())
+    }
+}
+
+/// 开始多周期计划（例如3个90/20周期加30分钟最终休息）
+#[tauri::command]
+pub async fn start_cycle_plan(
+    timer_manager: State<'_, TimerManagerState>,
+    cycles: u32,
+    final_break_minutes: Option<u32>,
+    task_id: Option<String>,
+    project_id: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<String, String> {
+    let manager_guard = timer_manager.read().await;
+    
+    if let Some(manager) = manager_guard.as_ref() {
+        let plan = CyclePlan { cycles, final_break_minutes };
+        let options = FocusSessionOptions {
+            task_id,
+            project_id,
+            tags: tags.unwrap_or_default(),
+            flow_mode: None,
+        };
+        manager.start_cycle_plan(plan, options).await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("定时器管理器未初始化".to_string())
+    }
+}
+
+/// 取消进行中的多周期计划
+#[tauri::command]
+pub async fn cancel_cycle_plan(
+    timer_manager: State<'_, TimerManagerState>,
+) -> Result<(), String> {
+    let manager_guard = timer_manager.read().await;
+    
+    if let Some(manager) = manager_guard.as_ref() {
+        manager.cancel_cycle_plan().await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("定时器管理器未初始化".to_string())
+    }
+}
+
+/// 获取进行中的多周期计划进度
+#[tauri::command]
+pub async fn get_cycle_plan(
+    timer_manager: State<'_, TimerManagerState>,
+) -> Result<Option<CyclePlanProgress>, String> {
+    let manager_guard = timer_manager.read().await;
+    
+    if let Some(manager) = manager_guard.as_ref() {
+        Ok(manager.get_cycle_plan().await)
+    } else {
+        Err("定时器管理器未初始化".to_string())
+    }
+}
+
+/// 延长当前会话（秒）
+#[tauri::command]
+pub async fn extend_current_session(
+    timer_manager: State<'_, TimerManagerState>,
+    seconds: u64,
+) -> Result<TimerState, String> {
+    let manager_guard = timer_manager.read().await;
+    
+    if let Some(manager) = manager_guard.as_ref() {
+        manager.extend_current_session(seconds).await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("定时器管理器未初始化".to_string())
+    }
+}
+
+/// 缩短当前会话（秒）
+#[tauri::command]
+pub async fn shorten_current_session(
+    timer_manager: State<'_, TimerManagerState>,
+    seconds: u64,
+) -> Result<TimerState, String> {
+    let manager_guard = timer_manager.read().await;
+    
+    if let Some(manager) = manager_guard.as_ref() {
+        manager.shorten_current_session(seconds).await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("定时器管理器未初始化".to_string())
+    }
+}
+
+/// 结束心流模式下正在加时的专注会话
+#[tauri::command]
+pub async fn finish_focus_session(
+    timer_manager: State<'_, TimerManagerState>,
+) -> Result<(), String> {
+    let manager_guard = timer_manager.read().await;
+    
+    if let Some(manager) = manager_guard.as_ref() {
+        manager.finish_focus_session().await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("定时器管理器未初始化".to_string())
+    }
+}
+
+/// 跳过长休息
+#[tauri::command]
+pub async fn skip_long_break(
+    timer_manager: State<'_, TimerManagerState>,
+) -> Result<(), String> {
+    let manager_guard = timer_manager.read().await;
+    
+    if let Some(manager) = manager_guard.as_ref() {
+        manager.skip_long_break().await
+            .map_err(|e| e.to_string())
+    } else {
+        Err("定时器管理器未初始化".to_string())
+    }
+}
+
 /// 获取今日统计
 #[tauri::command]
 pub async fn get_today_stats(
     timer_manager: State<'_, TimerManagerState>,
-) -> Result<serde_json::Value, String> {
+) -> Result<DailyGoalProgress, String> {
     let manager_guard = timer_manager.read().await;
     
     if let Some(manager) = manager_guard.as_ref() {
@@ -202,15 +416,21 @@ pub async fn get_today_stats(
     }
 }
 
-/// 更新用户设置
+/// 更新用户设置（已连接数据库时同时保存，重启后仍然生效）
 #[tauri::command]
 pub async fn update_timer_settings(
     timer_manager: State<'_, TimerManagerState>,
+    database: State<'_, DatabaseState>,
     settings: UserSettings,
 ) -> Result<(), String> {
     let manager_guard = timer_manager.read().await;
     
     if let Some(manager) = manager_guard.as_ref() {
+        let settings = match database.read().await.as_ref() {
+            Some(pool) => SettingsStore::new(pool.clone()).save_settings(&settings).await
+                .map_err(|e| e.to_string())?,
+            None => settings,
+        };
         manager.update_settings(settings).await;
         Ok(())
     } else {
@@ -234,17 +454,21 @@ pub async fn get_event_stats(
     }
 }
 
-/// 获取事件历史记录
+/// 分页获取事件历史记录（可按时间范围[start, end)和类别筛选）
 #[tauri::command]
 pub async fn get_event_history(
     event_manager: State<'_, EventManagerState>,
     limit: Option<usize>,
-) -> Result<serde_json::Value, String> {
+    offset: Option<usize>,
+    start: Option<DateTime<Utc>>,
+    end: Option<DateTime<Utc>>,
+    category: Option<EventCategory>,
+) -> Result<EventHistoryPage, String> {
     let manager_guard = event_manager.read().await;
     
     if let Some(manager) = manager_guard.as_ref() {
-        let history = manager.get_event_history(limit).await;
-        Ok(serde_json::to_value(history).unwrap())
+        let query = EventHistoryQuery { start, end, category, limit, offset };
+        manager.get_event_history(query).await
     } else {
         Err("事件管理器未初始化".to_string())
     }
@@ -264,6 +488,35 @@ pub async fn get_event_queue_status(
     }
 }
 
+/// 获取死信事件（重试用尽、处理失败、过期或因队列已满被丢弃的事件）
+#[tauri::command]
+pub async fn get_dead_letter_events(
+    event_manager: State<'_, EventManagerState>,
+    limit: Option<usize>,
+) -> Result<Vec<DeadLetter>, String> {
+    let manager_guard = event_manager.read().await;
+    
+    if let Some(manager) = manager_guard.as_ref() {
+        Ok(manager.get_dead_letters(limit).await)
+    } else {
+        Err("事件管理器未初始化".to_string())
+    }
+}
+
+/// 清空死信事件
+#[tauri::command]
+pub async fn clear_dead_letter_events(
+    event_manager: State<'_, EventManagerState>,
+) -> Result<usize, String> {
+    let manager_guard = event_manager.read().await;
+    
+    if let Some(manager) = manager_guard.as_ref() {
+        Ok(manager.clear_dead_letters().await)
+    } else {
+        Err("事件管理器未初始化".to_string())
+    }
+}
+
 /// 清理过期事件
 #[tauri::command]
 pub async fn cleanup_expired_events(
@@ -0,0 +1,190 @@
+//! 单元测试共用的辅助工具
+
+use std::borrow::Cow;
+
+use chrono::{FixedOffset, LocalResult, NaiveDate, NaiveDateTime, Offset, TimeZone};
+use sqlx::migrate::{Migration as SqlxMigration, MigrationType, Migrator};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use tauri_plugin_sql::MigrationKind;
+
+use crate::database::get_migrations;
+
+/// 带夏令时的测试时区：标准时间UTC+1，2024-03-31至2024-10-27（均为UTC 01:00切换）为UTC+2
+///
+/// 本地02:00-03:00在3月31日不存在，02:00-03:00在10月27日出现两次。
+#[derive(Debug, Clone, Copy)]
+pub struct DstZone;
+
+impl DstZone {
+    fn standard() -> FixedOffset {
+        FixedOffset::east_opt(3600).unwrap()
+    }
+
+    fn summer() -> FixedOffset {
+        FixedOffset::east_opt(7200).unwrap()
+    }
+
+    fn offset_at_utc(utc: &NaiveDateTime) -> FixedOffset {
+        let switch = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap().and_hms_opt(1, 0, 0).unwrap();
+        if *utc >= switch(3, 31) && *utc < switch(10, 27) {
+            Self::summer()
+        } else {
+            Self::standard()
+        }
+    }
+}
+
+impl TimeZone for DstZone {
+    type Offset = FixedOffset;
+
+    fn from_offset(_offset: &FixedOffset) -> Self {
+        DstZone
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+        self.offset_from_local_datetime(&local.and_hms_opt(0, 0, 0).unwrap())
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+        resolve_local(local, [Self::summer(), Self::standard()], Self::offset_at_utc)
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+        Self::offset_at_utc(&utc.and_hms_opt(0, 0, 0).unwrap())
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+        Self::offset_at_utc(utc)
+    }
+}
+
+/// 在0点进入夏令时的测试时区（类似America/Santiago）：标准时间UTC-4，2024-09-08 04:00 UTC起为UTC-3
+///
+/// 本地00:00-01:00在9月8日不存在，当天从01:00开始。
+#[derive(Debug, Clone, Copy)]
+pub struct MidnightDstZone;
+
+impl MidnightDstZone {
+    fn standard() -> FixedOffset {
+        FixedOffset::west_opt(4 * 3600).unwrap()
+    }
+
+    fn summer() -> FixedOffset {
+        FixedOffset::west_opt(3 * 3600).unwrap()
+    }
+
+    fn offset_at_utc(utc: &NaiveDateTime) -> FixedOffset {
+        let switch = NaiveDate::from_ymd_opt(2024, 9, 8).unwrap().and_hms_opt(4, 0, 0).unwrap();
+        if *utc >= switch {
+            Self::summer()
+        } else {
+            Self::standard()
+        }
+    }
+}
+
+impl TimeZone for MidnightDstZone {
+    type Offset = FixedOffset;
+
+    fn from_offset(_offset: &FixedOffset) -> Self {
+        MidnightDstZone
+    }
+
+    fn offset_from_local_date(&self, local: &NaiveDate) -> LocalResult<FixedOffset> {
+        self.offset_from_local_datetime(&local.and_hms_opt(0, 0, 0).unwrap())
+    }
+
+    fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<FixedOffset> {
+        resolve_local(local, [Self::summer(), Self::standard()], Self::offset_at_utc)
+    }
+
+    fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+        Self::offset_at_utc(&utc.and_hms_opt(0, 0, 0).unwrap())
+    }
+
+    fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+        Self::offset_at_utc(utc)
+    }
+}
+
+/// 按候选偏移换算回UTC，换算结果与偏移一致的才是有效的本地时间（较早的时刻在前）
+fn resolve_local(
+    local: &NaiveDateTime,
+    offsets: [FixedOffset; 2],
+    offset_at_utc: fn(&NaiveDateTime) -> FixedOffset,
+) -> LocalResult<FixedOffset> {
+    let valid: Vec<FixedOffset> = offsets
+        .into_iter()
+        .filter(|offset| offset_at_utc(&(*local - offset.fix())) == *offset)
+        .collect();
+
+    match valid.as_slice() {
+        [] => LocalResult::None,
+        [offset] => LocalResult::Single(*offset),
+        [earliest, latest, ..] => LocalResult::Ambiguous(*earliest, *latest),
+    }
+}
+
+/// 生成单声道16位PCM的WAV文件内容
+pub fn wav_bytes(sample_rate: u32, samples: &[i16]) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&1u16.to_le_bytes());
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&16u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        bytes.extend_from_slice(&sample.to_le_bytes());
+    }
+    bytes
+}
+
+/// 用与插件相同的迁移定义构建sqlx迁移器（包含Down迁移）
+pub fn build_migrator() -> Migrator {
+    let migrations = get_migrations()
+        .into_iter()
+        .map(|m| {
+            let migration_type = match m.kind {
+                MigrationKind::Up => MigrationType::ReversibleUp,
+                MigrationKind::Down => MigrationType::ReversibleDown,
+            };
+            SqlxMigration::new(
+                m.version,
+                Cow::Borrowed(m.description),
+                migration_type,
+                Cow::Borrowed(m.sql),
+                false,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    Migrator {
+        migrations: Cow::Owned(migrations),
+        ..Migrator::DEFAULT
+    }
+}
+
+/// 在临时目录中创建SQLite数据库文件
+pub async fn temp_pool() -> (tempfile::TempDir, SqlitePool) {
+    let dir = tempfile::tempdir().expect("创建临时目录失败");
+    let options = SqliteConnectOptions::new()
+        .filename(dir.path().join("focus_daily.db"))
+        .create_if_missing(true);
+    let pool = SqlitePool::connect_with(options).await.expect("连接临时数据库失败");
+    (dir, pool)
+}
+
+/// 创建已执行全部迁移的临时数据库（TempDir需在使用期间保持存活）
+pub async fn migrated_pool() -> (tempfile::TempDir, SqlitePool) {
+    let (dir, pool) = temp_pool().await;
+    build_migrator().run(&pool).await.expect("执行迁移失败");
+    (dir, pool)
+}
@@ -1,14 +1,14 @@
 import Database from '@tauri-apps/plugin-sql';
+import { invoke } from '@tauri-apps/api/core';
+import { SessionType } from '../types/models';
 import type {
   FocusSession,
-  CreateFocusSession,
   UserSettings,
   UpdateUserSettings,
   AudioConfig,
   CreateAudioConfig,
   AudioType,
-  TodayStats,
-  SessionType
+  TodayStats
 } from '../types/models';
 
 // 数据库实例
@@ -27,43 +27,13 @@ async function getDatabase(): Promise<Database> {
 // ============ 专注会话相关API ============
 
 /**
- * 创建新的专注会话
+ * 通过后端定时器开始专注或长休息会话
+ *
+ * 会话记录只由后端写入和标记完成，这里读取后端保存的记录
  */
-export async function createFocusSession(input: CreateFocusSession): Promise<FocusSession> {
-  const database = await getDatabase();
-  const id = generateUUID();
-  const now = new Date().toISOString();
-  
-  await database.execute(
-    `INSERT INTO focus_sessions (id, start_time, duration_seconds, session_type, completed, created_at, updated_at) 
-     VALUES ($1, $2, $3, $4, 0, $5, $6)`,
-    [id, now, input.duration_seconds, input.session_type, now, now]
-  );
-
-  return {
-    id,
-    start_time: now,
-    end_time: undefined,
-    duration_seconds: input.duration_seconds,
-    session_type: input.session_type,
-    completed: false,
-    created_at: now,
-    updated_at: now,
-  };
-}
-
-/**
- * 完成专注会话
- */
-export async function completeFocusSession(sessionId: string): Promise<FocusSession> {
-  const database = await getDatabase();
-  const now = new Date().toISOString();
-  
-  await database.execute(
-    'UPDATE focus_sessions SET end_time = $1, completed = 1, updated_at = $2 WHERE id = $3',
-    [now, now, sessionId]
-  );
-  
+export async function startTimerSession(sessionType: SessionType.Focus | SessionType.LongBreak): Promise<FocusSession> {
+  const command = sessionType === SessionType.Focus ? 'start_focus_session' : 'start_long_break_session';
+  const sessionId = await invoke<string>(command);
   return await getFocusSessionById(sessionId);
 }
 
@@ -118,9 +88,9 @@ export async function getTodayStats(): Promise<TodayStats> {
   let totalFocusTime = 0;
   
   for (const row of result) {
-    if (row.session_type === 'Focus') {
-      focusCount = row.count;
-      totalFocusTime = row.total_seconds;
+    if (row.session_type === 'Focus' || row.session_type === 'OpenFocus') {
+      focusCount += row.count;
+      totalFocusTime += row.total_seconds;
     } else if (row.session_type === 'LongBreak' || row.session_type === 'MicroBreak') {
       breakCount += row.count;
     }
@@ -216,6 +186,16 @@ export async function updateUserSettings(input: UpdateUserSettings): Promise<Use
     values.push(input.notifications_enabled ? 1 : 0);
   }
   
+  if (input.long_break_skip_warning_threshold !== undefined) {
+    updates.push(`long_break_skip_warning_threshold = $${paramIndex++}`);
+    values.push(input.long_break_skip_warning_threshold);
+  }
+  
+  if (input.flow_mode_enabled !== undefined) {
+    updates.push(`flow_mode_enabled = $${paramIndex++}`);
+    values.push(input.flow_mode_enabled ? 1 : 0);
+  }
+  
   if (updates.length > 0) {
     updates.push(`updated_at = $${paramIndex++}`);
     values.push(now);
@@ -246,9 +226,11 @@ export async function resetUserSettingsToDefault(): Promise<UserSettings> {
        micro_break_max_interval_minutes = $7,
        micro_break_duration_seconds = $8,
        notifications_enabled = $9,
-       updated_at = $10
-     WHERE id = $11`,
-    ['System', 'zh-CN', 0, 90, 20, 3, 5, 15, 1, now, 'default_settings']
+       long_break_skip_warning_threshold = $10,
+       flow_mode_enabled = $11,
+       updated_at = $12
+     WHERE id = $13`,
+    ['System', 'zh-CN', 0, 90, 20, 3, 5, 15, 1, null, 0, now, 'default_settings']
   );
   
   return await getUserSettings();
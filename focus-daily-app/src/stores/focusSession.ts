@@ -2,13 +2,12 @@ import { defineStore } from 'pinia';
 import { ref, computed } from 'vue';
 import type { 
   FocusSession, 
-  CreateFocusSession, 
   TodayStats 
 } from '../types/models';
 import { SessionType, SessionStatus } from '../types/models';
 import { 
-  createFocusSession, 
-  completeFocusSession,
+  startTimerSession,
+  getFocusSessionById,
   getRecentFocusSessions,
   getTodayStats,
   deleteFocusSession
@@ -53,18 +52,20 @@ export const useFocusSessionStore = defineStore('focusSession', () => {
         return '长休息';
       case SessionType.MicroBreak:
         return '微休息';
+      case SessionType.OpenFocus:
+        return '正计时专注';
       default:
         return '';
     }
   });
 
   // 动作
-  async function startSession(input: CreateFocusSession) {
+  async function startSession(sessionType: SessionType.Focus | SessionType.LongBreak) {
     loading.value = true;
     error.value = null;
     
     try {
-      currentSession.value = await createFocusSession(input);
+      currentSession.value = await startTimerSession(sessionType);
       remainingSeconds.value = currentSession.value.duration_seconds;
       sessionStatus.value = SessionStatus.Running;
       startTimer();
@@ -98,7 +99,8 @@ export const useFocusSessionStore = defineStore('focusSession', () => {
     error.value = null;
     
     try {
-      const completedSession = await completeFocusSession(currentSession.value.id);
+      // 会话完成状态由后端定时器写入，这里只读取最新记录
+      const completedSession = await getFocusSessionById(currentSession.value.id);
       sessionStatus.value = SessionStatus.Completed;
       stopTimer();
       
@@ -190,26 +192,13 @@ export const useFocusSessionStore = defineStore('focusSession', () => {
     }
   }
 
-  // 快捷启动方法
-  async function startFocusSession(durationMinutes: number = 90) {
-    await startSession({
-      session_type: SessionType.Focus,
-      duration_seconds: durationMinutes * 60
-    });
+  // 快捷启动方法（时长使用后端的用户设置）
+  async function startFocusSession() {
+    await startSession(SessionType.Focus);
   }
 
-  async function startLongBreak(durationMinutes: number = 20) {
-    await startSession({
-      session_type: SessionType.LongBreak,
-      duration_seconds: durationMinutes * 60
-    });
-  }
-
-  async function startMicroBreak(durationSeconds: number = 15) {
-    await startSession({
-      session_type: SessionType.MicroBreak,
-      duration_seconds: durationSeconds
-    });
+  async function startLongBreak() {
+    await startSession(SessionType.LongBreak);
   }
 
   function clearError() {
@@ -251,7 +240,6 @@ export const useFocusSessionStore = defineStore('focusSession', () => {
     deleteSession,
     startFocusSession,
     startLongBreak,
-    startMicroBreak,
     clearError,
     cleanup
   };
@@ -2,7 +2,8 @@
 export enum SessionType {
   Focus = "Focus",
   LongBreak = "LongBreak",
-  MicroBreak = "MicroBreak"
+  MicroBreak = "MicroBreak",
+  OpenFocus = "OpenFocus"
 }
 
 // 主题枚举
@@ -33,12 +34,6 @@ export interface FocusSession {
   updated_at: string;
 }
 
-// 创建专注会话输入
-export interface CreateFocusSession {
-  session_type: SessionType;
-  duration_seconds: number;
-}
-
 // 用户设置接口
 export interface UserSettings {
   id: string;
@@ -51,6 +46,8 @@ export interface UserSettings {
   micro_break_max_interval_minutes: number;
   micro_break_duration_seconds: number;
   notifications_enabled: boolean;
+  long_break_skip_warning_threshold?: number | null;
+  flow_mode_enabled: boolean;
   created_at: string;
   updated_at: string;
 }
@@ -66,6 +63,8 @@ export interface UpdateUserSettings {
   micro_break_max_interval_minutes?: number;
   micro_break_duration_seconds?: number;
   notifications_enabled?: boolean;
+  long_break_skip_warning_threshold?: number | null;
+  flow_mode_enabled?: boolean;
 }
 
 // 音频配置接口